@@ -0,0 +1,349 @@
+// Achievements unlocked by human players, driven by the game events of each match.
+// Progress is shared by everyone playing on this computer and saved between sessions.
+
+use crate::game::ball::BALL_SPEED_MAX;
+use crate::game::events::{GameEvent, GameEventListener};
+use crate::game::physics::Player;
+use crate::game::simulation::DEFAULT_WINNING_SCORE;
+use crate::player::player_type::PlayerType;
+use crate::storage::{self, Migration, StorageError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const ACHIEVEMENTS_FILE: &str = "achievements.json";
+
+// Schema history of the achievements file (see `storage`); the first format is v0.
+const MIGRATIONS: &[Migration] = &[];
+
+const MARATHON_RALLY_HITS: u32 = 20;
+const PINBALL_WALL_BOUNCES: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Achievement {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    // Number of times the condition must be met
+    pub goal: u32,
+}
+
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first_win",
+        name: "First Victory",
+        description: "Win a match",
+        goal: 1,
+    },
+    Achievement {
+        id: "flawless",
+        name: "Flawless",
+        description: "Win 10-0",
+        goal: 1,
+    },
+    Achievement {
+        id: "marathon",
+        name: "Marathon",
+        description: "Keep a rally going for 20 hits",
+        goal: 1,
+    },
+    Achievement {
+        id: "giant_slayer",
+        name: "Giant Slayer",
+        description: "Beat AI - Hard",
+        goal: 1,
+    },
+    Achievement {
+        id: "speed_of_light",
+        name: "Speed of Light",
+        description: "Return a ball travelling at max speed",
+        goal: 1,
+    },
+    Achievement {
+        id: "pinball",
+        name: "Pinball",
+        description: "Have the ball bounce off the walls 5 times in one rally",
+        goal: 1,
+    },
+    Achievement {
+        id: "veteran",
+        name: "Veteran",
+        description: "Win 10 matches",
+        goal: 10,
+    },
+];
+
+pub fn find(id: &str) -> Option<&'static Achievement> {
+    ACHIEVEMENTS.iter().find(|achievement| achievement.id == id)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AchievementProgress {
+    pub progress: u32,
+    pub unlocked_at: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AchievementsDocument {
+    progress: BTreeMap<String, AchievementProgress>,
+}
+
+// Per-match context needed to judge events.
+#[derive(Debug, Default)]
+struct MatchContext {
+    player_types: Option<(PlayerType, PlayerType)>,
+    rally_hits: u32,
+    rally_wall_bounces: u32,
+}
+
+impl MatchContext {
+    fn player_type(&self, player: Player) -> Option<PlayerType> {
+        self.player_types.and_then(|(left, right)| match player {
+            Player::Left => Some(left),
+            Player::Right => Some(right),
+            Player::Top | Player::Bottom | Player::LeftFront | Player::RightFront => None,
+        })
+    }
+
+    fn is_human(&self, player: Player) -> bool {
+        self.player_type(player) == Some(PlayerType::Human)
+    }
+
+    fn has_human(&self) -> bool {
+        self.is_human(Player::Left) || self.is_human(Player::Right)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AchievementTracker {
+    // `None` when the tracker could not be loaded: progress then stays in memory
+    path: Option<PathBuf>,
+    progress: BTreeMap<String, AchievementProgress>,
+    current_match: MatchContext,
+    // Unlocked by events but not yet announced
+    newly_unlocked: Vec<&'static Achievement>,
+}
+
+impl AchievementTracker {
+    pub fn load(path: &Path) -> Result<Self, StorageError> {
+        let document: AchievementsDocument = storage::load_document(path, MIGRATIONS)?.unwrap_or_default();
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            progress: document.progress,
+            ..Self::default()
+        })
+    }
+
+    // A tracker that is never written to disk.
+    pub fn detached() -> Self {
+        Self::default()
+    }
+
+    pub fn save(&self) -> Result<(), StorageError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let document = AchievementsDocument {
+            progress: self.progress.clone(),
+        };
+        storage::save_document(path, MIGRATIONS, &document)
+    }
+
+    pub fn progress(&self, achievement: &Achievement) -> AchievementProgress {
+        self.progress.get(achievement.id).copied().unwrap_or_default()
+    }
+
+    pub fn is_unlocked(&self, achievement: &Achievement) -> bool {
+        self.progress(achievement).unlocked_at.is_some()
+    }
+
+    pub fn unlocked_count(&self) -> usize {
+        ACHIEVEMENTS.iter().filter(|achievement| self.is_unlocked(achievement)).count()
+    }
+
+    pub fn start_match(&mut self, left: PlayerType, right: PlayerType) {
+        self.current_match = MatchContext {
+            player_types: Some((left, right)),
+            ..MatchContext::default()
+        };
+    }
+
+    // A match that does not count towards achievements.
+    pub fn start_unranked_match(&mut self) {
+        self.current_match = MatchContext::default();
+    }
+
+    // Achievements unlocked since the last call.
+    pub fn take_unlocked(&mut self) -> Vec<&'static Achievement> {
+        std::mem::take(&mut self.newly_unlocked)
+    }
+
+    // Feed one game event; returns the achievements it unlocked.
+    pub fn handle(&mut self, event: &GameEvent, timestamp: u64) -> Vec<&'static Achievement> {
+        let mut unlocked = Vec::new();
+        if !self.current_match.has_human() {
+            return unlocked;
+        }
+
+        match *event {
+            GameEvent::BallHitRacket { player, speed, .. } => {
+                self.current_match.rally_hits += 1;
+                if self.current_match.rally_hits >= MARATHON_RALLY_HITS {
+                    self.advance("marathon", timestamp, &mut unlocked);
+                }
+                if self.current_match.is_human(player) && speed >= BALL_SPEED_MAX {
+                    self.advance("speed_of_light", timestamp, &mut unlocked);
+                }
+            }
+            GameEvent::WallBounce { .. } => {
+                self.current_match.rally_wall_bounces += 1;
+                if self.current_match.rally_wall_bounces >= PINBALL_WALL_BOUNCES {
+                    self.advance("pinball", timestamp, &mut unlocked);
+                }
+            }
+            GameEvent::Serve { .. } => {
+                self.current_match.rally_hits = 0;
+                self.current_match.rally_wall_bounces = 0;
+            }
+            GameEvent::RacketMoved { .. }
+            | GameEvent::PointScored { .. }
+            | GameEvent::BallsCollided { .. }
+            | GameEvent::BallSpawned { .. }
+            | GameEvent::PowerUpSpawned { .. }
+            | GameEvent::PowerUpCollected { .. }
+            | GameEvent::PowerUpExpired { .. }
+            | GameEvent::ObstacleHit { .. }
+            | GameEvent::BallTeleported { .. }
+            | GameEvent::BrickBroken { .. } => {}
+            GameEvent::MatchWon {
+                winner,
+                left_score,
+                right_score,
+            } => {
+                if self.current_match.is_human(winner) {
+                    let loser = if winner == Player::Left { Player::Right } else { Player::Left };
+                    let (winner_score, loser_score) = if winner == Player::Left {
+                        (left_score, right_score)
+                    } else {
+                        (right_score, left_score)
+                    };
+
+                    self.advance("first_win", timestamp, &mut unlocked);
+                    self.advance("veteran", timestamp, &mut unlocked);
+                    if loser_score == 0 && winner_score >= DEFAULT_WINNING_SCORE {
+                        self.advance("flawless", timestamp, &mut unlocked);
+                    }
+                    if self.current_match.player_type(loser) == Some(PlayerType::AIHard) {
+                        self.advance("giant_slayer", timestamp, &mut unlocked);
+                    }
+                }
+                self.current_match = MatchContext::default();
+            }
+        }
+
+        unlocked
+    }
+
+    fn advance(&mut self, id: &str, timestamp: u64, unlocked: &mut Vec<&'static Achievement>) {
+        let Some(achievement) = find(id) else {
+            return;
+        };
+        let entry = self.progress.entry(id.to_string()).or_default();
+        if entry.unlocked_at.is_some() {
+            return;
+        }
+        entry.progress += 1;
+        if entry.progress >= achievement.goal {
+            entry.unlocked_at = Some(timestamp);
+            unlocked.push(achievement);
+        }
+    }
+}
+
+impl GameEventListener for AchievementTracker {
+    fn on_event(&mut self, event: &GameEvent) {
+        let unlocked = self.handle(event, crate::career::now());
+        self.newly_unlocked.extend(unlocked);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ggez::glam::Vec2;
+
+    fn hit(player: Player, speed: f32) -> GameEvent {
+        GameEvent::BallHitRacket {
+            player,
+            position: Vec2::ZERO,
+            speed,
+            offset: 0.0,
+        }
+    }
+
+    fn serve() -> GameEvent {
+        GameEvent::Serve {
+            server: Player::Left,
+            speed: 750.0,
+        }
+    }
+
+    fn bounce() -> GameEvent {
+        GameEvent::WallBounce {
+            position: Vec2::ZERO,
+            speed: 750.0,
+        }
+    }
+
+    fn unlocked_ids(tracker: &mut AchievementTracker, events: &[GameEvent]) -> Vec<&'static str> {
+        events
+            .iter()
+            .flat_map(|event| tracker.handle(event, 1))
+            .map(|achievement| achievement.id)
+            .collect()
+    }
+
+    #[test]
+    fn shutout_against_ai_hard_unlocks_several() {
+        let mut tracker = AchievementTracker::detached();
+        tracker.start_match(PlayerType::Human, PlayerType::AIHard);
+        let events = [GameEvent::MatchWon {
+            winner: Player::Left,
+            left_score: 10,
+            right_score: 0,
+        }];
+        assert_eq!(unlocked_ids(&mut tracker, &events), ["first_win", "flawless", "giant_slayer"]);
+        assert_eq!(tracker.progress(find("veteran").unwrap()).progress, 1);
+
+        // Already unlocked achievements are not reported again
+        tracker.start_match(PlayerType::Human, PlayerType::AIHard);
+        assert!(unlocked_ids(&mut tracker, &events).is_empty());
+    }
+
+    #[test]
+    fn rally_achievements_reset_on_serves() {
+        let mut tracker = AchievementTracker::detached();
+        tracker.start_match(PlayerType::AIEasy, PlayerType::Human);
+
+        let mut events = vec![hit(Player::Left, 800.0); 19];
+        events.push(serve());
+        events.extend(vec![bounce(); 4]);
+        assert!(unlocked_ids(&mut tracker, &events).is_empty());
+
+        let events = [hit(Player::Left, BALL_SPEED_MAX), bounce(), hit(Player::Right, BALL_SPEED_MAX)];
+        assert_eq!(unlocked_ids(&mut tracker, &events), ["pinball", "speed_of_light"]);
+    }
+
+    #[test]
+    fn ai_only_matches_do_not_count() {
+        let mut tracker = AchievementTracker::detached();
+        tracker.start_match(PlayerType::AIEasy, PlayerType::AIHard);
+        let events = [GameEvent::MatchWon {
+            winner: Player::Left,
+            left_score: 10,
+            right_score: 0,
+        }];
+        assert!(unlocked_ids(&mut tracker, &events).is_empty());
+        assert_eq!(tracker.unlocked_count(), 0);
+    }
+}
@@ -0,0 +1,176 @@
+// Turns game events into sound cues: which sound to play, and how.
+//
+// Racket hits and wall bounces get higher and louder as the ball speeds up, and are panned to where the ball is.
+// Each side has its own score jingle, played from that side of the stereo field. Collecting a power-up plays a
+// high racket hit. Obstacles sound like walls, bumpers like rackets, and portals like a deep, muffled bounce.
+// Breaking a Pong-Out brick plays a sharp, high bounce.
+
+use crate::audio::manager::{RACKET_HIT, SCORE, WALL_BOUNCE};
+use crate::game::ball::{BALL_SPEED, BALL_SPEED_MAX};
+use crate::game::events::GameEvent;
+use crate::game::physics::Player;
+
+// Pitch of a hit at full speed; a serve-speed hit plays the sound as recorded
+const HIT_PITCH_MAX: f32 = 1.5;
+const BOUNCE_PITCH_MAX: f32 = 1.3;
+// Volume of the slowest hit or bounce, relative to the fastest
+const HIT_VOLUME_MIN: f32 = 0.6;
+const BOUNCE_VOLUME_MIN: f32 = 0.5;
+// Score jingles: a brighter one for the left player and a deeper one for the right player
+const SCORE_PITCH_LEFT: f32 = 1.12;
+const SCORE_PITCH_RIGHT: f32 = 0.89;
+const SCORE_PAN: f32 = 0.6;
+const POWER_UP_PITCH: f32 = 2.0;
+const PORTAL_PITCH: f32 = 0.6;
+const PORTAL_VOLUME: f32 = 0.7;
+const BRICK_PITCH: f32 = 1.7;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoundCue {
+    pub sound: &'static str,
+    // Playback speed ratio, 1 plays the sound as recorded
+    pub pitch: f32,
+    // Relative to the sound effects channel, from 0 to 1
+    pub volume: f32,
+    // From -1 (left) to 1 (right)
+    pub pan: f32,
+}
+
+// How far the ball is from its serve speed (0) to its maximum speed (1).
+pub fn intensity(speed: f32) -> f32 {
+    ((speed - BALL_SPEED) / (BALL_SPEED_MAX - BALL_SPEED)).clamp(0.0, 1.0)
+}
+
+// Stereo position of `x` in an arena `width` wide.
+pub fn pan(x: f32, width: f32) -> f32 {
+    if width <= 0.0 {
+        return 0.0;
+    }
+    (x / width * 2.0 - 1.0).clamp(-1.0, 1.0)
+}
+
+pub fn cue_for(event: &GameEvent, arena_width: f32) -> Option<SoundCue> {
+    let cue = match *event {
+        GameEvent::BallHitRacket { position, speed, .. } => {
+            let intensity = intensity(speed);
+            SoundCue {
+                sound: RACKET_HIT,
+                pitch: 1.0 + (HIT_PITCH_MAX - 1.0) * intensity,
+                volume: HIT_VOLUME_MIN + (1.0 - HIT_VOLUME_MIN) * intensity,
+                pan: pan(position.x, arena_width),
+            }
+        }
+        GameEvent::WallBounce { position, speed } | GameEvent::BallsCollided { position, speed } => {
+            let intensity = intensity(speed);
+            SoundCue {
+                sound: WALL_BOUNCE,
+                pitch: 1.0 + (BOUNCE_PITCH_MAX - 1.0) * intensity,
+                volume: BOUNCE_VOLUME_MIN + (1.0 - BOUNCE_VOLUME_MIN) * intensity,
+                pan: pan(position.x, arena_width),
+            }
+        }
+        GameEvent::PointScored { scorer, .. } => {
+            let (pitch, pan) = match scorer {
+                Player::Left | Player::LeftFront => (SCORE_PITCH_LEFT, -SCORE_PAN),
+                Player::Right | Player::RightFront => (SCORE_PITCH_RIGHT, SCORE_PAN),
+                // Players on the top and bottom walls share the jingles of their teammates, from the middle
+                Player::Top => (SCORE_PITCH_LEFT, 0.0),
+                Player::Bottom => (SCORE_PITCH_RIGHT, 0.0),
+            };
+            SoundCue {
+                sound: SCORE,
+                pitch,
+                volume: 1.0,
+                pan,
+            }
+        }
+        GameEvent::ObstacleHit { position, speed, bumper, .. } => {
+            let intensity = intensity(speed);
+            let (sound, pitch_max) = if bumper {
+                (RACKET_HIT, HIT_PITCH_MAX)
+            } else {
+                (WALL_BOUNCE, BOUNCE_PITCH_MAX)
+            };
+            SoundCue {
+                sound,
+                pitch: 1.0 + (pitch_max - 1.0) * intensity,
+                volume: BOUNCE_VOLUME_MIN + (1.0 - BOUNCE_VOLUME_MIN) * intensity,
+                pan: pan(position.x, arena_width),
+            }
+        }
+        GameEvent::BallTeleported { to, .. } => SoundCue {
+            sound: WALL_BOUNCE,
+            pitch: PORTAL_PITCH,
+            volume: PORTAL_VOLUME,
+            pan: pan(to.x, arena_width),
+        },
+        GameEvent::BrickBroken { position, speed, .. } => SoundCue {
+            sound: WALL_BOUNCE,
+            pitch: BRICK_PITCH,
+            volume: BOUNCE_VOLUME_MIN + (1.0 - BOUNCE_VOLUME_MIN) * intensity(speed),
+            pan: pan(position.x, arena_width),
+        },
+        GameEvent::PowerUpCollected { position, .. } => SoundCue {
+            sound: RACKET_HIT,
+            pitch: POWER_UP_PITCH,
+            volume: 1.0,
+            pan: pan(position.x, arena_width),
+        },
+        GameEvent::Serve { .. }
+        | GameEvent::BallSpawned { .. }
+        | GameEvent::PowerUpSpawned { .. }
+        | GameEvent::PowerUpExpired { .. }
+        | GameEvent::RacketMoved { .. }
+        | GameEvent::MatchWon { .. } => return None,
+    };
+    Some(cue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ggez::glam::Vec2;
+
+    fn hit(x: f32, speed: f32) -> GameEvent {
+        GameEvent::BallHitRacket {
+            player: Player::Left,
+            position: Vec2::new(x, 300.0),
+            speed,
+            offset: 0.0,
+        }
+    }
+
+    #[test]
+    fn faster_hits_are_higher_and_louder() {
+        let slow = cue_for(&hit(640.0, BALL_SPEED), 1280.0).unwrap();
+        let fast = cue_for(&hit(640.0, BALL_SPEED_MAX), 1280.0).unwrap();
+        assert_eq!(slow.pitch, 1.0);
+        assert_eq!(slow.volume, HIT_VOLUME_MIN);
+        assert_eq!(fast.pitch, HIT_PITCH_MAX);
+        assert_eq!(fast.volume, 1.0);
+    }
+
+    #[test]
+    fn sounds_follow_the_ball_across_the_stereo_field() {
+        assert_eq!(cue_for(&hit(0.0, BALL_SPEED), 1280.0).unwrap().pan, -1.0);
+        assert_eq!(cue_for(&hit(640.0, BALL_SPEED), 1280.0).unwrap().pan, 0.0);
+        assert_eq!(cue_for(&hit(1280.0, BALL_SPEED), 1280.0).unwrap().pan, 1.0);
+        assert_eq!(pan(2000.0, 1280.0), 1.0);
+        assert_eq!(pan(100.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn each_side_has_its_own_score_jingle() {
+        let scored = |scorer| GameEvent::PointScored {
+            scorer,
+            position: Vec2::ZERO,
+            left_score: 1,
+            right_score: 0,
+            rally_over: true,
+        };
+        let left = cue_for(&scored(Player::Left), 1280.0).unwrap();
+        let right = cue_for(&scored(Player::Right), 1280.0).unwrap();
+        assert_ne!(left.pitch, right.pitch);
+        assert!(left.pan < 0.0 && right.pan > 0.0);
+    }
+}
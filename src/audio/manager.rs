@@ -0,0 +1,214 @@
+use crate::audio::cue::{self, SoundCue};
+use crate::audio::mixer::{Channel, Mixer, voices_to_free};
+use crate::audio::music::{Music, MusicScene};
+use crate::audio::{Asset, SoundPack, synth};
+use crate::game::events::{GameEvent, GameEventListener};
+use ggez::audio::{SoundData, SoundSource, SpatialSource};
+use ggez::{Context, GameResult};
+use std::collections::{HashMap, VecDeque};
+
+pub const RACKET_HIT: &str = "racket_hit.wav";
+pub const WALL_BOUNCE: &str = "wall_bounce.wav";
+pub const SCORE: &str = "score.wav";
+
+// How far from the centre a fully panned sound is placed, between the listener's ears
+const PAN_SPREAD: f32 = 0.8;
+
+// Every sound effect the game plays, loaded at startup.
+pub const SOUND_EFFECTS: [&str; 3] = [RACKET_HIT, WALL_BOUNCE, SCORE];
+
+// Read and validate every sound effect from the embedded assets.
+// Returns the sounds that can be played and a description of each one that cannot.
+pub fn load_sounds() -> (HashMap<&'static str, SoundData>, Vec<String>) {
+    let mut sounds = HashMap::new();
+    let mut problems = Vec::new();
+    for name in SOUND_EFFECTS {
+        match Asset::get(name) {
+            Some(file) => {
+                let data = SoundData::from_bytes(file.data.as_ref());
+                if data.can_play() {
+                    sounds.insert(name, data);
+                } else {
+                    problems.push(format!("Sound effect {} could not be decoded", name));
+                }
+            }
+            None => problems.push(format!("Sound effect {} is missing", name)),
+        }
+    }
+    (sounds, problems)
+}
+
+// Owns the sound effects, the voices playing them and the music.
+// Reacts to game events like any listener; the sounds are started by `play_pending`, which needs the context.
+pub struct AudioManager {
+    mixer: Mixer,
+    pack: SoundPack,
+    sounds: HashMap<&'static str, SoundData>,
+    // Sounds replaced by the asset pack in use
+    overrides: HashMap<&'static str, SoundData>,
+    problems: Vec<String>,
+    // Playing voices with their cue volume, oldest first
+    voices: VecDeque<(SpatialSource, f32)>,
+    pending: Vec<SoundCue>,
+    arena_width: f32,
+    playback_failed: bool,
+    music: Music,
+}
+
+impl AudioManager {
+    pub fn new(mixer: Mixer, pack: SoundPack) -> Self {
+        let (sounds, problems) = load_sounds();
+        Self {
+            mixer,
+            pack,
+            sounds,
+            overrides: HashMap::new(),
+            problems,
+            voices: VecDeque::new(),
+            pending: Vec::new(),
+            arena_width: 0.0,
+            playback_failed: false,
+            music: Music::new(),
+        }
+    }
+
+    // Sound effects that failed to load at startup.
+    pub fn asset_problems(&self) -> &[String] {
+        &self.problems
+    }
+
+    pub fn mixer(&self) -> &Mixer {
+        &self.mixer
+    }
+
+    pub fn sound_pack(&self) -> SoundPack {
+        self.pack
+    }
+
+    pub fn set_sound_pack(&mut self, pack: SoundPack) {
+        self.pack = pack;
+    }
+
+    // Play these sounds instead of the embedded samples (the Synth pack ignores them).
+    pub fn set_sound_overrides(&mut self, overrides: HashMap<&'static str, SoundData>) {
+        self.overrides = overrides;
+    }
+
+    pub fn toggle_mute(&mut self) -> bool {
+        self.mixer.set_muted(!self.mixer.is_muted());
+        self.apply_volume();
+        self.mixer.is_muted()
+    }
+
+    pub fn adjust_volume(&mut self, channel: Channel, delta: f32) -> f32 {
+        self.mixer.adjust_level(channel, delta);
+        self.apply_volume();
+        self.mixer.level(channel)
+    }
+
+    // Width of the arena the events' positions are in, used for panning.
+    pub fn set_arena_width(&mut self, width: f32) {
+        self.arena_width = width;
+    }
+
+    pub fn set_music_scene(&mut self, scene: MusicScene) {
+        self.music.set_scene(scene);
+    }
+
+    pub fn start_match(&mut self) {
+        self.music.start_match();
+    }
+
+    pub fn update_music(&mut self, context: &mut Context, delta_time: f32) {
+        self.music.update(context, delta_time, self.mixer.volume(Channel::Music));
+    }
+
+    pub fn play(&mut self, context: &mut Context, cue: SoundCue) -> GameResult {
+        // Synthesized sounds are rendered at the cue's pitch; samples are sped up instead
+        let (data, pitch) = match self.pack {
+            SoundPack::Samples => match self.overrides.get(cue.sound).or_else(|| self.sounds.get(cue.sound)) {
+                Some(data) => (data.clone(), cue.pitch),
+                // Missing sounds were reported at startup
+                None => return Ok(()),
+            },
+            SoundPack::Synth => (SoundData::from(synth::synthesize(&cue)), 1.0),
+        };
+
+        self.voices.retain(|(voice, _)| !voice.stopped());
+        for _ in 0..voices_to_free(self.voices.len()) {
+            self.voices.pop_front();
+        }
+
+        let mut voice = SpatialSource::from_data(context, data)?;
+        // The listener's ears are at x = -1 and x = 1; keep the emitter between them
+        voice.set_position([cue.pan * PAN_SPREAD, 0.0, 0.0]);
+        voice.set_pitch(pitch);
+        voice.set_volume(self.mixer.volume(Channel::Sfx) * cue.volume);
+        voice.play(context)?;
+        self.voices.push_back((voice, cue.volume));
+        Ok(())
+    }
+
+    pub fn play_pending(&mut self, context: &mut Context) {
+        let pending = std::mem::take(&mut self.pending);
+        if self.playback_failed || self.mixer.is_muted() {
+            return;
+        }
+        for cue in pending {
+            if let Err(error) = self.play(context, cue) {
+                // Report once: the audio device is unlikely to recover during the session
+                eprintln!("Could not play sound effects: {}. Sound effects are disabled.", error);
+                self.playback_failed = true;
+                self.voices.clear();
+                return;
+            }
+        }
+    }
+
+    fn apply_volume(&mut self) {
+        let volume = self.mixer.volume(Channel::Sfx);
+        for (voice, cue_volume) in self.voices.iter_mut() {
+            voice.set_volume(volume * *cue_volume);
+        }
+    }
+}
+
+impl GameEventListener for AudioManager {
+    fn on_event(&mut self, event: &GameEvent) {
+        if let Some(cue) = cue::cue_for(event, self.arena_width) {
+            self.pending.push(cue);
+        }
+        self.music.on_event(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::physics::Player;
+    use ggez::glam::Vec2;
+
+    #[test]
+    fn embedded_sound_effects_load() {
+        let (sounds, problems) = load_sounds();
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(sounds.len(), SOUND_EFFECTS.len());
+    }
+
+    #[test]
+    fn events_queue_their_sound() {
+        let mut audio = AudioManager::new(Mixer::new(), SoundPack::Samples);
+        audio.on_event(&GameEvent::BallHitRacket {
+            player: Player::Left,
+            position: Vec2::ZERO,
+            speed: 800.0,
+            offset: 0.0,
+        });
+        audio.on_event(&GameEvent::RacketMoved {
+            player: Player::Left,
+            distance: 3.0,
+        });
+        assert_eq!(audio.pending.len(), 1);
+        assert_eq!(audio.pending[0].sound, RACKET_HIT);
+    }
+}
@@ -0,0 +1,134 @@
+// Volume channels. The effective volume of a sound is its channel level scaled by the master level, or silence when muted.
+
+// Sounds playing at once; starting another one stops the oldest.
+pub const MAX_VOICES: usize = 8;
+// Step used by the volume keys.
+pub const VOLUME_STEP: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Master,
+    Sfx,
+    Music,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mixer {
+    master: f32,
+    sfx: f32,
+    music: f32,
+    muted: bool,
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            sfx: 1.0,
+            music: 0.6,
+            muted: false,
+        }
+    }
+}
+
+impl Mixer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Level of a single channel, from 0 to 1.
+    pub fn level(&self, channel: Channel) -> f32 {
+        match channel {
+            Channel::Master => self.master,
+            Channel::Sfx => self.sfx,
+            Channel::Music => self.music,
+        }
+    }
+
+    pub fn set_level(&mut self, channel: Channel, level: f32) {
+        let level = level.clamp(0.0, 1.0);
+        match channel {
+            Channel::Master => self.master = level,
+            Channel::Sfx => self.sfx = level,
+            Channel::Music => self.music = level,
+        }
+    }
+
+    pub fn adjust_level(&mut self, channel: Channel, delta: f32) {
+        // Round to the step so repeated presses land on exact percentages
+        let level = ((self.level(channel) + delta) / VOLUME_STEP).round() * VOLUME_STEP;
+        self.set_level(channel, level);
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    // Volume a sound on `channel` should be played at.
+    pub fn volume(&self, channel: Channel) -> f32 {
+        if self.muted {
+            return 0.0;
+        }
+        match channel {
+            Channel::Master => self.master,
+            Channel::Sfx | Channel::Music => self.master * self.level(channel),
+        }
+    }
+}
+
+// Number of playing voices to stop before starting a new one.
+pub fn voices_to_free(playing: usize) -> usize {
+    (playing + 1).saturating_sub(MAX_VOICES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_are_scaled_by_master() {
+        let mut mixer = Mixer::new();
+        mixer.set_level(Channel::Master, 0.5);
+        mixer.set_level(Channel::Sfx, 0.8);
+        assert_eq!(mixer.volume(Channel::Sfx), 0.4);
+        assert_eq!(mixer.volume(Channel::Master), 0.5);
+
+        mixer.set_level(Channel::Music, 3.0);
+        assert_eq!(mixer.level(Channel::Music), 1.0);
+    }
+
+    #[test]
+    fn mute_silences_every_channel_and_keeps_levels() {
+        let mut mixer = Mixer::new();
+        mixer.set_muted(true);
+        assert_eq!(mixer.volume(Channel::Sfx), 0.0);
+        assert_eq!(mixer.volume(Channel::Music), 0.0);
+        mixer.set_muted(false);
+        assert_eq!(mixer.volume(Channel::Sfx), 1.0);
+    }
+
+    #[test]
+    fn volume_steps_stay_in_range() {
+        let mut mixer = Mixer::new();
+        for _ in 0..15 {
+            mixer.adjust_level(Channel::Master, -VOLUME_STEP);
+        }
+        assert_eq!(mixer.level(Channel::Master), 0.0);
+        for _ in 0..3 {
+            mixer.adjust_level(Channel::Master, VOLUME_STEP);
+        }
+        assert!((mixer.level(Channel::Master) - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn oldest_voices_are_freed_past_the_limit() {
+        assert_eq!(voices_to_free(0), 0);
+        assert_eq!(voices_to_free(MAX_VOICES - 1), 0);
+        assert_eq!(voices_to_free(MAX_VOICES), 1);
+        assert_eq!(voices_to_free(MAX_VOICES + 2), 3);
+    }
+}
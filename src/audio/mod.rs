@@ -1,18 +1,47 @@
-use ggez::audio::SoundSource;
+pub mod cue;
+pub mod manager;
+pub mod mixer;
+pub mod music;
+pub mod synth;
+
 use rust_embed::RustEmbed;
+use serde::{Deserialize, Serialize};
 
 #[derive(RustEmbed)]
 #[folder = "assets/sfx"]
 pub struct Asset;
 
-pub fn play_embedded_sound(ctx: &mut ggez::Context, name: &str) -> ggez::GameResult<()> {
-    if let Some(data) = Asset::get(name) {
-        let bytes = data.data.as_ref();
-        let sound_data = ggez::audio::SoundData::from_bytes(bytes);
-        let mut src = ggez::audio::Source::from_data(ctx, sound_data)?;
-        src.play_detached(ctx)?;
-        Ok(())
-    } else {
-        Err(ggez::GameError::ResourceLoadError(format!("Embedded SFX not found: {}", name)))
+// Where sound effects come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SoundPack {
+    // The embedded WAV samples
+    #[default]
+    Samples,
+    // Beeps generated by the synthesizer
+    Synth,
+}
+
+impl SoundPack {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SoundPack::Samples => "Samples",
+            SoundPack::Synth => "Synth",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "samples" => Some(SoundPack::Samples),
+            "synth" => Some(SoundPack::Synth),
+            _ => None,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            SoundPack::Samples => SoundPack::Synth,
+            SoundPack::Synth => SoundPack::Samples,
+        }
     }
 }
@@ -0,0 +1,405 @@
+// Background music.
+//
+// Every track loops continuously from startup; scenes only change their volumes, so switching between them is a
+// crossfade and the match layers always stay in time with each other. Tracks come from `assets/music` when a file is
+// embedded there, and are otherwise composed by the synthesizer.
+
+use crate::audio::synth::{self, Envelope, Note, Waveform};
+use crate::game::events::{GameEvent, GameEventListener};
+use ggez::audio::{SoundData, SoundSource, Source};
+use ggez::{Context, GameResult};
+use rust_embed::RustEmbed;
+
+#[derive(RustEmbed)]
+#[folder = "assets/music"]
+struct MusicAsset;
+
+const BEAT: f32 = 0.5; // 120 BPM
+const BARS: usize = 4;
+const BEATS_PER_BAR: usize = 4;
+// Seconds for a track to fade fully in or out
+const CROSSFADE_TIME: f32 = 1.5;
+// Level of the match music while paused
+const PAUSED_LEVEL: f32 = 0.35;
+// Rally hits and score gap that add one intensity level each
+const RALLY_PER_LEVEL: u32 = 6;
+const GAP_PER_LEVEL: u8 = 3;
+pub const MAX_INTENSITY: usize = 2;
+
+// Chord progression, one chord per bar: Am, F, C, G
+const BASS: [f32; BARS] = [110.0, 87.31, 130.81, 98.0];
+const CHORDS: [[f32; 3]; BARS] = [
+    [440.0, 523.25, 659.25],
+    [349.23, 440.0, 523.25],
+    [392.0, 523.25, 659.25],
+    [392.0, 493.88, 587.33],
+];
+const ARPEGGIO: [usize; 4] = [0, 1, 2, 1];
+
+const PLUCK: Envelope = Envelope {
+    attack: 0.005,
+    decay: 0.1,
+    sustain: 0.5,
+    release: 0.05,
+};
+const PAD: Envelope = Envelope {
+    attack: 0.05,
+    decay: 0.2,
+    sustain: 0.6,
+    release: 0.2,
+};
+const KICK: Envelope = Envelope {
+    attack: 0.001,
+    decay: 0.1,
+    sustain: 0.2,
+    release: 0.04,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MusicTrack {
+    Menu,
+    MatchBase,
+    MatchDrums,
+    MatchLead,
+    GameOver,
+}
+
+impl MusicTrack {
+    pub const ALL: [MusicTrack; 5] = [
+        MusicTrack::Menu,
+        MusicTrack::MatchBase,
+        MusicTrack::MatchDrums,
+        MusicTrack::MatchLead,
+        MusicTrack::GameOver,
+    ];
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            MusicTrack::Menu => "menu.wav",
+            MusicTrack::MatchBase => "match_base.wav",
+            MusicTrack::MatchDrums => "match_drums.wav",
+            MusicTrack::MatchLead => "match_lead.wav",
+            MusicTrack::GameOver => "game_over.wav",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|track| track == self).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MusicScene {
+    Menu,
+    Match,
+    Paused,
+    GameOver,
+}
+
+// Number of layers added on top of the match base track.
+pub fn intensity(rally_hits: u32, score_gap: u8) -> usize {
+    let rally_level = (rally_hits / RALLY_PER_LEVEL) as usize;
+    let gap_level = (score_gap / GAP_PER_LEVEL) as usize;
+    rally_level.max(gap_level).min(MAX_INTENSITY)
+}
+
+fn note(waveform: Waveform, frequency: f32, duration: f32, volume: f32, envelope: Envelope) -> Note {
+    Note {
+        waveform,
+        frequency,
+        end_frequency: frequency,
+        duration,
+        volume,
+        envelope,
+    }
+}
+
+// The built-in composition of a track, one loop long.
+pub fn compose(track: MusicTrack) -> Vec<Note> {
+    let mut notes = Vec::new();
+    for bar in 0..BARS {
+        let chord = CHORDS[bar];
+        match track {
+            MusicTrack::Menu => {
+                for beat in 0..BEATS_PER_BAR {
+                    notes.push(note(Waveform::Sine, chord[ARPEGGIO[beat]] / 2.0, BEAT, 0.3, PAD));
+                }
+            }
+            MusicTrack::MatchBase => {
+                for eighth in 0..BEATS_PER_BAR * 2 {
+                    let octave = if eighth % 2 == 0 { 1.0 } else { 2.0 };
+                    notes.push(note(Waveform::Square, BASS[bar] * octave, BEAT / 2.0, 0.2, PLUCK));
+                }
+            }
+            MusicTrack::MatchDrums => {
+                for _ in 0..BEATS_PER_BAR {
+                    let kick = 0.15;
+                    notes.push(Note {
+                        end_frequency: 45.0,
+                        ..note(Waveform::Sine, 150.0, kick, 0.7, KICK)
+                    });
+                    notes.push(Note::rest(BEAT - kick));
+                }
+            }
+            MusicTrack::MatchLead => {
+                for sixteenth in 0..BEATS_PER_BAR * 4 {
+                    let frequency = chord[ARPEGGIO[sixteenth % ARPEGGIO.len()]];
+                    notes.push(note(Waveform::Square, frequency, BEAT / 4.0, 0.08, PLUCK));
+                }
+            }
+            MusicTrack::GameOver => {
+                for tone in [2, 0] {
+                    notes.push(note(Waveform::Sine, chord[tone] / 2.0, BEAT * 2.0, 0.3, PAD));
+                }
+            }
+        }
+    }
+    notes
+}
+
+// Samples in one loop; every built-in track is exactly this long so the layers stay aligned.
+pub fn loop_length() -> usize {
+    (BEAT * (BARS * BEATS_PER_BAR) as f32 * synth::SAMPLE_RATE as f32) as usize
+}
+
+pub fn render_track(track: MusicTrack) -> Vec<f32> {
+    let mut samples = synth::render(&compose(track));
+    samples.resize(loop_length(), 0.0);
+    samples
+}
+
+// Volume of each track, fading towards what the current scene and intensity call for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MusicMix {
+    scene: MusicScene,
+    intensity: usize,
+    gains: [f32; MusicTrack::ALL.len()],
+}
+
+impl MusicMix {
+    pub fn new(scene: MusicScene) -> Self {
+        let mut mix = Self {
+            scene,
+            intensity: 0,
+            gains: [0.0; MusicTrack::ALL.len()],
+        };
+        // Start at the scene's levels instead of fading in from silence
+        for track in MusicTrack::ALL {
+            mix.gains[track.index()] = mix.target(track);
+        }
+        mix
+    }
+
+    pub fn set_scene(&mut self, scene: MusicScene) {
+        self.scene = scene;
+    }
+
+    pub fn set_intensity(&mut self, intensity: usize) {
+        self.intensity = intensity.min(MAX_INTENSITY);
+    }
+
+    // Volume `track` is fading towards.
+    pub fn target(&self, track: MusicTrack) -> f32 {
+        let layer = match track {
+            MusicTrack::Menu => return if self.scene == MusicScene::Menu { 1.0 } else { 0.0 },
+            MusicTrack::GameOver => return if self.scene == MusicScene::GameOver { 1.0 } else { 0.0 },
+            MusicTrack::MatchBase => 0,
+            MusicTrack::MatchDrums => 1,
+            MusicTrack::MatchLead => 2,
+        };
+        if layer > self.intensity {
+            return 0.0;
+        }
+        match self.scene {
+            MusicScene::Match => 1.0,
+            MusicScene::Paused => PAUSED_LEVEL,
+            MusicScene::Menu | MusicScene::GameOver => 0.0,
+        }
+    }
+
+    pub fn gain(&self, track: MusicTrack) -> f32 {
+        self.gains[track.index()]
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        let step = delta_time / CROSSFADE_TIME;
+        for track in MusicTrack::ALL {
+            let target = self.target(track);
+            let gain = &mut self.gains[track.index()];
+            *gain = if *gain < target {
+                (*gain + step).min(target)
+            } else {
+                (*gain - step).max(target)
+            };
+        }
+    }
+}
+
+// Plays the music tracks and follows the match to pick the intensity.
+pub struct Music {
+    mix: MusicMix,
+    sources: Vec<(MusicTrack, Source)>,
+    started: bool,
+    failed: bool,
+    rally_hits: u32,
+    score_gap: u8,
+}
+
+impl Music {
+    pub fn new() -> Self {
+        Self {
+            mix: MusicMix::new(MusicScene::Menu),
+            sources: Vec::new(),
+            started: false,
+            failed: false,
+            rally_hits: 0,
+            score_gap: 0,
+        }
+    }
+
+    pub fn set_scene(&mut self, scene: MusicScene) {
+        self.mix.set_scene(scene);
+    }
+
+    pub fn start_match(&mut self) {
+        self.rally_hits = 0;
+        self.score_gap = 0;
+        self.mix.set_intensity(0);
+    }
+
+    // Fade the tracks and apply `volume`, the music channel's volume. Tracks are loaded on the first call.
+    pub fn update(&mut self, context: &mut Context, delta_time: f32, volume: f32) {
+        if self.failed {
+            return;
+        }
+        if !self.started {
+            self.started = true;
+            if let Err(error) = self.start(context) {
+                eprintln!("Could not play music: {}. Music is disabled.", error);
+                self.failed = true;
+                self.sources.clear();
+                return;
+            }
+        }
+
+        self.mix.update(delta_time);
+        for (track, source) in self.sources.iter_mut() {
+            source.set_volume(self.mix.gain(*track) * volume);
+        }
+    }
+
+    fn start(&mut self, context: &mut Context) -> GameResult {
+        for track in MusicTrack::ALL {
+            let mut source = Source::from_data(context, load_track(track))?;
+            source.set_repeat(true);
+            source.set_volume(0.0);
+            self.sources.push((track, source));
+        }
+        // Start every track in the same frame so the match layers line up
+        for (_, source) in self.sources.iter_mut() {
+            source.play(context)?;
+        }
+        Ok(())
+    }
+}
+
+// An embedded track replacing the built-in one, or the synthesized track.
+fn load_track(track: MusicTrack) -> SoundData {
+    if let Some(file) = MusicAsset::get(track.file_name()) {
+        let data = SoundData::from_bytes(file.data.as_ref());
+        if data.can_play() {
+            return data;
+        }
+        eprintln!("Music track {} could not be decoded, using the built-in track", track.file_name());
+    }
+    SoundData::from(synth::encode_wav(&render_track(track)))
+}
+
+impl GameEventListener for Music {
+    fn on_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::Serve { .. } => self.rally_hits = 0,
+            GameEvent::BallHitRacket { .. } => self.rally_hits += 1,
+            GameEvent::PointScored { left_score, right_score, .. } => self.score_gap = left_score.abs_diff(right_score),
+            GameEvent::WallBounce { .. }
+            | GameEvent::BallsCollided { .. }
+            | GameEvent::BallSpawned { .. }
+            | GameEvent::PowerUpSpawned { .. }
+            | GameEvent::PowerUpCollected { .. }
+            | GameEvent::PowerUpExpired { .. }
+            | GameEvent::ObstacleHit { .. }
+            | GameEvent::BallTeleported { .. }
+            | GameEvent::BrickBroken { .. }
+            | GameEvent::RacketMoved { .. }
+            | GameEvent::MatchWon { .. } => return,
+        }
+        self.mix.set_intensity(intensity(self.rally_hits, self.score_gap));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::physics::Player;
+    use ggez::glam::Vec2;
+
+    #[test]
+    fn tracks_fill_exactly_one_loop() {
+        for track in MusicTrack::ALL {
+            let length: f32 = compose(track).iter().map(|note| note.duration).sum();
+            assert!((length - loop_length() as f32 / synth::SAMPLE_RATE as f32).abs() < 0.01, "{:?}", track);
+            assert_eq!(render_track(track).len(), loop_length());
+        }
+    }
+
+    #[test]
+    fn intensity_grows_with_rallies_and_score_gap() {
+        assert_eq!(intensity(0, 0), 0);
+        assert_eq!(intensity(RALLY_PER_LEVEL, 0), 1);
+        assert_eq!(intensity(0, GAP_PER_LEVEL * 2), 2);
+        assert_eq!(intensity(100, 10), MAX_INTENSITY);
+    }
+
+    #[test]
+    fn scenes_crossfade() {
+        let mut mix = MusicMix::new(MusicScene::Menu);
+        assert_eq!(mix.gain(MusicTrack::Menu), 1.0);
+        assert_eq!(mix.gain(MusicTrack::MatchBase), 0.0);
+
+        mix.set_scene(MusicScene::Match);
+        mix.update(CROSSFADE_TIME / 2.0);
+        assert_eq!(mix.gain(MusicTrack::Menu), 0.5);
+        assert_eq!(mix.gain(MusicTrack::MatchBase), 0.5);
+        mix.update(CROSSFADE_TIME);
+        assert_eq!(mix.gain(MusicTrack::Menu), 0.0);
+        assert_eq!(mix.gain(MusicTrack::MatchBase), 1.0);
+        assert_eq!(mix.gain(MusicTrack::MatchDrums), 0.0);
+
+        mix.set_scene(MusicScene::Paused);
+        mix.update(CROSSFADE_TIME);
+        assert_eq!(mix.gain(MusicTrack::MatchBase), PAUSED_LEVEL);
+    }
+
+    #[test]
+    fn long_rallies_add_layers() {
+        let mut music = Music::new();
+        music.set_scene(MusicScene::Match);
+        let hit = GameEvent::BallHitRacket {
+            player: Player::Left,
+            position: Vec2::ZERO,
+            speed: 750.0,
+            offset: 0.0,
+        };
+        for _ in 0..RALLY_PER_LEVEL {
+            music.on_event(&hit);
+        }
+        assert_eq!(music.mix.target(MusicTrack::MatchDrums), 1.0);
+        assert_eq!(music.mix.target(MusicTrack::MatchLead), 0.0);
+
+        music.on_event(&GameEvent::Serve {
+            server: Player::Right,
+            speed: 750.0,
+        });
+        assert_eq!(music.mix.target(MusicTrack::MatchDrums), 0.0);
+    }
+}
@@ -0,0 +1,251 @@
+// Procedural sound effects: retro beeps built from square and sine oscillators shaped by an envelope.
+//
+// Sounds are rendered to mono sample buffers and wrapped in a WAV header, so they play like any embedded sample.
+
+use crate::audio::cue::SoundCue;
+use crate::audio::manager::{RACKET_HIT, SCORE, WALL_BOUNCE};
+
+pub const SAMPLE_RATE: u32 = 44_100;
+
+// Peak level of a sound effect note; square waves are loud at full scale
+const NOTE_VOLUME: f32 = 0.4;
+// Score jingle: an arpeggiated C major chord
+const JINGLE: [f32; 3] = [523.25, 659.25, 783.99];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    Square,
+    Sine,
+}
+
+impl Waveform {
+    // Value of the wave at `phase`, counted in periods.
+    fn sample(self, phase: f32) -> f32 {
+        match self {
+            Waveform::Square => {
+                if phase.fract() < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Sine => (phase * std::f32::consts::TAU).sin(),
+        }
+    }
+}
+
+// Attack, decay and release times are in seconds; `sustain` is the level held between decay and release.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Envelope {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+impl Envelope {
+    // Level at `time` seconds into a note lasting `duration` seconds.
+    pub fn level(&self, time: f32, duration: f32) -> f32 {
+        let level = if time < self.attack {
+            time / self.attack
+        } else if time < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (time - self.attack) / self.decay
+        } else {
+            self.sustain
+        };
+        let remaining = duration - time;
+        if remaining < self.release {
+            level * (remaining / self.release).max(0.0)
+        } else {
+            level
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Note {
+    pub waveform: Waveform,
+    pub frequency: f32,
+    // Frequency reached at the end of the note, for pitch slides
+    pub end_frequency: f32,
+    pub duration: f32,
+    // Peak level, from 0 to 1
+    pub volume: f32,
+    pub envelope: Envelope,
+}
+
+impl Note {
+    // Silence lasting `duration` seconds.
+    pub fn rest(duration: f32) -> Self {
+        Note {
+            waveform: Waveform::Sine,
+            frequency: 0.0,
+            end_frequency: 0.0,
+            duration,
+            volume: 0.0,
+            envelope: Envelope {
+                attack: 0.0,
+                decay: 0.0,
+                sustain: 0.0,
+                release: 0.0,
+            },
+        }
+    }
+}
+
+const BLIP: Envelope = Envelope {
+    attack: 0.002,
+    decay: 0.03,
+    sustain: 0.6,
+    release: 0.03,
+};
+
+const CHIME: Envelope = Envelope {
+    attack: 0.005,
+    decay: 0.05,
+    sustain: 0.7,
+    release: 0.04,
+};
+
+// Notes for a cue, played one after the other. The cue's pitch scales every frequency.
+pub fn notes_for(cue: &SoundCue) -> Vec<Note> {
+    let blip = |frequency: f32, duration: f32| Note {
+        waveform: Waveform::Square,
+        frequency: frequency * cue.pitch,
+        end_frequency: frequency * cue.pitch * 0.9,
+        duration,
+        volume: NOTE_VOLUME,
+        envelope: BLIP,
+    };
+    match cue.sound {
+        RACKET_HIT => vec![blip(440.0, 0.08)],
+        WALL_BOUNCE => vec![blip(220.0, 0.06)],
+        SCORE => JINGLE
+            .iter()
+            .map(|&frequency| Note {
+                waveform: Waveform::Sine,
+                frequency: frequency * cue.pitch,
+                end_frequency: frequency * cue.pitch,
+                duration: 0.09,
+                volume: NOTE_VOLUME,
+                envelope: CHIME,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub fn render(notes: &[Note]) -> Vec<f32> {
+    let mut samples = Vec::new();
+    for note in notes {
+        let count = (note.duration * SAMPLE_RATE as f32) as usize;
+        let mut phase = 0.0;
+        for index in 0..count {
+            let time = index as f32 / SAMPLE_RATE as f32;
+            let progress = time / note.duration;
+            let frequency = note.frequency + (note.end_frequency - note.frequency) * progress;
+            samples.push(note.waveform.sample(phase) * note.envelope.level(time, note.duration) * note.volume);
+            phase += frequency / SAMPLE_RATE as f32;
+        }
+    }
+    samples
+}
+
+// Wrap samples in a 16-bit mono PCM WAV file.
+pub fn encode_wav(samples: &[f32]) -> Vec<u8> {
+    let data_size = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes()); // format chunk size
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        wav.extend_from_slice(&value.to_le_bytes());
+    }
+    wav
+}
+
+// A cue rendered as a playable WAV file.
+pub fn synthesize(cue: &SoundCue) -> Vec<u8> {
+    encode_wav(&render(&notes_for(cue)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ggez::audio::SoundData;
+
+    fn cue(sound: &'static str, pitch: f32) -> SoundCue {
+        SoundCue {
+            sound,
+            pitch,
+            volume: 1.0,
+            pan: 0.0,
+        }
+    }
+
+    #[test]
+    fn envelope_rises_holds_and_fades() {
+        assert_eq!(BLIP.level(0.0, 0.1), 0.0);
+        assert_eq!(BLIP.level(0.002, 0.1), 1.0);
+        assert_eq!(BLIP.level(0.05, 0.1), 0.6);
+        assert!(BLIP.level(0.09, 0.1) < 0.6);
+        assert_eq!(BLIP.level(0.1, 0.1), 0.0);
+    }
+
+    #[test]
+    fn square_wave_alternates_between_full_levels() {
+        let note = Note {
+            waveform: Waveform::Square,
+            frequency: 441.0,
+            end_frequency: 441.0,
+            duration: 0.05,
+            volume: 0.5,
+            envelope: Envelope {
+                attack: 0.0,
+                decay: 0.0,
+                sustain: 1.0,
+                release: 0.0,
+            },
+        };
+        let samples = render(&[note]);
+        assert_eq!(samples.len(), (0.05 * SAMPLE_RATE as f32) as usize);
+        // 441 Hz at 44.1 kHz: 50 samples high, then 50 samples low (the edges may round either way)
+        assert!(samples[1..49].iter().all(|&sample| sample == 0.5));
+        assert!(samples[51..99].iter().all(|&sample| sample == -0.5));
+    }
+
+    #[test]
+    fn rests_are_silent() {
+        let samples = render(&[Note::rest(0.01)]);
+        assert_eq!(samples.len(), 441);
+        assert!(samples.iter().all(|&sample| sample == 0.0));
+    }
+
+    #[test]
+    fn pitch_shortens_the_wave_period() {
+        let crossings = |samples: &[f32]| samples.windows(2).filter(|pair| pair[0] > 0.0 && pair[1] <= 0.0).count();
+        let low = render(&notes_for(&cue(RACKET_HIT, 1.0)));
+        let high = render(&notes_for(&cue(RACKET_HIT, 1.5)));
+        assert_eq!(low.len(), high.len());
+        assert!(crossings(&high) > crossings(&low));
+    }
+
+    #[test]
+    fn every_sound_renders_to_a_playable_wav() {
+        for sound in [RACKET_HIT, WALL_BOUNCE, SCORE] {
+            let wav = synthesize(&cue(sound, 1.0));
+            assert!(wav.len() > 44, "{} rendered no samples", sound);
+            assert!(SoundData::from_bytes(&wav).can_play(), "{} is not a valid WAV", sound);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+// The campaign: a ladder of AI opponents for Player 1 to beat one after the other, each in its own arena and with
+// its own rules. Beating an opponent opens the next rung, and beating one of the AI personalities unlocks it as a
+// player type in the menu. Progress is shared by everyone playing on this computer and saved between sessions.
+
+use crate::game::arena::Arena;
+use crate::game::mode::GameMode;
+use crate::game::simulation::Simulation;
+use crate::player::player_type::PlayerType;
+use crate::storage::{self, Migration, StorageError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const CAMPAIGN_FILE: &str = "campaign.json";
+
+// Schema history of the campaign file (see `storage`); the first format is v0.
+const MIGRATIONS: &[Migration] = &[];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rung {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub opponent: PlayerType,
+    // Id of a built-in arena
+    pub arena: &'static str,
+    pub winning_score: u8,
+    // Balls put in play by each serve
+    pub balls: u8,
+    pub power_ups: bool,
+}
+
+pub const LADDER: [Rung; 5] = [
+    Rung {
+        name: "Rookie",
+        description: "A warm-up on the open field.",
+        opponent: PlayerType::AIEasy,
+        arena: "open",
+        winning_score: 5,
+        balls: 1,
+        power_ups: false,
+    },
+    Rung {
+        name: "The Wall",
+        description: "Gets to every ball and sends it straight back. Find the angle between the pillars.",
+        opponent: PlayerType::AIDefensive,
+        arena: "pillars",
+        winning_score: 5,
+        balls: 1,
+        power_ups: false,
+    },
+    Rung {
+        name: "Hothead",
+        description: "Takes the ball on the edge of the racket for steep returns, off bumpers that kick back.",
+        opponent: PlayerType::AIAggressive,
+        arena: "bumpers",
+        winning_score: 7,
+        balls: 1,
+        power_ups: false,
+    },
+    Rung {
+        name: "Joker",
+        description: "Waits on the wrong side and never returns a ball the same way twice. Power-ups are on.",
+        opponent: PlayerType::AITrickster,
+        arena: "portals",
+        winning_score: 7,
+        balls: 1,
+        power_ups: true,
+    },
+    Rung {
+        name: "Champion",
+        description: "The best AI there is, with two balls a serve and power-ups, to 10.",
+        opponent: PlayerType::AIHard,
+        arena: "crescents",
+        winning_score: 10,
+        balls: 2,
+        power_ups: true,
+    },
+];
+
+impl Rung {
+    // Set up `simulation` for a match on this rung, Player 1 against the rung's opponent. It still needs a reset.
+    pub fn prepare(&self, simulation: &mut Simulation) {
+        simulation.set_player_types(PlayerType::Human, self.opponent);
+        simulation.set_mode(GameMode::Classic, PlayerType::AIMedium, PlayerType::AIMedium);
+        simulation.set_arena(Arena::built_in(self.arena).unwrap_or_else(Arena::open));
+        simulation.winning_score = self.winning_score;
+        simulation.multi_ball.balls = self.balls;
+        simulation.multi_ball.collisions = false;
+        simulation.multi_ball.spawns = false;
+        simulation.power_up_rules.enabled = self.power_ups;
+    }
+
+    // The rules of the match, as players read them: "Pillars, first to 5, 2 balls, power-ups".
+    pub fn rules(&self) -> String {
+        let arena = Arena::built_in(self.arena).map_or_else(|| self.arena.to_string(), |arena| arena.name);
+        let mut rules = format!("{}, first to {}", arena, self.winning_score);
+        if self.balls > 1 {
+            rules.push_str(&format!(", {} balls", self.balls));
+        }
+        if self.power_ups {
+            rules.push_str(", power-ups");
+        }
+        rules
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CampaignDocument {
+    cleared: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct Campaign {
+    // `None` when the progress could not be loaded: it then stays in memory
+    path: Option<PathBuf>,
+    // Rungs beaten, from the bottom of the ladder
+    cleared: usize,
+}
+
+impl Campaign {
+    pub fn load(path: &Path) -> Result<Self, StorageError> {
+        let document: CampaignDocument = storage::load_document(path, MIGRATIONS)?.unwrap_or_default();
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            cleared: document.cleared.min(LADDER.len()),
+        })
+    }
+
+    // Progress that is never written to disk.
+    pub fn detached() -> Self {
+        Self::default()
+    }
+
+    pub fn save(&self) -> Result<(), StorageError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        storage::save_document(path, MIGRATIONS, &CampaignDocument { cleared: self.cleared })
+    }
+
+    pub fn cleared(&self) -> usize {
+        self.cleared
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.cleared == LADDER.len()
+    }
+
+    // Whether the rung at `index` can be played: every rung below it has been beaten.
+    pub fn is_open(&self, index: usize) -> bool {
+        index <= self.cleared && index < LADDER.len()
+    }
+
+    // Whether `player_type` can be picked in the menu: personalities have to be beaten in the campaign first.
+    pub fn is_unlocked(&self, player_type: PlayerType) -> bool {
+        !player_type.is_personality() || LADDER[..self.cleared].iter().any(|rung| rung.opponent == player_type)
+    }
+
+    // Player 1 beat the rung at `index`. Returns the rung if that opened the next one.
+    pub fn record_win(&mut self, index: usize) -> Option<&'static Rung> {
+        if index != self.cleared || index >= LADDER.len() {
+            return None;
+        }
+        self.cleared += 1;
+        Some(&LADDER[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ladder_meets_every_personality_in_a_built_in_arena() {
+        for rung in &LADDER {
+            assert!(Arena::built_in(rung.arena).is_some(), "{} has no arena", rung.name);
+            assert_ne!(rung.opponent, PlayerType::Human);
+        }
+        for personality in PlayerType::ALL.into_iter().filter(PlayerType::is_personality) {
+            assert!(LADDER.iter().any(|rung| rung.opponent == personality));
+        }
+        assert_eq!(LADDER[4].rules(), "Crescents, first to 10, 2 balls, power-ups");
+    }
+
+    #[test]
+    fn rungs_are_beaten_in_order_and_unlock_their_opponent() {
+        let mut campaign = Campaign::detached();
+        assert!(campaign.is_open(0) && !campaign.is_open(1));
+        assert!(!campaign.is_unlocked(PlayerType::AIDefensive));
+        assert!(campaign.is_unlocked(PlayerType::AIHard));
+
+        assert_eq!(campaign.record_win(1), None);
+        assert_eq!(campaign.record_win(0).map(|rung| rung.name), Some("Rookie"));
+        assert_eq!(campaign.record_win(1).map(|rung| rung.opponent), Some(PlayerType::AIDefensive));
+        // Beating a rung again changes nothing
+        assert_eq!(campaign.record_win(0), None);
+        assert_eq!(campaign.cleared(), 2);
+        assert!(campaign.is_unlocked(PlayerType::AIDefensive));
+        assert!(!campaign.is_unlocked(PlayerType::AITrickster));
+        assert!(campaign.is_open(2) && !campaign.is_complete());
+    }
+
+    #[test]
+    fn progress_survives_a_save_and_load() {
+        let directory = std::env::temp_dir().join(format!("rust-pong-campaign-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let path = directory.join(CAMPAIGN_FILE);
+        let mut campaign = Campaign::load(&path).unwrap();
+        for index in 0..LADDER.len() {
+            campaign.record_win(index);
+        }
+        campaign.save().unwrap();
+
+        let reloaded = Campaign::load(&path).unwrap();
+        assert!(reloaded.is_complete());
+        assert!(!reloaded.is_open(LADDER.len()));
+    }
+}
@@ -0,0 +1,46 @@
+use crate::player::player_type::PlayerType;
+
+pub const DEFAULT_RATING: f32 = 1000.0;
+const K_FACTOR: f32 = 32.0;
+
+// Fixed ratings for the AI tiers and personalities; humans without a profile count as a default-rated player.
+pub fn opponent_rating(player_type: PlayerType) -> f32 {
+    match player_type {
+        PlayerType::Human => DEFAULT_RATING,
+        PlayerType::AIEasy => 800.0,
+        PlayerType::AIMedium => 1000.0,
+        PlayerType::AIHard => 1200.0,
+        PlayerType::AIDefensive => 1100.0,
+        PlayerType::AIAggressive => 1150.0,
+        PlayerType::AITrickster => 1250.0,
+    }
+}
+
+// Probability of winning against `opponent`, as predicted by the ratings.
+pub fn expected_score(rating: f32, opponent: f32) -> f32 {
+    1.0 / (1.0 + 10f32.powf((opponent - rating) / 400.0))
+}
+
+pub fn rating_change(rating: f32, opponent: f32, won: bool) -> f32 {
+    let actual = if won { 1.0 } else { 0.0 };
+    K_FACTOR * (actual - expected_score(rating, opponent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_ratings_exchange_half_the_k_factor() {
+        assert_eq!(expected_score(1000.0, 1000.0), 0.5);
+        assert_eq!(rating_change(1000.0, 1000.0, true), 16.0);
+        assert_eq!(rating_change(1000.0, 1000.0, false), -16.0);
+    }
+
+    #[test]
+    fn upsets_are_worth_more() {
+        let upset = rating_change(800.0, 1200.0, true);
+        let expected_win = rating_change(1200.0, 800.0, true);
+        assert!(upset > 25.0 && expected_win < 5.0);
+    }
+}
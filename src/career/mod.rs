@@ -0,0 +1,151 @@
+// Career profiles: named players with a rating, match history and personal bests, saved between sessions.
+
+pub mod elo;
+pub mod profile;
+pub mod store;
+
+use crate::game::physics::Player;
+use crate::game::score::Score;
+use crate::game::stats::MatchStats;
+use crate::player::player_type::PlayerType;
+use profile::MatchRecord;
+use std::time::{SystemTime, UNIX_EPOCH};
+use store::ProfileStore;
+
+// Who played on one side of a match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Participant {
+    pub player_type: PlayerType,
+    // Only used by human players; `None` plays as a guest
+    pub profile: Option<String>,
+}
+
+impl Participant {
+    pub fn profile_name(&self) -> Option<&str> {
+        match self.player_type {
+            PlayerType::Human => self.profile.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn display_name(&self) -> String {
+        match (self.player_type, self.profile_name()) {
+            (_, Some(name)) => name.to_string(),
+            (PlayerType::Human, None) => "Guest".to_string(),
+            (player_type, None) => player_type.display_name().to_string(),
+        }
+    }
+
+    fn rating(&self, store: &ProfileStore) -> f32 {
+        self.profile_name()
+            .and_then(|name| store.get(name))
+            .map(|profile| profile.rating)
+            .unwrap_or_else(|| elo::opponent_rating(self.player_type))
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0)
+}
+
+// Record a finished match on the profiles that took part. Returns whether any profile changed.
+pub fn record_match(store: &mut ProfileStore, left: &Participant, right: &Participant, score: &Score, stats: &MatchStats, timestamp: u64) -> bool {
+    // Playing against yourself does not count
+    if left.profile_name().is_some() && left.profile_name() == right.profile_name() {
+        return false;
+    }
+
+    // Both sides are rated from their pre-match ratings
+    let ratings = (left.rating(store), right.rating(store));
+    let sides = [(Player::Left, left, right, ratings.1), (Player::Right, right, left, ratings.0)];
+
+    let mut changed = false;
+    for (side, participant, opponent, opponent_rating) in sides {
+        let Some(profile) = participant.profile_name().and_then(|name| store.get_mut(name)) else {
+            continue;
+        };
+        let other_side = if side == Player::Left { Player::Right } else { Player::Left };
+        let record = MatchRecord {
+            timestamp,
+            opponent: opponent.display_name(),
+            opponent_type: opponent.player_type.cli_name().to_string(),
+            score: score.get(side),
+            opponent_score: score.get(other_side),
+            won: score.get(side) > score.get(other_side),
+            rating_change: 0.0,
+            longest_rally: stats.longest_rally(),
+            fastest_ball: stats.max_ball_speed(),
+        };
+        profile.record_match(record, opponent_rating);
+        changed = true;
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn human(profile: &str) -> Participant {
+        Participant {
+            player_type: PlayerType::Human,
+            profile: Some(profile.to_string()),
+        }
+    }
+
+    fn final_score(left: u8, right: u8) -> Score {
+        let mut score = Score::new();
+        (0..left).for_each(|_| score.increment(Player::Left));
+        (0..right).for_each(|_| score.increment(Player::Right));
+        score
+    }
+
+    #[test]
+    fn both_profiles_are_updated_in_a_human_match() {
+        let mut store = ProfileStore::detached();
+        store.create("Ada", 0).unwrap();
+        store.create("Grace", 0).unwrap();
+
+        assert!(record_match(
+            &mut store,
+            &human("Ada"),
+            &human("Grace"),
+            &final_score(10, 7),
+            &MatchStats::new(),
+            5
+        ));
+
+        let ada = store.get("Ada").unwrap();
+        let grace = store.get("Grace").unwrap();
+        assert_eq!(ada.record_against("human").wins, 1);
+        assert_eq!(grace.record_against("human").losses, 1);
+        assert_eq!(ada.rating + grace.rating, 2.0 * elo::DEFAULT_RATING);
+        assert_eq!(grace.history[0].opponent, "Ada");
+    }
+
+    #[test]
+    fn guests_and_ai_are_not_recorded() {
+        let mut store = ProfileStore::detached();
+        store.create("Ada", 0).unwrap();
+        let ai = Participant {
+            player_type: PlayerType::AIHard,
+            profile: Some("Ada".to_string()),
+        };
+        let guest = Participant {
+            player_type: PlayerType::Human,
+            profile: None,
+        };
+
+        assert!(!record_match(&mut store, &guest, &ai, &final_score(3, 10), &MatchStats::new(), 0));
+        assert!(!record_match(
+            &mut store,
+            &human("Ada"),
+            &human("Ada"),
+            &final_score(3, 10),
+            &MatchStats::new(),
+            0
+        ));
+        assert!(record_match(&mut store, &human("Ada"), &ai, &final_score(3, 10), &MatchStats::new(), 0));
+        assert_eq!(store.get("Ada").unwrap().record_against("ai-hard").losses, 1);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::career::elo::{DEFAULT_RATING, rating_change};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Only the most recent matches are kept in a profile's history.
+pub const MAX_HISTORY: usize = 50;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct WinLoss {
+    pub wins: u32,
+    pub losses: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PersonalBests {
+    pub longest_rally: u32,
+    pub fastest_ball: f32,
+    pub biggest_win_margin: u8,
+}
+
+// One finished match, seen from the profile's side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub timestamp: u64,
+    // Opponent profile name, or the AI tier / "Guest" when there is none
+    pub opponent: String,
+    // Opponent player type, as its command-line name (e.g. "ai-hard")
+    pub opponent_type: String,
+    pub score: u8,
+    pub opponent_score: u8,
+    pub won: bool,
+    pub rating_change: f32,
+    pub longest_rally: u32,
+    pub fastest_ball: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub rating: f32,
+    pub created: u64,
+    // Win/loss record keyed by opponent player type ("human", "ai-easy", ...)
+    pub records: BTreeMap<String, WinLoss>,
+    pub bests: PersonalBests,
+    pub history: Vec<MatchRecord>,
+}
+
+impl Profile {
+    pub fn new(name: &str, created: u64) -> Self {
+        Self {
+            name: name.to_string(),
+            rating: DEFAULT_RATING,
+            created,
+            records: BTreeMap::new(),
+            bests: PersonalBests::default(),
+            history: Vec::new(),
+        }
+    }
+
+    pub fn total(&self) -> WinLoss {
+        self.records.values().fold(WinLoss::default(), |total, record| WinLoss {
+            wins: total.wins + record.wins,
+            losses: total.losses + record.losses,
+        })
+    }
+
+    pub fn record_against(&self, opponent_type: &str) -> WinLoss {
+        self.records.get(opponent_type).copied().unwrap_or_default()
+    }
+
+    // Apply a finished match: rating, win/loss record, personal bests and history.
+    // `record.rating_change` is filled in from `opponent_rating`.
+    pub fn record_match(&mut self, mut record: MatchRecord, opponent_rating: f32) {
+        record.rating_change = rating_change(self.rating, opponent_rating, record.won);
+        self.rating += record.rating_change;
+
+        let win_loss = self.records.entry(record.opponent_type.clone()).or_default();
+        if record.won {
+            win_loss.wins += 1;
+            let margin = record.score.saturating_sub(record.opponent_score);
+            self.bests.biggest_win_margin = self.bests.biggest_win_margin.max(margin);
+        } else {
+            win_loss.losses += 1;
+        }
+        self.bests.longest_rally = self.bests.longest_rally.max(record.longest_rally);
+        self.bests.fastest_ball = self.bests.fastest_ball.max(record.fastest_ball);
+
+        self.history.push(record);
+        if self.history.len() > MAX_HISTORY {
+            let excess = self.history.len() - MAX_HISTORY;
+            self.history.drain(..excess);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(won: bool, longest_rally: u32) -> MatchRecord {
+        MatchRecord {
+            timestamp: 0,
+            opponent: "AI - Hard".to_string(),
+            opponent_type: "ai-hard".to_string(),
+            score: if won { 10 } else { 4 },
+            opponent_score: if won { 4 } else { 10 },
+            won,
+            rating_change: 0.0,
+            longest_rally,
+            fastest_ball: 1200.0,
+        }
+    }
+
+    #[test]
+    fn match_updates_rating_record_and_bests() {
+        let mut profile = Profile::new("Ada", 0);
+        profile.record_match(record(true, 12), 1200.0);
+        profile.record_match(record(false, 3), 1200.0);
+
+        assert_eq!(profile.record_against("ai-hard"), WinLoss { wins: 1, losses: 1 });
+        assert_eq!(profile.record_against("ai-easy"), WinLoss::default());
+        assert_eq!(profile.bests.longest_rally, 12);
+        assert_eq!(profile.bests.biggest_win_margin, 6);
+        assert!(profile.rating > DEFAULT_RATING);
+        assert_eq!(profile.history.len(), 2);
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut profile = Profile::new("Ada", 0);
+        for rally in 0..(MAX_HISTORY as u32 + 5) {
+            profile.record_match(record(true, rally), 1000.0);
+        }
+        assert_eq!(profile.history.len(), MAX_HISTORY);
+        assert_eq!(profile.history[0].longest_rally, 5);
+        assert_eq!(profile.total().wins, MAX_HISTORY as u32 + 5);
+    }
+}
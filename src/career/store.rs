@@ -0,0 +1,144 @@
+use crate::career::profile::Profile;
+use crate::storage::{self, Migration, StorageError};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+
+pub const PROFILES_FILE: &str = "profiles.json";
+pub const MAX_NAME_LENGTH: usize = 16;
+
+// Schema history of the profiles file; index N upgrades a v(N) document to v(N + 1).
+const MIGRATIONS: &[Migration] = &[adopt_unversioned];
+
+// v0 -> v1: documents written before versioning, either a bare list of profiles or an object without a version.
+fn adopt_unversioned(document: Value) -> Result<Value, StorageError> {
+    match document {
+        Value::Array(profiles) => Ok(json!({ "version": 1, "profiles": profiles })),
+        Value::Object(mut object) => {
+            object.entry("profiles").or_insert_with(|| json!([]));
+            object.insert("version".to_string(), json!(1));
+            Ok(Value::Object(object))
+        }
+        _ => Err(StorageError::Invalid("expected a list of profiles".to_string())),
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfilesDocument {
+    profiles: Vec<Profile>,
+}
+
+// All career profiles, backed by a file in the user data directory.
+#[derive(Debug, Default)]
+pub struct ProfileStore {
+    // `None` when the store could not be loaded: it then works in memory and never overwrites the file
+    path: Option<PathBuf>,
+    profiles: Vec<Profile>,
+}
+
+impl ProfileStore {
+    pub fn load(path: &Path) -> Result<Self, StorageError> {
+        let document: ProfilesDocument = storage::load_document(path, MIGRATIONS)?.unwrap_or_default();
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            profiles: document.profiles,
+        })
+    }
+
+    // A store that is never written to disk.
+    pub fn detached() -> Self {
+        Self::default()
+    }
+
+    pub fn save(&self) -> Result<(), StorageError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let document = ProfilesDocument {
+            profiles: self.profiles.clone(),
+        };
+        storage::save_document(path, MIGRATIONS, &document)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Profile> {
+        self.profiles.iter_mut().find(|profile| profile.name == name)
+    }
+
+    // Create a profile, or return the name of the existing one when the name is taken.
+    pub fn create(&mut self, name: &str, created: u64) -> Result<String, StorageError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            return Err(StorageError::Invalid(format!("profile names must be 1 to {} characters", MAX_NAME_LENGTH)));
+        }
+        if self.get(name).is_none() {
+            self.profiles.push(Profile::new(name, created));
+        }
+        Ok(name.to_string())
+    }
+
+    // Profile names in creation order.
+    pub fn names(&self) -> Vec<String> {
+        self.profiles.iter().map(|profile| profile.name.clone()).collect()
+    }
+
+    // Profiles ordered by rating, best first.
+    pub fn leaderboard(&self) -> Vec<&Profile> {
+        let mut ranked: Vec<&Profile> = self.profiles.iter().collect();
+        ranked.sort_by(|a, b| b.rating.total_cmp(&a.rating).then_with(|| a.name.cmp(&b.name)));
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(test: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("rust-pong-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        directory.join(PROFILES_FILE)
+    }
+
+    #[test]
+    fn profiles_survive_a_save_and_load() {
+        let path = temp_path("profiles-roundtrip");
+        let mut store = ProfileStore::load(&path).unwrap();
+        assert!(store.names().is_empty());
+
+        store.create("Ada", 1).unwrap();
+        store.create("Grace", 2).unwrap();
+        store.get_mut("Grace").unwrap().rating = 1100.0;
+        store.save().unwrap();
+
+        let reloaded = ProfileStore::load(&path).unwrap();
+        assert_eq!(reloaded.leaderboard(), store.leaderboard());
+        let ranked: Vec<&str> = reloaded.leaderboard().iter().map(|profile| profile.name.as_str()).collect();
+        assert_eq!(ranked, ["Grace", "Ada"]);
+    }
+
+    #[test]
+    fn unversioned_files_are_migrated() {
+        let path = temp_path("profiles-migration");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let profile = serde_json::to_value(Profile::new("Ada", 1)).unwrap();
+        std::fs::write(&path, Value::Array(vec![profile]).to_string()).unwrap();
+
+        let store = ProfileStore::load(&path).unwrap();
+        assert_eq!(store.names(), ["Ada"]);
+    }
+
+    #[test]
+    fn names_are_validated_and_unique() {
+        let mut store = ProfileStore::detached();
+        assert!(store.create("   ", 0).is_err());
+        assert!(store.create("a name that is far too long", 0).is_err());
+        assert_eq!(store.create(" Ada ", 0).unwrap(), "Ada");
+        store.create("Ada", 0).unwrap();
+        assert_eq!(store.names(), ["Ada"]);
+        assert!(store.save().is_ok());
+    }
+}
@@ -0,0 +1,416 @@
+// Command-line options.
+//
+// Passing any match flag (--mode, --left, --right, --top, --bottom, --left-front, --right-front, --score, --seed)
+// skips the menu and starts a match directly.
+// Audio, serve, multi-ball, power-up and arena flags override the saved settings for this session only.
+// --headless runs the match without a window and prints the final score as JSON on stdout.
+
+use crate::audio::SoundPack;
+use crate::game::mode::GameMode;
+use crate::game::multiball::{MAX_BALLS, MultiBall};
+use crate::game::powerup::{PowerUpRules, SpawnRate};
+use crate::game::serve::ServeRule;
+use crate::game::simulation::DEFAULT_WINNING_SCORE;
+use crate::player::player_type::PlayerType;
+
+pub const USAGE: &str = "Usage: Rust-Pong [OPTIONS]
+
+Options:
+  --left <TYPE>       Left player type: human, ai-easy, ai-medium, ai-hard, or the campaign's AI personalities
+                      ai-defensive, ai-aggressive and ai-trickster (default: human)
+  --right <TYPE>      Right player type (default: ai-hard)
+  --mode <MODE>       Game mode: classic, ffa (four-player free-for-all), teams (2 vs 2), doubles, pongout, or the
+                      single-player challenges survival, time-attack and wall (default: classic)
+  --top <TYPE>        Top player type in the ffa and teams modes (default: ai-medium)
+  --bottom <TYPE>     Bottom player type in the ffa and teams modes (default: ai-medium)
+  --left-front <TYPE> Left front player type in doubles (default: ai-medium)
+  --right-front <TYPE>
+                      Right front player type in doubles (default: ai-medium)
+  --score <N>         Points needed to win the match (default: 10; Pong-Out is won on its bricks)
+  --seed <N>          Seed for the ball's random serves
+  --serve-rule <RULE> Who serves after a point: random, loser, alternate (default: random)
+  --human-serve       Human players hold the ball and serve it themselves (D, Left arrow, K or Numpad 8)
+  --balls <N>         Balls put in play by each serve, from 1 to 5 (default: 1)
+  --ball-collisions   Balls bounce off each other
+  --ball-spawns       Extra balls join long rallies
+  --power-ups         Power-ups appear in the arena for the last hitter to collect
+  --power-up-rate <RATE>
+                      How often power-ups appear: rare, normal, frequent (default: normal)
+  --arena <ARENA>     Arena: open, pillars, bumpers, portals, crescents, or a level file in the arenas folder
+                      (a path to one for headless runs)
+  --windowed          Run in a window instead of fullscreen
+  --size <WxH>        Window or headless arena size, e.g. 1280x720
+  --headless          Simulate the match without a window and print the result as JSON
+  --frames <N>        Stop a headless match after N frames
+  --volume <0-100>    Master volume in percent
+  --sfx-volume <0-100>
+                      Sound effects volume in percent
+  --music-volume <0-100>
+                      Music volume in percent
+  --mute              Start with all sound muted
+  --sound-pack <PACK> Sound effects: samples, synth
+  -h, --help          Print this help";
+
+const DEFAULT_HEADLESS_SIZE: (f32, f32) = (1280.0, 720.0);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliError {
+    Help,
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliOptions {
+    pub left: PlayerType,
+    pub right: PlayerType,
+    pub mode: GameMode,
+    pub top: PlayerType,
+    pub bottom: PlayerType,
+    pub left_front: PlayerType,
+    pub right_front: PlayerType,
+    pub winning_score: u8,
+    pub seed: Option<u64>,
+    pub windowed: bool,
+    pub size: Option<(f32, f32)>,
+    pub headless: bool,
+    pub frames: Option<u64>,
+    pub skip_menu: bool,
+    // Audio overrides for this session, on top of the saved settings
+    pub master_volume: Option<f32>,
+    pub sfx_volume: Option<f32>,
+    pub music_volume: Option<f32>,
+    pub mute: bool,
+    pub sound_pack: Option<SoundPack>,
+    pub serve_rule: Option<ServeRule>,
+    pub human_serve: bool,
+    pub balls: Option<u8>,
+    pub ball_collisions: bool,
+    pub ball_spawns: bool,
+    pub power_ups: bool,
+    pub power_up_rate: Option<SpawnRate>,
+    pub arena: Option<String>,
+}
+
+impl Default for CliOptions {
+    fn default() -> Self {
+        Self {
+            left: PlayerType::Human,
+            right: PlayerType::AIHard,
+            mode: GameMode::Classic,
+            top: PlayerType::AIMedium,
+            bottom: PlayerType::AIMedium,
+            left_front: PlayerType::AIMedium,
+            right_front: PlayerType::AIMedium,
+            winning_score: DEFAULT_WINNING_SCORE,
+            seed: None,
+            windowed: false,
+            size: None,
+            headless: false,
+            frames: None,
+            skip_menu: false,
+            master_volume: None,
+            sfx_volume: None,
+            music_volume: None,
+            mute: false,
+            sound_pack: None,
+            serve_rule: None,
+            human_serve: false,
+            balls: None,
+            ball_collisions: false,
+            ball_spawns: false,
+            power_ups: false,
+            power_up_rate: None,
+            arena: None,
+        }
+    }
+}
+
+impl CliOptions {
+    // Types of Players 3 and 4 in the selected mode: the top and bottom players, or the front players in doubles.
+    pub fn extra_player_types(&self) -> (PlayerType, PlayerType) {
+        match self.mode {
+            GameMode::Doubles => (self.left_front, self.right_front),
+            _ => (self.top, self.bottom),
+        }
+    }
+
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, CliError> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => return Err(CliError::Help),
+                "--windowed" => options.windowed = true,
+                "--headless" => options.headless = true,
+                "--left" => {
+                    options.left = parse_player_type(&arg, args.next())?;
+                    options.skip_menu = true;
+                }
+                "--right" => {
+                    options.right = parse_player_type(&arg, args.next())?;
+                    options.skip_menu = true;
+                }
+                "--mode" => {
+                    let value = expect_value(&arg, args.next())?;
+                    options.mode = GameMode::from_cli_name(&value).ok_or_else(|| CliError::Invalid(format!("{}: unknown game mode '{}'", arg, value)))?;
+                    options.skip_menu = true;
+                }
+                "--top" => {
+                    options.top = parse_player_type(&arg, args.next())?;
+                    options.skip_menu = true;
+                }
+                "--bottom" => {
+                    options.bottom = parse_player_type(&arg, args.next())?;
+                    options.skip_menu = true;
+                }
+                "--left-front" => {
+                    options.left_front = parse_player_type(&arg, args.next())?;
+                    options.skip_menu = true;
+                }
+                "--right-front" => {
+                    options.right_front = parse_player_type(&arg, args.next())?;
+                    options.skip_menu = true;
+                }
+                "--score" => {
+                    options.winning_score = parse_number(&arg, args.next())?;
+                    if options.winning_score == 0 {
+                        return Err(CliError::Invalid("--score must be at least 1".to_string()));
+                    }
+                    options.skip_menu = true;
+                }
+                "--seed" => {
+                    options.seed = Some(parse_number(&arg, args.next())?);
+                    options.skip_menu = true;
+                }
+                "--size" => options.size = Some(parse_size(&arg, args.next())?),
+                "--frames" => options.frames = Some(parse_number(&arg, args.next())?),
+                "--volume" => options.master_volume = Some(parse_percent(&arg, args.next())?),
+                "--sfx-volume" => options.sfx_volume = Some(parse_percent(&arg, args.next())?),
+                "--music-volume" => options.music_volume = Some(parse_percent(&arg, args.next())?),
+                "--mute" => options.mute = true,
+                "--sound-pack" => {
+                    let value = expect_value(&arg, args.next())?;
+                    let pack = SoundPack::from_cli_name(&value).ok_or_else(|| CliError::Invalid(format!("{}: unknown sound pack '{}'", arg, value)))?;
+                    options.sound_pack = Some(pack);
+                }
+                "--serve-rule" => {
+                    let value = expect_value(&arg, args.next())?;
+                    let rule = ServeRule::from_cli_name(&value).ok_or_else(|| CliError::Invalid(format!("{}: unknown serve rule '{}'", arg, value)))?;
+                    options.serve_rule = Some(rule);
+                }
+                "--human-serve" => options.human_serve = true,
+                "--balls" => {
+                    let balls = parse_number(&arg, args.next())?;
+                    if !(1..=MAX_BALLS).contains(&balls) {
+                        return Err(CliError::Invalid(format!("--balls must be between 1 and {}", MAX_BALLS)));
+                    }
+                    options.balls = Some(balls);
+                }
+                "--ball-collisions" => options.ball_collisions = true,
+                "--ball-spawns" => options.ball_spawns = true,
+                "--power-ups" => options.power_ups = true,
+                "--power-up-rate" => {
+                    let value = expect_value(&arg, args.next())?;
+                    let rate = SpawnRate::from_cli_name(&value).ok_or_else(|| CliError::Invalid(format!("{}: unknown spawn rate '{}'", arg, value)))?;
+                    options.power_up_rate = Some(rate);
+                }
+                "--arena" => options.arena = Some(expect_value(&arg, args.next())?),
+                other => return Err(CliError::Invalid(format!("Unknown argument: {}", other))),
+            }
+        }
+
+        if options.frames.is_some() && !options.headless {
+            return Err(CliError::Invalid("--frames only applies to --headless runs".to_string()));
+        }
+
+        Ok(options)
+    }
+
+    // Override the multi-ball options given on the command line.
+    pub fn apply_multi_ball(&self, multi_ball: &mut MultiBall) {
+        if let Some(balls) = self.balls {
+            multi_ball.balls = balls;
+        }
+        if self.ball_collisions {
+            multi_ball.collisions = true;
+        }
+        if self.ball_spawns {
+            multi_ball.spawns = true;
+        }
+    }
+
+    // Override the power-up rules given on the command line.
+    pub fn apply_power_ups(&self, rules: &mut PowerUpRules) {
+        if self.power_ups {
+            rules.enabled = true;
+        }
+        if let Some(rate) = self.power_up_rate {
+            rules.rate = rate;
+        }
+    }
+
+    // Arena size for headless runs.
+    pub fn headless_size(&self) -> (f32, f32) {
+        self.size.unwrap_or(DEFAULT_HEADLESS_SIZE)
+    }
+}
+
+fn expect_value(flag: &str, value: Option<String>) -> Result<String, CliError> {
+    value.ok_or_else(|| CliError::Invalid(format!("{} expects a value", flag)))
+}
+
+fn parse_player_type(flag: &str, value: Option<String>) -> Result<PlayerType, CliError> {
+    let value = expect_value(flag, value)?;
+    PlayerType::from_cli_name(&value).ok_or_else(|| CliError::Invalid(format!("{}: unknown player type '{}'", flag, value)))
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, CliError> {
+    let value = expect_value(flag, value)?;
+    value.parse().map_err(|_| CliError::Invalid(format!("{}: invalid number '{}'", flag, value)))
+}
+
+// Volume given in percent, as a level from 0 to 1.
+fn parse_percent(flag: &str, value: Option<String>) -> Result<f32, CliError> {
+    let percent: u8 = parse_number(flag, value)?;
+    if percent > 100 {
+        return Err(CliError::Invalid(format!("{} must be between 0 and 100", flag)));
+    }
+    Ok(percent as f32 / 100.0)
+}
+
+fn parse_size(flag: &str, value: Option<String>) -> Result<(f32, f32), CliError> {
+    let value = expect_value(flag, value)?;
+    let invalid = || CliError::Invalid(format!("{}: expected WIDTHxHEIGHT, got '{}'", flag, value));
+    let (width, height) = value.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width: u32 = width.parse().map_err(|_| invalid())?;
+    let height: u32 = height.parse().map_err(|_| invalid())?;
+    if width == 0 || height == 0 {
+        return Err(invalid());
+    }
+    Ok((width as f32, height as f32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliOptions, CliError> {
+        CliOptions::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_opens_the_menu() {
+        let options = parse(&[]).unwrap();
+        assert_eq!(options, CliOptions::default());
+        assert!(!options.skip_menu);
+    }
+
+    #[test]
+    fn full_command_line() {
+        let options = parse(&[
+            "--left",
+            "human",
+            "--right",
+            "ai-hard",
+            "--mode",
+            "teams",
+            "--top",
+            "ai-easy",
+            "--bottom",
+            "human",
+            "--left-front",
+            "ai-hard",
+            "--right-front",
+            "ai-easy",
+            "--score",
+            "5",
+            "--seed",
+            "42",
+            "--windowed",
+            "--size",
+            "1280x720",
+            "--headless",
+            "--frames",
+            "600",
+            "--volume",
+            "50",
+            "--sfx-volume",
+            "80",
+            "--mute",
+            "--sound-pack",
+            "synth",
+            "--serve-rule",
+            "loser",
+            "--human-serve",
+            "--balls",
+            "3",
+            "--ball-collisions",
+            "--ball-spawns",
+            "--power-ups",
+            "--power-up-rate",
+            "frequent",
+            "--arena",
+            "portals",
+        ])
+        .unwrap();
+        assert_eq!(options.left, PlayerType::Human);
+        assert_eq!(options.right, PlayerType::AIHard);
+        assert_eq!(options.mode, GameMode::Teams);
+        assert_eq!((options.top, options.bottom), (PlayerType::AIEasy, PlayerType::Human));
+        assert_eq!((options.left_front, options.right_front), (PlayerType::AIHard, PlayerType::AIEasy));
+        assert_eq!(options.extra_player_types(), (PlayerType::AIEasy, PlayerType::Human));
+        assert_eq!(options.winning_score, 5);
+        assert_eq!(options.seed, Some(42));
+        assert!(options.windowed && options.headless && options.skip_menu);
+        assert_eq!(options.size, Some((1280.0, 720.0)));
+        assert_eq!(options.frames, Some(600));
+        assert_eq!(options.master_volume, Some(0.5));
+        assert_eq!(options.sfx_volume, Some(0.8));
+        assert!(options.mute);
+        assert_eq!(options.sound_pack, Some(SoundPack::Synth));
+        assert_eq!(options.serve_rule, Some(ServeRule::Loser));
+        assert!(options.human_serve);
+        assert_eq!(options.arena.as_deref(), Some("portals"));
+        let mut multi_ball = MultiBall::default();
+        options.apply_multi_ball(&mut multi_ball);
+        assert_eq!(
+            multi_ball,
+            MultiBall {
+                balls: 3,
+                collisions: true,
+                spawns: true
+            }
+        );
+        let mut rules = PowerUpRules::default();
+        options.apply_power_ups(&mut rules);
+        assert_eq!(
+            rules,
+            PowerUpRules {
+                enabled: true,
+                rate: SpawnRate::Frequent
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_bad_values() {
+        assert!(matches!(parse(&["--left", "robot"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--score"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--score", "0"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--size", "1280"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--frames", "10"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--volume", "101"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--sound-pack", "orchestra"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--serve-rule", "winner"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--mode", "solo"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--top", "robot"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--left-front"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--balls", "0"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--balls", "6"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--power-up-rate", "always"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--arena"]), Err(CliError::Invalid(_))));
+        assert!(matches!(parse(&["--fullscreen"]), Err(CliError::Invalid(_))));
+        assert_eq!(parse(&["--help"]), Err(CliError::Help));
+    }
+}
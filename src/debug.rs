@@ -61,7 +61,7 @@ impl DebugInfo {
         };
         let vel_length = 60.0;
         let end = position + dir * vel_length;
-        self.ball_velocity_mesh = Some(Mesh::new_line(context, &[position, end], 3.0, Color::from_rgb(0, 255, 0))?);
+        self.ball_velocity_mesh = Some(Mesh::new_line(context, &[position, end], 3.0, Color::WHITE)?);
 
         // Build an arrowhead (triangle) at the end of the velocity vector
         let arrow_tip = end + dir * 8.0;
@@ -71,17 +71,18 @@ impl DebugInfo {
         let left = arrow_back + perp * arrow_half_width;
         let right = arrow_back - perp * arrow_half_width;
         let points = [arrow_tip, left, right];
-        self.ball_velocity_arrow_mesh = Some(Mesh::new_polygon(context, DrawMode::fill(), &points, Color::from_rgb(0, 255, 0))?);
+        self.ball_velocity_arrow_mesh = Some(Mesh::new_polygon(context, DrawMode::fill(), &points, Color::WHITE)?);
 
         Ok(())
     }
 
-    pub fn draw(&self, canvas: &mut Canvas) {
+    // Meshes are white and tinted with `color` when drawn.
+    pub fn draw(&self, canvas: &mut Canvas, color: Color) {
         if !self.enabled {
             return;
         }
 
-        canvas.draw(&self.fps_text, DrawParam::default().dest(self.position).color(Color::from_rgb(0, 255, 0)));
+        canvas.draw(&self.fps_text, DrawParam::default().dest(self.position).color(color));
 
         let line_height = 22.0;
         let pos = self.position;
@@ -89,15 +90,15 @@ impl DebugInfo {
         let pos_vel = Vec2::new(pos.x, pos.y + line_height * 3.2);
         let pos_speed = Vec2::new(pos.x, pos.y + line_height * 4.4);
 
-        canvas.draw(&self.ball_position_text, DrawParam::default().dest(pos_ball).color(Color::from_rgb(0, 255, 0)));
-        canvas.draw(&self.ball_velocity_text, DrawParam::default().dest(pos_vel).color(Color::from_rgb(0, 255, 0)));
-        canvas.draw(&self.ball_speed_text, DrawParam::default().dest(pos_speed).color(Color::from_rgb(0, 255, 0)));
+        canvas.draw(&self.ball_position_text, DrawParam::default().dest(pos_ball).color(color));
+        canvas.draw(&self.ball_velocity_text, DrawParam::default().dest(pos_vel).color(color));
+        canvas.draw(&self.ball_speed_text, DrawParam::default().dest(pos_speed).color(color));
 
         if let Some(mesh) = &self.ball_velocity_mesh {
-            canvas.draw(mesh, DrawParam::default());
+            canvas.draw(mesh, DrawParam::default().color(color));
         }
         if let Some(mesh) = &self.ball_velocity_arrow_mesh {
-            canvas.draw(mesh, DrawParam::default());
+            canvas.draw(mesh, DrawParam::default().color(color));
         }
     }
 }
@@ -0,0 +1,535 @@
+// The arena editor: places, moves, resizes and deletes the obstacles of an arena with the mouse.
+//
+// The editor works on a field of any size, in pixels, and stores obstacles in the level format's fractions (see
+// `game::arena`). Moving obstacles are edited where they start. Every change can be undone and redone.
+
+use crate::game::arena::{Arena, Body, Motion, Obstacle, Shape, arc_angle};
+use ggez::glam::Vec2;
+
+// Spacing of the snapping grid, as a fraction of the field's width and height
+pub const GRID_STEP: f32 = 0.025;
+// Sizes snap to steps of this fraction of the field's height
+const SIZE_STEP: f32 = 0.005;
+const MIN_SIZE: f32 = 0.01;
+// Distance in pixels within which the mouse grabs an obstacle or a handle
+const GRAB_MARGIN: f32 = 8.0;
+// Size change of a mouse wheel step, and angle change of a curve's span (degrees)
+const WHEEL_SCALE: f32 = 1.1;
+const WHEEL_SPAN: f32 = 10.0;
+// Motion given to an obstacle by `cycle_motion`
+const MOTION_OFFSET: f32 = 0.2;
+const MOTION_PERIOD: f32 = 4.0;
+// Changes that can be undone
+const MAX_UNDO: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    Block,
+    Bumper,
+    Portal,
+    Curve,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 4] = [Tool::Block, Tool::Bumper, Tool::Portal, Tool::Curve];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Block => "Block",
+            Tool::Bumper => "Bumper",
+            Tool::Portal => "Portal",
+            Tool::Curve => "Curve",
+        }
+    }
+
+    // A new obstacle of this kind at (x, y), as fractions of the field.
+    fn shape_at(&self, x: f32, y: f32) -> Shape {
+        match self {
+            Tool::Block => Shape::Block {
+                x,
+                y,
+                width: 0.04,
+                height: 0.16,
+            },
+            Tool::Bumper => Shape::Bumper { x, y, radius: 0.05 },
+            // The exit starts mirrored across the middle of the field
+            Tool::Portal => Shape::Portal {
+                x,
+                y,
+                to_x: 1.0 - x,
+                to_y: 1.0 - y,
+                radius: 0.04,
+            },
+            // The middle of the curve goes where the mouse is, bowing upwards
+            Tool::Curve => Shape::Curve {
+                x,
+                y: (y + 0.15).min(1.0),
+                radius: 0.15,
+                from: 220.0,
+                to: 320.0,
+                thickness: 0.025,
+            },
+        }
+    }
+}
+
+// What the mouse holds while dragging.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Part {
+    // The whole obstacle
+    Body,
+    // The size handle
+    Handle,
+    // Where a portal's balls come out
+    Exit,
+}
+
+#[derive(Debug, Clone)]
+struct Grab {
+    index: usize,
+    part: Part,
+    // Mouse position when the drag started, and the obstacle as it was then
+    start: Vec2,
+    original: Obstacle,
+    // The arena before the drag, to undo it
+    before: Arena,
+}
+
+#[derive(Debug, Clone)]
+pub struct Editor {
+    pub arena: Arena,
+    // Level file the arena is saved as, in the arenas folder
+    pub file: String,
+    pub tool: Tool,
+    pub snap: bool,
+    selected: Option<usize>,
+    grab: Option<Grab>,
+    undo: Vec<Arena>,
+    redo: Vec<Arena>,
+    // Changes since the arena was opened or last saved
+    dirty: bool,
+    // Whether leaving with unsaved changes was asked for once already
+    leave_asked: bool,
+}
+
+impl Editor {
+    // Edit `arena`, loaded from the arena `id`. Built-in arenas are saved as a new level file next to the others.
+    pub fn open(mut arena: Arena, id: &str) -> Self {
+        let file = if id.to_ascii_lowercase().ends_with(".json") {
+            id.to_string()
+        } else {
+            arena.name = format!("{} (custom)", arena.name);
+            format!("{}-custom.json", id)
+        };
+        Self {
+            arena,
+            file,
+            tool: Tool::Block,
+            snap: true,
+            selected: None,
+            grab: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            dirty: false,
+            leave_asked: false,
+        }
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.dirty = false;
+    }
+
+    // Whether the editor may close. Unsaved changes keep it open the first time, until changed again.
+    pub fn confirm_leave(&mut self) -> bool {
+        let leave = !self.dirty || self.leave_asked;
+        self.leave_asked = true;
+        leave
+    }
+
+    // Start over with an empty arena, saved as a new file.
+    pub fn clear(&mut self, file: String) {
+        self.record();
+        self.arena = Arena {
+            name: file.trim_end_matches(".json").to_string(),
+            obstacles: Vec::new(),
+        };
+        self.file = file;
+        self.selected = None;
+    }
+
+    // Every obstacle where it starts, in a `width` x `height` field.
+    pub fn bodies(&self, width: f32, height: f32) -> Vec<Body> {
+        self.arena.bodies(0.0, width, height)
+    }
+
+    // The selected obstacle's size handle, in pixels.
+    pub fn handle(&self, width: f32, height: f32) -> Option<Vec2> {
+        let index = self.selected?;
+        Some(handle_of(&self.arena.obstacles[index].body(0.0, width, height)))
+    }
+
+    // Press the mouse at `point`: grab the selected obstacle's handle or a portal exit, or any obstacle under the
+    // mouse, or else place a new obstacle with the current tool.
+    pub fn press(&mut self, point: Vec2, width: f32, height: f32) {
+        let bodies = self.bodies(width, height);
+        let on_handle = self.selected.filter(|&index| handle_of(&bodies[index]).distance(point) <= GRAB_MARGIN * 1.5);
+        let (index, part) = match on_handle {
+            Some(index) => (index, Part::Handle),
+            None => match part_at(&bodies, point) {
+                Some(found) => found,
+                None => {
+                    let before = self.arena.clone();
+                    let at = self.snapped(Vec2::new(point.x / width, point.y / height));
+                    self.arena.obstacles.push(Obstacle::fixed(self.tool.shape_at(at.x, at.y)));
+                    self.commit(before);
+                    self.selected = Some(self.arena.obstacles.len() - 1);
+                    return;
+                }
+            },
+        };
+        self.selected = Some(index);
+        self.grab = Some(Grab {
+            index,
+            part,
+            start: point,
+            original: self.arena.obstacles[index],
+            before: self.arena.clone(),
+        });
+    }
+
+    // Drag whatever the mouse holds to `point`.
+    pub fn drag(&mut self, point: Vec2, width: f32, height: f32) {
+        let Some(grab) = &self.grab else {
+            return;
+        };
+        let field = Vec2::new(width, height);
+        let moved = (point - grab.start) / field;
+        let mut obstacle = grab.original;
+        match grab.part {
+            Part::Body => {
+                let (x, y) = position(&obstacle.shape);
+                let at = self.snapped(Vec2::new(x, y) + moved);
+                set_position(&mut obstacle.shape, at);
+            }
+            Part::Exit => {
+                if let Shape::Portal { to_x, to_y, .. } = &mut obstacle.shape {
+                    let at = self.snapped(Vec2::new(*to_x, *to_y) + moved);
+                    (*to_x, *to_y) = (at.x, at.y);
+                }
+            }
+            Part::Handle => {
+                let center = match obstacle.body(0.0, width, height) {
+                    Body::Block { center, .. } | Body::Bumper { center, .. } | Body::Portal { center, .. } | Body::Curve { center, .. } => center,
+                };
+                // Sizes are fractions of the field's height
+                let reach = (point - center) / height;
+                let size = |value: f32| self.snapped_size(value);
+                match &mut obstacle.shape {
+                    Shape::Block { width, height, .. } => {
+                        *width = size(reach.x.abs() * 2.0);
+                        *height = size(reach.y.abs() * 2.0);
+                    }
+                    Shape::Bumper { radius, .. } | Shape::Portal { radius, .. } => *radius = size(reach.length()),
+                    Shape::Curve { radius, from, to, .. } => {
+                        // The curve turns to face the mouse, keeping its span
+                        *radius = size(reach.length());
+                        let half_span = (*to - *from) / 2.0;
+                        let middle = reach.y.atan2(reach.x).to_degrees().rem_euclid(360.0);
+                        (*from, *to) = (middle - half_span, middle + half_span);
+                    }
+                }
+            }
+        }
+        let index = grab.index;
+        self.arena.obstacles[index] = obstacle;
+    }
+
+    // Let go of the mouse, keeping the drag as one change to undo.
+    pub fn release(&mut self) {
+        if let Some(grab) = self.grab.take()
+            && self.arena.obstacles[grab.index] != grab.original
+        {
+            self.commit(grab.before);
+        }
+    }
+
+    // Remove the obstacle under `point`, if any.
+    pub fn delete_at(&mut self, point: Vec2, width: f32, height: f32) {
+        if let Some((index, _)) = part_at(&self.bodies(width, height), point) {
+            self.selected = Some(index);
+            self.delete_selected();
+        }
+    }
+
+    pub fn delete_selected(&mut self) {
+        let Some(index) = self.selected.take() else {
+            return;
+        };
+        let before = self.arena.clone();
+        self.arena.obstacles.remove(index);
+        self.commit(before);
+    }
+
+    // Grow (`steps` > 0) or shrink the selected obstacle; curves widen or narrow their span instead.
+    pub fn resize_selected(&mut self, steps: f32) {
+        let Some(index) = self.selected else {
+            return;
+        };
+        let before = self.arena.clone();
+        let scale = WHEEL_SCALE.powf(steps);
+        let grow = |value: &mut f32| *value = (*value * scale).max(MIN_SIZE);
+        match &mut self.arena.obstacles[index].shape {
+            Shape::Block { width, height, .. } => {
+                grow(width);
+                grow(height);
+            }
+            Shape::Bumper { radius, .. } | Shape::Portal { radius, .. } => grow(radius),
+            Shape::Curve { from, to, .. } => {
+                let span = (*to - *from + 2.0 * WHEEL_SPAN * steps).clamp(WHEEL_SPAN, 360.0);
+                let middle = (*from + *to) / 2.0;
+                (*from, *to) = (middle - span / 2.0, middle + span / 2.0);
+            }
+        }
+        self.commit(before);
+    }
+
+    // Make the selected obstacle slide sideways, then up and down, then stand still again.
+    pub fn cycle_motion(&mut self) {
+        let Some(index) = self.selected else {
+            return;
+        };
+        let before = self.arena.clone();
+        let obstacle = &mut self.arena.obstacles[index];
+        obstacle.motion = match obstacle.motion {
+            None => Some(Motion {
+                dx: MOTION_OFFSET,
+                dy: 0.0,
+                period: MOTION_PERIOD,
+            }),
+            Some(motion) if motion.dx != 0.0 => Some(Motion {
+                dx: 0.0,
+                dy: MOTION_OFFSET,
+                period: MOTION_PERIOD,
+            }),
+            Some(_) => None,
+        };
+        self.commit(before);
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(arena) = self.undo.pop() {
+            self.redo.push(std::mem::replace(&mut self.arena, arena));
+            self.after_history();
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(arena) = self.redo.pop() {
+            self.undo.push(std::mem::replace(&mut self.arena, arena));
+            self.after_history();
+        }
+    }
+
+    fn after_history(&mut self) {
+        self.grab = None;
+        self.selected = self.selected.filter(|&index| index < self.arena.obstacles.len());
+        self.dirty = true;
+    }
+
+    // Keep the arena as it is now for undo.
+    fn record(&mut self) {
+        self.commit(self.arena.clone());
+    }
+
+    // Keep `before` for undo, as the state the latest change started from.
+    fn commit(&mut self, before: Arena) {
+        self.undo.push(before);
+        if self.undo.len() > MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        self.dirty = true;
+        self.leave_asked = false;
+    }
+
+    fn snapped(&self, at: Vec2) -> Vec2 {
+        let at = if self.snap { (at / GRID_STEP).round() * GRID_STEP } else { at };
+        at.clamp(Vec2::ZERO, Vec2::ONE)
+    }
+
+    fn snapped_size(&self, size: f32) -> f32 {
+        let size = if self.snap { (size / SIZE_STEP).round() * SIZE_STEP } else { size };
+        size.max(MIN_SIZE)
+    }
+}
+
+fn position(shape: &Shape) -> (f32, f32) {
+    match *shape {
+        Shape::Block { x, y, .. } | Shape::Bumper { x, y, .. } | Shape::Portal { x, y, .. } | Shape::Curve { x, y, .. } => (x, y),
+    }
+}
+
+fn set_position(shape: &mut Shape, at: Vec2) {
+    match shape {
+        Shape::Block { x, y, .. } | Shape::Bumper { x, y, .. } | Shape::Portal { x, y, .. } | Shape::Curve { x, y, .. } => {
+            (*x, *y) = (at.x, at.y);
+        }
+    }
+}
+
+// Where the size handle of a placed obstacle is: a block's bottom right corner, the right of a circle, or the
+// middle of a curve.
+pub fn handle_of(body: &Body) -> Vec2 {
+    match *body {
+        Body::Block { center, half_size } => center + half_size,
+        Body::Bumper { center, radius } | Body::Portal { center, radius, .. } => center + Vec2::new(radius, 0.0),
+        Body::Curve { center, radius, from, to, .. } => center + Vec2::from_angle((from + to) / 2.0) * radius,
+    }
+}
+
+// The topmost obstacle under `point`, and which part of it.
+fn part_at(bodies: &[Body], point: Vec2) -> Option<(usize, Part)> {
+    bodies.iter().enumerate().rev().find_map(|(index, body)| {
+        let part = match *body {
+            Body::Block { center, half_size } => {
+                let outside = ((point - center).abs() - half_size).max(Vec2::ZERO);
+                (outside.length() <= GRAB_MARGIN).then_some(Part::Body)
+            }
+            Body::Bumper { center, radius } => (point.distance(center) <= radius + GRAB_MARGIN).then_some(Part::Body),
+            Body::Portal { center, radius, exit } => {
+                if point.distance(center) <= radius + GRAB_MARGIN {
+                    Some(Part::Body)
+                } else {
+                    (point.distance(exit) <= radius + GRAB_MARGIN).then_some(Part::Exit)
+                }
+            }
+            Body::Curve {
+                center,
+                radius,
+                from,
+                to,
+                half_thickness,
+            } => {
+                let offset = point - center;
+                let angle = arc_angle(offset, from);
+                let on_band = (offset.length() - radius).abs() <= half_thickness + GRAB_MARGIN;
+                (on_band && angle <= to).then_some(Part::Body)
+            }
+        }?;
+        Some((index, part))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const W: f32 = 800.0;
+    const H: f32 = 400.0;
+
+    fn editor() -> Editor {
+        Editor::open(Arena::open(), "open")
+    }
+
+    #[test]
+    fn built_in_arenas_are_saved_as_new_files() {
+        let editor = editor();
+        assert_eq!(editor.file, "open-custom.json");
+        assert_eq!(editor.arena.name, "Open (custom)");
+        assert_eq!(Editor::open(Arena::open(), "mine.json").file, "mine.json");
+    }
+
+    #[test]
+    fn place_move_and_undo() {
+        let mut editor = editor();
+        editor.press(Vec2::new(203.0, 98.0), W, H);
+        editor.release();
+        // Snapped to the grid
+        assert_eq!(editor.arena.obstacles[0].shape, Tool::Block.shape_at(0.25, 0.25));
+        assert_eq!(editor.selected(), Some(0));
+
+        // Drag it right by a tenth of the field
+        editor.press(Vec2::new(200.0, 100.0), W, H);
+        editor.drag(Vec2::new(280.0, 101.0), W, H);
+        editor.release();
+        assert_eq!(position(&editor.arena.obstacles[0].shape), (0.35, 0.25));
+
+        editor.undo();
+        assert_eq!(position(&editor.arena.obstacles[0].shape), (0.25, 0.25));
+        editor.undo();
+        assert!(editor.arena.obstacles.is_empty());
+        editor.redo();
+        editor.redo();
+        assert_eq!(position(&editor.arena.obstacles[0].shape), (0.35, 0.25));
+        assert!(editor.is_dirty());
+    }
+
+    #[test]
+    fn handles_resize_and_clicks_delete() {
+        let mut editor = editor();
+        editor.tool = Tool::Bumper;
+        editor.press(Vec2::new(400.0, 100.0), W, H);
+        editor.release();
+        let handle = editor.handle(W, H).unwrap();
+        assert_eq!(handle, Vec2::new(420.0, 100.0));
+        editor.press(handle, W, H);
+        editor.drag(Vec2::new(440.0, 100.0), W, H);
+        editor.release();
+        assert!(matches!(editor.arena.obstacles[0].shape, Shape::Bumper { radius, .. } if (radius - 0.1).abs() < 1e-5));
+
+        editor.resize_selected(-1.0);
+        assert!(matches!(editor.arena.obstacles[0].shape, Shape::Bumper { radius, .. } if radius < 0.1));
+
+        // A click on nothing removes nothing
+        editor.delete_at(Vec2::new(700.0, 350.0), W, H);
+        assert_eq!(editor.arena.obstacles.len(), 1);
+        editor.delete_at(Vec2::new(410.0, 100.0), W, H);
+        assert!(editor.arena.obstacles.is_empty());
+        assert_eq!(editor.selected(), None);
+    }
+
+    #[test]
+    fn portal_exits_move_on_their_own() {
+        let mut editor = editor();
+        editor.tool = Tool::Portal;
+        editor.press(Vec2::new(200.0, 100.0), W, H);
+        editor.release();
+        // The exit is mirrored at (600, 300)
+        editor.press(Vec2::new(600.0, 300.0), W, H);
+        editor.drag(Vec2::new(620.0, 300.0), W, H);
+        editor.release();
+        assert!(matches!(editor.arena.obstacles[0].shape, Shape::Portal { x, to_x, .. } if x == 0.25 && (to_x - 0.775).abs() < 1e-5));
+    }
+
+    #[test]
+    fn motion_cycles_and_new_changes_drop_redo() {
+        let mut editor = editor();
+        editor.press(Vec2::new(200.0, 100.0), W, H);
+        editor.release();
+        editor.cycle_motion();
+        assert_eq!(editor.arena.obstacles[0].motion.map(|motion| motion.dx), Some(MOTION_OFFSET));
+        editor.cycle_motion();
+        assert_eq!(editor.arena.obstacles[0].motion.map(|motion| motion.dy), Some(MOTION_OFFSET));
+        editor.cycle_motion();
+        assert_eq!(editor.arena.obstacles[0].motion, None);
+
+        editor.undo();
+        editor.delete_selected();
+        editor.redo();
+        assert!(editor.arena.obstacles.is_empty());
+
+        // Unsaved changes need a second try to leave
+        assert!(!editor.confirm_leave());
+        assert!(editor.confirm_leave());
+        editor.mark_saved();
+        assert!(editor.confirm_leave());
+    }
+}
@@ -0,0 +1,145 @@
+// Screen shake, racket squash and the ball trail.
+
+use crate::game::physics::Player;
+use ggez::glam::Vec2;
+use std::collections::VecDeque;
+
+// Largest shake offset, in pixels, at full trauma
+const SHAKE_MAX_OFFSET: f32 = 18.0;
+// Trauma lost per second
+const SHAKE_DECAY: f32 = 1.8;
+// How far a racket is squashed at most (fraction of its width)
+const SQUASH_MAX: f32 = 0.45;
+const SQUASH_DECAY: f32 = 4.0;
+// Ball positions kept for the trail, most recent first
+pub const TRAIL_FRAMES: usize = 10;
+
+// Shake driven by a "trauma" level that decays over time. The offset grows with the square of the trauma, so small
+// knocks barely move the screen.
+#[derive(Debug, Clone, Default)]
+pub struct ScreenShake {
+    trauma: f32,
+    time: f32,
+}
+
+impl ScreenShake {
+    pub fn add(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.0);
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.trauma = (self.trauma - SHAKE_DECAY * delta_time).max(0.0);
+        self.time += delta_time;
+    }
+
+    // Offset to draw the playing field at.
+    pub fn offset(&self) -> Vec2 {
+        let amount = SHAKE_MAX_OFFSET * self.trauma * self.trauma;
+        // Two unrelated frequencies look random enough and stay deterministic
+        Vec2::new((self.time * 47.0).sin(), (self.time * 59.0).cos()) * amount
+    }
+
+    pub fn clear(&mut self) {
+        self.trauma = 0.0;
+    }
+}
+
+// Rackets flatten when the ball hits them, then spring back.
+#[derive(Debug, Clone, Default)]
+pub struct RacketSquash {
+    // Indexed by `Player::index`
+    amounts: [f32; Player::COUNT],
+}
+
+impl RacketSquash {
+    // `strength` from 0 to 1
+    pub fn hit(&mut self, player: Player, strength: f32) {
+        let amount = &mut self.amounts[player.index()];
+        *amount = amount.max(strength.clamp(0.0, 1.0));
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        for amount in &mut self.amounts {
+            *amount = (*amount - SQUASH_DECAY * delta_time).max(0.0);
+        }
+    }
+
+    // Scale to draw `player`'s racket with: thinner and a little taller while squashed.
+    pub fn scale(&self, player: Player) -> Vec2 {
+        let amount = self.amounts[player.index()] * SQUASH_MAX;
+        Vec2::new(1.0 - amount, 1.0 + amount * 0.3)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// Recent ball positions.
+#[derive(Debug, Clone, Default)]
+pub struct Trail {
+    positions: VecDeque<Vec2>,
+}
+
+impl Trail {
+    pub fn record(&mut self, position: Vec2) {
+        if self.positions.len() == TRAIL_FRAMES {
+            self.positions.pop_back();
+        }
+        self.positions.push_front(position);
+    }
+
+    // Past positions (the current one excluded) with the opacity of the ghost drawn there.
+    pub fn ghosts(&self, persistence: f32) -> impl Iterator<Item = (Vec2, f32)> + '_ {
+        self.positions.iter().skip(1).copied().zip(trail_alphas(persistence))
+    }
+}
+
+// Opacity of each trail ghost, from the most recent one.
+fn trail_alphas(persistence: f32) -> impl Iterator<Item = f32> {
+    (1..TRAIL_FRAMES as i32).map(move |age| persistence.powi(age)).take_while(|alpha| *alpha > 0.02)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shake_decays() {
+        let mut shake = ScreenShake::default();
+        assert_eq!(shake.offset(), Vec2::ZERO);
+        shake.add(0.8);
+        shake.update(0.01);
+        assert!(shake.offset().length() > 0.0);
+        shake.add(5.0);
+        assert_eq!(shake.trauma, 1.0);
+        shake.update(1.0);
+        assert_eq!(shake.offset(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn squash_springs_back() {
+        let mut squash = RacketSquash::default();
+        squash.hit(Player::Left, 1.0);
+        let scale = squash.scale(Player::Left);
+        assert!(scale.x < 1.0 && scale.y > 1.0);
+        assert_eq!(squash.scale(Player::Right), Vec2::ONE);
+        squash.update(1.0);
+        assert_eq!(squash.scale(Player::Left), Vec2::ONE);
+    }
+
+    #[test]
+    fn trail_fades_out() {
+        let alphas: Vec<f32> = trail_alphas(0.5).collect();
+        assert_eq!(alphas[..3], [0.5, 0.25, 0.125]);
+        assert!(alphas.windows(2).all(|pair| pair[1] < pair[0]));
+        assert_eq!(trail_alphas(0.0).count(), 0);
+
+        let mut trail = Trail::default();
+        for x in 0..20 {
+            trail.record(Vec2::new(x as f32, 0.0));
+        }
+        let ghosts: Vec<(Vec2, f32)> = trail.ghosts(0.5).collect();
+        assert_eq!(ghosts[0], (Vec2::new(18.0, 0.0), 0.5));
+    }
+}
@@ -0,0 +1,271 @@
+// Visual feedback ("juice") for game events: hit sparks, wall-bounce dust, goal explosions, screen shake, racket
+// squash and the ball trail.
+//
+// Everything here is plain state updated from `GameEvent`s and the frame time, so it runs without a window; the UI
+// only reads it back to draw. The intensity (0 to 1) scales particle counts, shake and squash; 0 turns them off.
+
+pub mod juice;
+pub mod particles;
+
+use crate::audio::cue;
+use crate::game::events::{GameEvent, GameEventListener};
+use crate::game::physics::Player;
+use ggez::glam::Vec2;
+use juice::{RacketSquash, ScreenShake, Trail};
+use particles::ParticleSystem;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::f32::consts::FRAC_PI_2;
+
+pub const DEFAULT_INTENSITY: f32 = 1.0;
+// Trauma added by a goal, and by the fastest possible hit
+const GOAL_SHAKE: f32 = 0.6;
+const HIT_SHAKE: f32 = 0.25;
+
+pub struct Effects {
+    intensity: f32,
+    arena: Vec2,
+    particles: ParticleSystem,
+    shake: ScreenShake,
+    squash: RacketSquash,
+    // One trail per ball in play
+    trails: Vec<Trail>,
+    rng: StdRng,
+}
+
+impl Effects {
+    pub fn new(intensity: f32) -> Self {
+        Self::with_rng(intensity, StdRng::from_os_rng())
+    }
+
+    fn with_rng(intensity: f32, rng: StdRng) -> Self {
+        Self {
+            intensity: intensity.clamp(0.0, 1.0),
+            arena: Vec2::ZERO,
+            particles: ParticleSystem::new(),
+            shake: ScreenShake::default(),
+            squash: RacketSquash::default(),
+            trails: Vec::new(),
+            rng,
+        }
+    }
+
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity.clamp(0.0, 1.0);
+        if self.intensity == 0.0 {
+            self.particles.clear();
+            self.shake.clear();
+            self.squash.clear();
+        }
+    }
+
+    pub fn set_arena(&mut self, width: f32, height: f32) {
+        self.arena = Vec2::new(width, height);
+    }
+
+    // Forget everything from the previous match.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.shake.clear();
+        self.squash.clear();
+        self.trails.clear();
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.particles.update(delta_time);
+        self.shake.update(delta_time);
+        self.squash.update(delta_time);
+    }
+
+    // Called once per frame with the position of every ball in play, for the trails.
+    pub fn record_balls(&mut self, positions: impl ExactSizeIterator<Item = Vec2>) {
+        self.trails.resize_with(positions.len(), Trail::default);
+        for (trail, position) in self.trails.iter_mut().zip(positions) {
+            trail.record(position);
+        }
+    }
+
+    pub fn particles(&self) -> &ParticleSystem {
+        &self.particles
+    }
+
+    pub fn trails(&self) -> &[Trail] {
+        &self.trails
+    }
+
+    pub fn shake_offset(&self) -> Vec2 {
+        self.shake.offset()
+    }
+
+    pub fn racket_scale(&self, player: Player) -> Vec2 {
+        self.squash.scale(player)
+    }
+}
+
+impl GameEventListener for Effects {
+    fn on_event(&mut self, event: &GameEvent) {
+        match *event {
+            // A new serve starts a new trail instead of streaking across the field
+            GameEvent::Serve { .. } => self.trails.clear(),
+            GameEvent::BallHitRacket { player, position, speed, .. } => {
+                let strength = cue::intensity(speed);
+                // Sparks follow the ball away from the racket
+                let inward = player.inward();
+                let direction = inward.y.atan2(inward.x);
+                self.particles
+                    .emit(position, &particles::sparks(direction, strength, self.intensity), &mut self.rng);
+                self.squash.hit(player, (0.4 + 0.6 * strength) * self.intensity);
+                self.shake.add(HIT_SHAKE * strength * self.intensity);
+            }
+            GameEvent::WallBounce { position, speed } => {
+                let direction = if position.y < self.arena.y / 2.0 { FRAC_PI_2 } else { -FRAC_PI_2 };
+                let burst = particles::dust(direction, cue::intensity(speed), self.intensity);
+                self.particles.emit(position, &burst, &mut self.rng);
+            }
+            GameEvent::ObstacleHit {
+                position,
+                normal,
+                speed,
+                bumper,
+            } => {
+                let direction = normal.y.atan2(normal.x);
+                let burst = if bumper {
+                    particles::sparks(direction, cue::intensity(speed), self.intensity)
+                } else {
+                    particles::dust(direction, cue::intensity(speed), self.intensity)
+                };
+                self.particles.emit(position, &burst, &mut self.rng);
+            }
+            GameEvent::BrickBroken { owner, position, speed } => {
+                // Debris flies back into the field
+                let inward = owner.inward();
+                let burst = particles::sparks(inward.y.atan2(inward.x), cue::intensity(speed).max(0.5), self.intensity);
+                self.particles.emit(position, &burst, &mut self.rng);
+                self.shake.add(HIT_SHAKE * self.intensity);
+            }
+            GameEvent::BallTeleported { from, to } => {
+                // The trails would stretch across the field
+                self.trails.clear();
+                for position in [from, to] {
+                    self.particles.emit(position, &particles::flash(0.5, self.intensity), &mut self.rng);
+                }
+            }
+            GameEvent::BallsCollided { position, speed } => {
+                self.particles
+                    .emit(position, &particles::flash(cue::intensity(speed), self.intensity), &mut self.rng);
+            }
+            GameEvent::BallSpawned { position } | GameEvent::PowerUpCollected { position, .. } => {
+                self.particles.emit(position, &particles::flash(1.0, self.intensity), &mut self.rng);
+            }
+            GameEvent::PointScored { position, .. } => {
+                // The remaining balls move up in the list: start their trails afresh rather than swap them
+                self.trails.clear();
+                // The ball is already past the edge: explode where it crossed it
+                let origin = position.clamp(Vec2::ZERO, self.arena.max(Vec2::ZERO));
+                self.particles.emit(origin, &particles::explosion(self.intensity), &mut self.rng);
+                self.shake.add(GOAL_SHAKE * self.intensity);
+            }
+            GameEvent::PowerUpSpawned { position, .. } => {
+                self.particles.emit(position, &particles::flash(0.2, self.intensity), &mut self.rng);
+            }
+            GameEvent::RacketMoved { .. } | GameEvent::PowerUpExpired { .. } | GameEvent::MatchWon { .. } => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::simulation::Simulation;
+    use crate::player::player_type::PlayerType;
+    use particles::ParticleKind;
+    use std::collections::HashSet;
+
+    fn effects(intensity: f32) -> Effects {
+        let mut effects = Effects::with_rng(intensity, StdRng::seed_from_u64(5));
+        effects.set_arena(1280.0, 720.0);
+        effects
+    }
+
+    fn count(effects: &Effects, kind: ParticleKind) -> usize {
+        effects.particles().particles().iter().filter(|particle| particle.kind == kind).count()
+    }
+
+    #[test]
+    fn events_spawn_effects() {
+        let mut effects = effects(1.0);
+        effects.on_event(&GameEvent::BallHitRacket {
+            player: Player::Right,
+            position: Vec2::new(1200.0, 300.0),
+            speed: 2500.0,
+            offset: 0.0,
+        });
+        assert!(count(&effects, ParticleKind::Spark) > 0);
+        assert!(effects.racket_scale(Player::Right).x < 1.0);
+
+        effects.on_event(&GameEvent::WallBounce {
+            position: Vec2::new(600.0, 0.0),
+            speed: 750.0,
+        });
+        // Dust off the top wall falls into the field
+        assert!(
+            effects
+                .particles()
+                .particles()
+                .iter()
+                .filter(|particle| particle.kind == ParticleKind::Dust)
+                .all(|dust| dust.velocity.y > 0.0)
+        );
+
+        effects.on_event(&GameEvent::PointScored {
+            scorer: Player::Left,
+            position: Vec2::new(1300.0, 400.0),
+            left_score: 1,
+            right_score: 0,
+            rally_over: true,
+        });
+        assert!(count(&effects, ParticleKind::Explosion) > 0);
+        assert!(effects.particles().particles().iter().all(|particle| particle.position.x <= 1280.0));
+        effects.update(1.0 / 60.0);
+        assert!(effects.shake_offset().length() > 0.0);
+    }
+
+    #[test]
+    fn zero_intensity_turns_effects_off() {
+        let mut effects = effects(0.0);
+        effects.on_event(&GameEvent::PointScored {
+            scorer: Player::Right,
+            position: Vec2::new(-20.0, 100.0),
+            left_score: 0,
+            right_score: 1,
+            rally_over: true,
+        });
+        effects.update(1.0 / 60.0);
+        assert!(effects.particles().particles().is_empty());
+        assert_eq!(effects.shake_offset(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn effects_follow_a_simulated_match() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIHard, PlayerType::AIHard, 2, Some(4));
+        let mut effects = effects(1.0);
+        let keys = HashSet::new();
+        let mut most_particles = 0;
+        while simulation.winner().is_none() {
+            for event in simulation.step(1.0 / 60.0, &keys) {
+                effects.on_event(&event);
+            }
+            effects.update(1.0 / 60.0);
+            most_particles = most_particles.max(effects.particles().particles().len());
+        }
+        assert!(most_particles > 0);
+        for _ in 0..120 {
+            effects.update(1.0 / 60.0);
+        }
+        assert!(effects.particles().particles().is_empty());
+    }
+}
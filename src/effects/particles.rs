@@ -0,0 +1,204 @@
+// A small CPU particle system: particles only have a position, a velocity and a lifetime, so they can be updated
+// (and tested) without a window. The UI decides how each kind looks.
+
+use ggez::glam::Vec2;
+use rand::Rng;
+use std::f32::consts::{PI, TAU};
+
+// Oldest particles are dropped beyond this
+pub const MAX_PARTICLES: usize = 600;
+// Fraction of its velocity a particle keeps after one second
+const DRAG: f32 = 0.08;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticleKind {
+    // Racket hits
+    Spark,
+    // Wall bounces
+    Dust,
+    // Goals
+    Explosion,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particle {
+    pub kind: ParticleKind,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub size: f32,
+    // Seconds left, out of `lifetime`
+    pub life: f32,
+    pub lifetime: f32,
+}
+
+impl Particle {
+    // From 1 when the particle is born to 0 when it dies.
+    pub fn remaining(&self) -> f32 {
+        (self.life / self.lifetime).clamp(0.0, 1.0)
+    }
+}
+
+// How a burst of particles leaves its origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Burst {
+    pub kind: ParticleKind,
+    pub count: usize,
+    // Angle of the burst's center, in radians (0 points right, PI / 2 down)
+    pub direction: f32,
+    // Total angle the particles are spread over
+    pub spread: f32,
+    pub speed: (f32, f32),
+    pub size: (f32, f32),
+    pub lifetime: (f32, f32),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+}
+
+impl ParticleSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    pub fn emit(&mut self, origin: Vec2, burst: &Burst, rng: &mut impl Rng) {
+        for _ in 0..burst.count {
+            let angle = burst.direction + (rng.random::<f32>() - 0.5) * burst.spread;
+            let lifetime = between(burst.lifetime, rng);
+            self.particles.push(Particle {
+                kind: burst.kind,
+                position: origin,
+                velocity: Vec2::from_angle(angle) * between(burst.speed, rng),
+                size: between(burst.size, rng),
+                life: lifetime,
+                lifetime,
+            });
+        }
+        if self.particles.len() > MAX_PARTICLES {
+            let excess = self.particles.len() - MAX_PARTICLES;
+            self.particles.drain(..excess);
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        let drag = DRAG.powf(delta_time);
+        for particle in &mut self.particles {
+            particle.position += particle.velocity * delta_time;
+            particle.velocity *= drag;
+            particle.life -= delta_time;
+        }
+        self.particles.retain(|particle| particle.life > 0.0);
+    }
+}
+
+fn between((low, high): (f32, f32), rng: &mut impl Rng) -> f32 {
+    low + (high - low) * rng.random::<f32>()
+}
+
+// Sparks thrown the way the ball leaves the racket; `strength` (0 to 1) is how fast the ball goes.
+pub fn sparks(direction: f32, strength: f32, intensity: f32) -> Burst {
+    Burst {
+        kind: ParticleKind::Spark,
+        count: scaled(6.0 + 18.0 * strength, intensity),
+        direction,
+        spread: PI / 2.0,
+        speed: (200.0, 400.0 + 500.0 * strength),
+        size: (3.0, 6.0),
+        lifetime: (0.15, 0.35 + 0.2 * strength),
+    }
+}
+
+// A puff of dust off a wall; `direction` points away from it.
+pub fn dust(direction: f32, strength: f32, intensity: f32) -> Burst {
+    Burst {
+        kind: ParticleKind::Dust,
+        count: scaled(4.0 + 6.0 * strength, intensity),
+        direction,
+        spread: PI * 0.8,
+        speed: (40.0, 160.0),
+        size: (4.0, 9.0),
+        lifetime: (0.3, 0.6),
+    }
+}
+
+// A small flash all around, where balls collide or a new ball appears; `strength` from 0 to 1.
+pub fn flash(strength: f32, intensity: f32) -> Burst {
+    Burst {
+        kind: ParticleKind::Spark,
+        count: scaled(8.0 + 8.0 * strength, intensity),
+        direction: 0.0,
+        spread: TAU,
+        speed: (100.0, 250.0 + 250.0 * strength),
+        size: (2.0, 5.0),
+        lifetime: (0.15, 0.4),
+    }
+}
+
+pub fn explosion(intensity: f32) -> Burst {
+    Burst {
+        kind: ParticleKind::Explosion,
+        count: scaled(60.0, intensity),
+        direction: 0.0,
+        spread: TAU,
+        speed: (150.0, 700.0),
+        size: (4.0, 10.0),
+        lifetime: (0.4, 0.9),
+    }
+}
+
+fn scaled(count: f32, intensity: f32) -> usize {
+    (count * intensity).round() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn particles_move_slow_down_and_die() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut system = ParticleSystem::new();
+        system.emit(Vec2::ZERO, &sparks(0.0, 1.0, 1.0), &mut rng);
+        let count = system.particles().len();
+        assert_eq!(count, 24);
+
+        let speed = |system: &ParticleSystem| system.particles()[0].velocity.length();
+        let before = speed(&system);
+        system.update(0.1);
+        assert!(speed(&system) < before);
+        // Sparks fly to the right, within a quarter turn of straight ahead
+        assert!(system.particles().iter().all(|particle| particle.position.x > 0.0));
+
+        system.update(1.0);
+        assert!(system.particles().is_empty());
+    }
+
+    #[test]
+    fn intensity_scales_bursts() {
+        assert_eq!(explosion(1.0).count, 60);
+        assert_eq!(explosion(0.5).count, 30);
+        assert_eq!(explosion(0.0).count, 0);
+        assert!(sparks(0.0, 1.0, 1.0).count > sparks(0.0, 0.0, 1.0).count);
+    }
+
+    #[test]
+    fn oldest_particles_are_dropped() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut system = ParticleSystem::new();
+        for _ in 0..20 {
+            system.emit(Vec2::ZERO, &explosion(1.0), &mut rng);
+        }
+        assert_eq!(system.particles().len(), MAX_PARTICLES);
+    }
+}
@@ -0,0 +1,515 @@
+// Arenas: the obstacles placed in the field.
+//
+// A few arenas are built in. More can be added as level files, JSON documents in the `arenas` folder of the user
+// data directory:
+//
+// { "name": "Gates", "obstacles": [
+//     { "type": "block", "x": 0.5, "y": 0.2, "width": 0.04, "height": 0.2, "motion": { "dx": 0.0, "dy": 0.1, "period": 4 } },
+//     { "type": "bumper", "x": 0.3, "y": 0.5, "radius": 0.05 },
+//     { "type": "portal", "x": 0.35, "y": 0.2, "to_x": 0.65, "to_y": 0.8, "radius": 0.04 },
+//     { "type": "curve", "x": 0.5, "y": 0.5, "radius": 0.3, "from": 200, "to": 340, "thickness": 0.02 } ] }
+//
+// Positions (`x`, `y`, `to_x`, `to_y`) are fractions of the field's width and height, so arenas fit any window.
+// Sizes (`width`, `height`, `radius`, `thickness`) and motion offsets are fractions of the field's height, which
+// keeps bumpers round. Curve angles are in degrees, clockwise from the right. An obstacle with a `motion` slides back
+// and forth by (`dx`, `dy`) every `period` seconds.
+
+use ggez::glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const ARENAS_DIR: &str = "arenas";
+pub const DEFAULT_ARENA: &str = "open";
+pub const BUILT_IN_ARENAS: [&str; 5] = ["open", "pillars", "bumpers", "portals", "crescents"];
+// Obstacles kept clear of the middle of the field, where balls are served from (fraction of the field's height)
+const SERVE_CLEARANCE: f32 = 0.06;
+
+#[derive(Debug)]
+pub enum ArenaError {
+    Io(io::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArenaError::Io(error) => write!(f, "I/O error: {}", error),
+            ArenaError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<io::Error> for ArenaError {
+    fn from(error: io::Error) -> Self {
+        ArenaError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Shape {
+    // A solid rectangle the ball bounces off
+    Block {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+    // A round bumper that kicks the ball away faster
+    Bumper {
+        x: f32,
+        y: f32,
+        radius: f32,
+    },
+    // Balls entering the portal at (x, y) come out at (to_x, to_y), going the same way
+    Portal {
+        x: f32,
+        y: f32,
+        to_x: f32,
+        to_y: f32,
+        radius: f32,
+    },
+    // A wall bent along a circle around (x, y), from angle `from` to angle `to`
+    Curve {
+        x: f32,
+        y: f32,
+        radius: f32,
+        from: f32,
+        to: f32,
+        thickness: f32,
+    },
+}
+
+// Back-and-forth movement of an obstacle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Motion {
+    #[serde(default)]
+    pub dx: f32,
+    #[serde(default)]
+    pub dy: f32,
+    // Seconds to go there and back
+    pub period: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Obstacle {
+    #[serde(flatten)]
+    pub shape: Shape,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motion: Option<Motion>,
+}
+
+// An obstacle placed in a field of a given size, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Body {
+    Block {
+        center: Vec2,
+        half_size: Vec2,
+    },
+    Bumper {
+        center: Vec2,
+        radius: f32,
+    },
+    Portal {
+        center: Vec2,
+        radius: f32,
+        exit: Vec2,
+    },
+    // Angles in radians, `from` below `to`
+    Curve {
+        center: Vec2,
+        radius: f32,
+        from: f32,
+        to: f32,
+        half_thickness: f32,
+    },
+}
+
+impl Obstacle {
+    pub fn fixed(shape: Shape) -> Self {
+        Self { shape, motion: None }
+    }
+
+    pub fn moving(shape: Shape, dx: f32, dy: f32, period: f32) -> Self {
+        Self {
+            shape,
+            motion: Some(Motion { dx, dy, period }),
+        }
+    }
+
+    // Where the obstacle is `time` seconds into the match, in a `width` x `height` field.
+    pub fn body(&self, time: f32, width: f32, height: f32) -> Body {
+        let shift = self.motion.map_or(Vec2::ZERO, |motion| {
+            // Eases from the start to the far end and back
+            let phase = (1.0 - (time / motion.period * TAU).cos()) / 2.0;
+            Vec2::new(motion.dx, motion.dy) * height * phase
+        });
+        let point = |x: f32, y: f32| Vec2::new(x * width, y * height) + shift;
+        match self.shape {
+            Shape::Block { x, y, width: w, height: h } => Body::Block {
+                center: point(x, y),
+                half_size: Vec2::new(w, h) * height / 2.0,
+            },
+            Shape::Bumper { x, y, radius } => Body::Bumper {
+                center: point(x, y),
+                radius: radius * height,
+            },
+            Shape::Portal { x, y, to_x, to_y, radius } => Body::Portal {
+                center: point(x, y),
+                radius: radius * height,
+                exit: Vec2::new(to_x * width, to_y * height),
+            },
+            Shape::Curve {
+                x,
+                y,
+                radius,
+                from,
+                to,
+                thickness,
+            } => Body::Curve {
+                center: point(x, y),
+                radius: radius * height,
+                from: from.to_radians(),
+                to: to.to_radians(),
+                half_thickness: thickness * height / 2.0,
+            },
+        }
+    }
+
+    // Why the obstacle cannot be played with, if it cannot.
+    fn problem(&self) -> Option<String> {
+        let inside = |value: f32| (0.0..=1.0).contains(&value);
+        let positive = |value: f32| value > 0.0 && value.is_finite();
+        match self.shape {
+            Shape::Block { x, y, width, height } => {
+                if !inside(x) || !inside(y) {
+                    return Some("a block lies outside the field".to_string());
+                }
+                if !positive(width) || !positive(height) {
+                    return Some("a block needs a positive width and height".to_string());
+                }
+            }
+            Shape::Bumper { x, y, radius } => {
+                if !inside(x) || !inside(y) {
+                    return Some("a bumper lies outside the field".to_string());
+                }
+                if !positive(radius) {
+                    return Some("a bumper needs a positive radius".to_string());
+                }
+            }
+            Shape::Portal { x, y, to_x, to_y, radius } => {
+                if !inside(x) || !inside(y) || !inside(to_x) || !inside(to_y) {
+                    return Some("a portal lies outside the field".to_string());
+                }
+                if !positive(radius) {
+                    return Some("a portal needs a positive radius".to_string());
+                }
+            }
+            Shape::Curve {
+                x,
+                y,
+                radius,
+                from,
+                to,
+                thickness,
+            } => {
+                if !inside(x) || !inside(y) {
+                    return Some("a curve is centered outside the field".to_string());
+                }
+                if !positive(radius) || !positive(thickness) {
+                    return Some("a curve needs a positive radius and thickness".to_string());
+                }
+                if from >= to || to - from > 360.0 {
+                    return Some("a curve's angles must go up from `from` to `to`, at most 360 degrees apart".to_string());
+                }
+            }
+        }
+        if let Some(motion) = self.motion
+            && !positive(motion.period)
+        {
+            return Some("a moving obstacle needs a positive period".to_string());
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Arena {
+    pub name: String,
+    #[serde(default)]
+    pub obstacles: Vec<Obstacle>,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::open()
+    }
+}
+
+impl Arena {
+    // The classic empty field.
+    pub fn open() -> Self {
+        Self {
+            name: "Open".to_string(),
+            obstacles: Vec::new(),
+        }
+    }
+
+    // Two blocks sliding up and down in each half.
+    pub fn pillars() -> Self {
+        let block = |x: f32, y: f32| Shape::Block {
+            x,
+            y,
+            width: 0.04,
+            height: 0.18,
+        };
+        Self {
+            name: "Pillars".to_string(),
+            obstacles: vec![
+                Obstacle::moving(block(0.3, 0.2), 0.0, 0.4, 5.0),
+                Obstacle::moving(block(0.7, 0.8), 0.0, -0.4, 5.0),
+                Obstacle::fixed(block(0.5, 0.12)),
+                Obstacle::fixed(block(0.5, 0.88)),
+            ],
+        }
+    }
+
+    // Bumpers around the middle of the field.
+    pub fn bumpers() -> Self {
+        let bumper = |x: f32, y: f32, radius: f32| Obstacle::fixed(Shape::Bumper { x, y, radius });
+        Self {
+            name: "Bumpers".to_string(),
+            obstacles: vec![
+                bumper(0.5, 0.22, 0.06),
+                bumper(0.5, 0.78, 0.06),
+                bumper(0.33, 0.4, 0.045),
+                bumper(0.67, 0.6, 0.045),
+            ],
+        }
+    }
+
+    // Two portals sending the ball across the field, and a bumper drifting between them.
+    pub fn portals() -> Self {
+        Self {
+            name: "Portals".to_string(),
+            obstacles: vec![
+                Obstacle::fixed(Shape::Portal {
+                    x: 0.35,
+                    y: 0.2,
+                    to_x: 0.65,
+                    to_y: 0.8,
+                    radius: 0.045,
+                }),
+                Obstacle::fixed(Shape::Portal {
+                    x: 0.65,
+                    y: 0.2,
+                    to_x: 0.35,
+                    to_y: 0.8,
+                    radius: 0.045,
+                }),
+                Obstacle::moving(
+                    Shape::Bumper {
+                        x: 0.3,
+                        y: 0.35,
+                        radius: 0.035,
+                    },
+                    0.4,
+                    0.0,
+                    7.0,
+                ),
+            ],
+        }
+    }
+
+    // Curved walls above and below the middle of the field, hollow side in.
+    pub fn crescents() -> Self {
+        let curve = |from: f32, to: f32| {
+            Obstacle::fixed(Shape::Curve {
+                x: 0.5,
+                y: 0.5,
+                radius: 0.3,
+                from,
+                to,
+                thickness: 0.025,
+            })
+        };
+        Self {
+            name: "Crescents".to_string(),
+            obstacles: vec![curve(235.0, 305.0), curve(55.0, 125.0)],
+        }
+    }
+
+    pub fn built_in(id: &str) -> Option<Self> {
+        match id {
+            "open" => Some(Self::open()),
+            "pillars" => Some(Self::pillars()),
+            "bumpers" => Some(Self::bumpers()),
+            "portals" => Some(Self::portals()),
+            "crescents" => Some(Self::crescents()),
+            _ => None,
+        }
+    }
+
+    // Every obstacle as placed `time` seconds into the match, in a `width` x `height` field.
+    pub fn bodies(&self, time: f32, width: f32, height: f32) -> Vec<Body> {
+        self.obstacles.iter().map(|obstacle| obstacle.body(time, width, height)).collect()
+    }
+
+    // Why the arena cannot be played in, if it cannot: a broken obstacle, or one on the serving spot.
+    pub fn problem(&self) -> Option<String> {
+        if let Some(problem) = self.obstacles.iter().find_map(Obstacle::problem) {
+            return Some(problem);
+        }
+        // Measured on a square field, with every obstacle where it starts and where it ends up
+        let center = Vec2::splat(0.5);
+        for obstacle in &self.obstacles {
+            let period = obstacle.motion.map_or(0.0, |motion| motion.period);
+            for time in [0.0, period / 2.0] {
+                if covers(&obstacle.body(time, 1.0, 1.0), center, SERVE_CLEARANCE) {
+                    return Some("an obstacle covers the middle of the field, where balls are served".to_string());
+                }
+            }
+        }
+        None
+    }
+}
+
+// Whether a `body` reaches within `margin` of `point`. Portals never block anything.
+fn covers(body: &Body, point: Vec2, margin: f32) -> bool {
+    match *body {
+        Body::Block { center, half_size } => {
+            let distance = ((point - center).abs() - half_size).max(Vec2::ZERO);
+            distance.length() < margin
+        }
+        Body::Bumper { center, radius } => point.distance(center) < radius + margin,
+        Body::Portal { .. } => false,
+        Body::Curve {
+            center,
+            radius,
+            from,
+            to,
+            half_thickness,
+        } => {
+            let offset = point - center;
+            let angle = arc_angle(offset, from);
+            if angle <= to {
+                (offset.length() - radius).abs() < half_thickness + margin
+            } else {
+                [from, to]
+                    .into_iter()
+                    .any(|end| point.distance(center + Vec2::from_angle(end) * radius) < half_thickness + margin)
+            }
+        }
+    }
+}
+
+// The angle of `offset` brought into the turn starting at `from`, so a curve covers it when it is at most `to`.
+pub fn arc_angle(offset: Vec2, from: f32) -> f32 {
+    from + (offset.y.atan2(offset.x) - from).rem_euclid(TAU)
+}
+
+pub fn parse_arena_file(json: &[u8]) -> Result<Arena, ArenaError> {
+    let arena: Arena = serde_json::from_slice(json).map_err(|error| ArenaError::Invalid(error.to_string()))?;
+    match arena.problem() {
+        Some(problem) => Err(ArenaError::Invalid(problem)),
+        None => Ok(arena),
+    }
+}
+
+// Built-in arena ids followed by the level files found in `directory`.
+pub fn available_arenas(directory: &Path) -> Vec<String> {
+    let mut files: Vec<String> = fs::read_dir(directory)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json")))
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    BUILT_IN_ARENAS.iter().map(|id| id.to_string()).chain(files).collect()
+}
+
+// The built-in arena `id`, or the level file `id` in `directory`.
+pub fn load_arena(directory: &Path, id: &str) -> Result<Arena, ArenaError> {
+    match Arena::built_in(id) {
+        Some(arena) => Ok(arena),
+        None => parse_arena_file(&fs::read(directory.join(id))?),
+    }
+}
+
+// Write `arena` as the level file `file` in `directory`, refusing arenas that could not be loaded back.
+pub fn save_arena(directory: &Path, file: &str, arena: &Arena) -> Result<(), ArenaError> {
+    if let Some(problem) = arena.problem() {
+        return Err(ArenaError::Invalid(problem));
+    }
+    let json = serde_json::to_string_pretty(arena).map_err(|error| ArenaError::Invalid(error.to_string()))?;
+    fs::create_dir_all(directory)?;
+    let path = directory.join(file);
+    let temporary_path = path.with_extension("tmp");
+    fs::write(&temporary_path, json)?;
+    fs::rename(&temporary_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_arenas_are_playable() {
+        for id in BUILT_IN_ARENAS {
+            let arena = Arena::built_in(id).unwrap();
+            assert_eq!(arena.problem(), None, "{}", id);
+        }
+        assert_eq!(Arena::built_in("maze"), None);
+    }
+
+    #[test]
+    fn level_files_round_trip() {
+        let json = br#"{ "name": "Gates", "obstacles": [
+            { "type": "block", "x": 0.5, "y": 0.2, "width": 0.04, "height": 0.2, "motion": { "dy": 0.1, "period": 4 } },
+            { "type": "curve", "x": 0.5, "y": 1.0, "radius": 0.2, "from": 200, "to": 340, "thickness": 0.02 } ] }"#;
+        let arena = parse_arena_file(json).unwrap();
+        assert_eq!(arena.obstacles.len(), 2);
+        assert_eq!(arena.obstacles[0].motion, Some(Motion { dx: 0.0, dy: 0.1, period: 4.0 }));
+        let saved = serde_json::to_vec(&arena).unwrap();
+        assert_eq!(parse_arena_file(&saved).unwrap(), arena);
+
+        assert!(matches!(
+            parse_arena_file(br#"{ "name": "Bad", "obstacles": [{ "type": "wormhole" }] }"#),
+            Err(ArenaError::Invalid(_))
+        ));
+        let blocked = br#"{ "name": "Blocked", "obstacles": [{ "type": "bumper", "x": 0.5, "y": 0.5, "radius": 0.1 }] }"#;
+        assert!(matches!(parse_arena_file(blocked), Err(ArenaError::Invalid(_))));
+
+        let directory = std::env::temp_dir().join(format!("rust-pong-arenas-{}", std::process::id()));
+        save_arena(&directory, "gates.json", &arena).unwrap();
+        assert_eq!(load_arena(&directory, "gates.json").unwrap(), arena);
+        assert!(available_arenas(&directory).contains(&"gates.json".to_string()));
+        let mut blocked = arena.clone();
+        blocked.obstacles.push(Obstacle::fixed(Shape::Bumper { x: 0.5, y: 0.5, radius: 0.1 }));
+        assert!(matches!(save_arena(&directory, "blocked.json", &blocked), Err(ArenaError::Invalid(_))));
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn moving_obstacles_slide_there_and_back() {
+        let obstacle = Obstacle::moving(Shape::Bumper { x: 0.25, y: 0.5, radius: 0.05 }, 0.5, 0.0, 4.0);
+        let center = |time: f32| match obstacle.body(time, 800.0, 400.0) {
+            Body::Bumper { center, radius } => {
+                assert_eq!(radius, 20.0);
+                center
+            }
+            other => panic!("expected a bumper, got {:?}", other),
+        };
+        assert_eq!(center(0.0), Vec2::new(200.0, 200.0));
+        assert!((center(2.0) - Vec2::new(400.0, 200.0)).length() < 0.01);
+        assert!((center(4.0) - Vec2::new(200.0, 200.0)).length() < 0.01);
+    }
+}
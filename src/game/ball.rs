@@ -1,3 +1,4 @@
+use crate::game::physics::Player;
 use ggez::{Context, GameResult, glam::Vec2, graphics};
 use rand::Rng;
 
@@ -10,47 +11,56 @@ pub struct Ball {
     pub position: Vec2,
     pub velocity: Vec2,
     pub speed: f32,
-    ball_mesh: graphics::Mesh,
+    // The last racket to touch the ball in this rally
+    pub last_hitter: Option<Player>,
+    // Set while the ball sticks to a sticky racket (power-up)
+    pub stuck: Option<Stuck>,
 }
 
-pub fn randomize_velocity(vector: &mut Vec2, x: f32, y: f32) {
-    let mut random_thread = rand::rng();
-    vector.x = match random_thread.random_bool(0.5) {
+// A ball held by a sticky racket. Its velocity is already the one it leaves with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stuck {
+    pub player: Player,
+    // Where it sticks along the racket, from its center, in the racket's frame
+    pub along: f32,
+    // Seconds before it leaves
+    pub remaining: f32,
+}
+
+pub fn randomize_velocity(vector: &mut Vec2, x: f32, y: f32, rng: &mut impl Rng) {
+    vector.x = match rng.random_bool(0.5) {
         true => x,
         false => -x,
     };
-    vector.y = match random_thread.random_bool(0.5) {
+    vector.y = match rng.random_bool(0.5) {
         true => y,
         false => -y,
     };
 }
 
 impl Ball {
-    // Draw the ball on the provided canvas.
-    pub fn draw_on_canvas(&self, canvas: &mut graphics::Canvas) {
-        canvas.draw(&self.ball_mesh, graphics::DrawParam::default().dest(self.position));
+    // Build the mesh used to draw any ball; the ball itself holds no graphics state.
+    pub fn build_mesh(context: &mut Context, color: graphics::Color) -> GameResult<graphics::Mesh> {
+        let ball_rectangle = graphics::Rect::new(-BALL_SIZE / 2.0, -BALL_SIZE / 2.0, BALL_SIZE, BALL_SIZE);
+        graphics::Mesh::new_rectangle(context, graphics::DrawMode::fill(), ball_rectangle, color)
     }
 
-    // Reset ball position and speed, and randomize its direction.
-    pub fn reset(&mut self, position_x: f32, position_y: f32) {
-        self.position = Vec2::new(position_x, position_y);
-        self.speed = BALL_SPEED;
-        randomize_velocity(&mut self.velocity, self.speed, self.speed);
-        self.velocity = self.velocity.normalize() * self.speed;
+    // Draw the ball on the provided canvas.
+    pub fn draw_on_canvas(&self, canvas: &mut graphics::Canvas, mesh: &graphics::Mesh) {
+        canvas.draw(mesh, graphics::DrawParam::default().dest(self.position));
     }
 
-    pub fn new(position_x: f32, position_y: f32, context: &mut Context) -> GameResult<Self> {
+    pub fn new(position_x: f32, position_y: f32, rng: &mut impl Rng) -> Self {
         let mut ball_velocity = Vec2::new(0.0, 0.0);
-        randomize_velocity(&mut ball_velocity, BALL_SPEED, BALL_SPEED);
+        randomize_velocity(&mut ball_velocity, BALL_SPEED, BALL_SPEED, rng);
 
-        let ball_rectangle = graphics::Rect::new(-BALL_SIZE / 2.0, -BALL_SIZE / 2.0, BALL_SIZE, BALL_SIZE);
-        let ball_mesh = graphics::Mesh::new_rectangle(context, graphics::DrawMode::fill(), ball_rectangle, graphics::Color::WHITE)?;
-        Ok(Ball {
+        Ball {
             position: Vec2::new(position_x, position_y),
             velocity: ball_velocity.normalize() * BALL_SPEED,
             speed: BALL_SPEED,
-            ball_mesh,
-        })
+            last_hitter: None,
+            stuck: None,
+        }
     }
 
     pub fn move_ball(&mut self, delta_time: f32) {
@@ -0,0 +1,120 @@
+// Pong-Out's brick walls.
+//
+// Each player defends a wall of bricks between their racket and their goal. A ball that gets past a racket breaks
+// the first brick it hits, bounces back into the field and gives a point to the other side. A match is won by
+// breaking every brick of the opponent's wall, or by getting a ball through a gap in it into their goal.
+
+use crate::game::physics::Player;
+use crate::game::serve;
+use ggez::glam::Vec2;
+
+pub const ROWS: usize = 10;
+pub const COLUMNS: usize = 2;
+// Bricks fill the space behind the racket, in pixels: each column is this deep, with gaps between the bricks and
+// along the goal line
+const BRICK_DEPTH: f32 = 12.0;
+const BRICK_GAP: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Brick {
+    pub owner: Player,
+    // Row 0 is at the top, column 0 against the goal line
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Brick {
+    // Center and half size of the brick in a `width` x `height` field.
+    pub fn rect(&self, width: f32, height: f32) -> (Vec2, Vec2) {
+        let row_height = (height - BRICK_GAP) / ROWS as f32;
+        let half_size = Vec2::new(BRICK_DEPTH, row_height - BRICK_GAP) / 2.0;
+        let from_goal = BRICK_GAP + self.column as f32 * (BRICK_DEPTH + BRICK_GAP) + half_size.x;
+        let x = if self.owner == Player::Right { width - from_goal } else { from_goal };
+        let y = BRICK_GAP + self.row as f32 * row_height + half_size.y;
+        (Vec2::new(x, y), half_size)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BrickWalls {
+    bricks: Vec<Brick>,
+    // The player who got a ball into the other goal, which ends the match
+    breached_by: Option<Player>,
+}
+
+impl BrickWalls {
+    // No walls, outside of Pong-Out.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // A full wall in front of the left and right goals.
+    pub fn build() -> Self {
+        let bricks = [Player::Left, Player::Right]
+            .into_iter()
+            .flat_map(|owner| (0..COLUMNS).flat_map(move |column| (0..ROWS).map(move |row| Brick { owner, row, column })))
+            .collect();
+        Self { bricks, breached_by: None }
+    }
+
+    pub fn bricks(&self) -> &[Brick] {
+        &self.bricks
+    }
+
+    pub fn remaining(&self, owner: Player) -> usize {
+        self.bricks.iter().filter(|brick| brick.owner == owner).count()
+    }
+
+    pub fn break_brick(&mut self, index: usize) -> Brick {
+        self.bricks.remove(index)
+    }
+
+    // `scorer` got a ball through a wall.
+    pub fn breach(&mut self, scorer: Player) {
+        self.breached_by.get_or_insert(scorer);
+    }
+
+    // The player who scored a goal, or else whose opponent has no brick left.
+    pub fn winner(&self) -> Option<Player> {
+        self.breached_by.or_else(|| {
+            [Player::Left, Player::Right]
+                .into_iter()
+                .find(|&owner| self.remaining(owner) == 0)
+                .map(serve::opponent)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::racket::{RACKET_OFFSET, RACKET_WIDTH_HALF};
+
+    #[test]
+    fn walls_stand_between_the_rackets_and_the_goals() {
+        let walls = BrickWalls::build();
+        assert_eq!(walls.remaining(Player::Left), ROWS * COLUMNS);
+        assert_eq!(walls.remaining(Player::Right), ROWS * COLUMNS);
+        for brick in walls.bricks() {
+            let (center, half_size) = brick.rect(800.0, 600.0);
+            let from_goal = if brick.owner == Player::Left { center.x } else { 800.0 - center.x };
+            assert!(from_goal - half_size.x > 0.0 && from_goal + half_size.x < RACKET_OFFSET - RACKET_WIDTH_HALF);
+            assert!(center.y - half_size.y > 0.0 && center.y + half_size.y < 600.0);
+        }
+    }
+
+    #[test]
+    fn clearing_a_wall_or_scoring_a_goal_wins() {
+        let mut walls = BrickWalls::build();
+        assert_eq!(walls.winner(), None);
+        while let Some(index) = walls.bricks().iter().position(|brick| brick.owner == Player::Right) {
+            walls.break_brick(index);
+        }
+        assert_eq!(walls.winner(), Some(Player::Left));
+
+        let mut walls = BrickWalls::build();
+        walls.breach(Player::Right);
+        assert_eq!(walls.winner(), Some(Player::Right));
+        assert_eq!(BrickWalls::new().bricks().len(), 0);
+    }
+}
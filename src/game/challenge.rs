@@ -0,0 +1,172 @@
+// Single-player challenges: survival, time attack and wall practice, played by Player 1 on the left.
+//
+// Survival lasts until the player lets a ball through, against an AI whose racket gets faster the longer it goes,
+// and every serve leaves faster too; the score is the time survived. Time attack scores as many goals as possible in
+// 60 seconds against the AI, and is won by ending ahead. Wall practice returns the ball off a solid wall until the
+// player misses it, and is won by reaching 50 returns. Only time with a ball in play counts.
+
+use crate::game::mode::GameMode;
+use crate::game::physics::Player;
+use serde::Serialize;
+
+pub const TIME_ATTACK_SECONDS: f32 = 60.0;
+pub const WALL_PRACTICE_TARGET: u32 = 50;
+// Survival pace: 1% faster per second in play, up to three times the normal speed
+const SURVIVAL_SPEED_UP: f32 = 0.01;
+const SURVIVAL_MAX_PACE: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ChallengeResult {
+    #[serde(skip)]
+    pub mode: GameMode,
+    // Higher is better; see `format_score`
+    pub score: u32,
+    pub won: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChallengeRun {
+    mode: GameMode,
+    // Seconds with a ball in play
+    elapsed: f32,
+    // Balls the player hit back
+    returns: u32,
+    scored: u32,
+    conceded: u32,
+}
+
+impl ChallengeRun {
+    // A run of `mode`, if it is a challenge.
+    pub fn new(mode: GameMode) -> Option<Self> {
+        mode.is_challenge().then_some(Self {
+            mode,
+            elapsed: 0.0,
+            returns: 0,
+            scored: 0,
+            conceded: 0,
+        })
+    }
+
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
+
+    pub fn returns(&self) -> u32 {
+        self.returns
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.elapsed += delta_time;
+    }
+
+    pub fn record_return(&mut self, player: Player) {
+        if player == Player::Left {
+            self.returns += 1;
+        }
+    }
+
+    pub fn record_goal(&mut self, conceded: Player) {
+        if conceded == Player::Left {
+            self.conceded += 1;
+        } else {
+            self.scored += 1;
+        }
+    }
+
+    // Speed of the AI's racket and of serves, relative to normal.
+    pub fn pace(&self) -> f32 {
+        match self.mode {
+            GameMode::Survival => (1.0 + SURVIVAL_SPEED_UP * self.elapsed).min(SURVIVAL_MAX_PACE),
+            _ => 1.0,
+        }
+    }
+
+    // Seconds left of a time attack.
+    pub fn time_left(&self) -> Option<f32> {
+        (self.mode == GameMode::TimeAttack).then(|| (TIME_ATTACK_SECONDS - self.elapsed).max(0.0))
+    }
+
+    // Tenths of a second survived, goals scored in a time attack, or returns off the practice wall.
+    pub fn score(&self) -> u32 {
+        match self.mode {
+            GameMode::Survival => (self.elapsed * 10.0) as u32,
+            GameMode::TimeAttack => self.scored,
+            _ => self.returns,
+        }
+    }
+
+    // How the run ended, once it has.
+    pub fn result(&self) -> Option<ChallengeResult> {
+        let won = match self.mode {
+            GameMode::Survival => (self.conceded > 0).then_some(false),
+            GameMode::TimeAttack => (self.elapsed >= TIME_ATTACK_SECONDS).then_some(self.scored > self.conceded),
+            GameMode::WallPractice if self.returns >= WALL_PRACTICE_TARGET => Some(true),
+            GameMode::WallPractice => (self.conceded > 0).then_some(false),
+            _ => None,
+        }?;
+        Some(ChallengeResult {
+            mode: self.mode,
+            score: self.score(),
+            won,
+        })
+    }
+}
+
+// A challenge score as players read it.
+pub fn format_score(mode: GameMode, score: u32) -> String {
+    match mode {
+        GameMode::Survival => format!("{}.{} s", score / 10, score % 10),
+        GameMode::TimeAttack => format!("{} goals", score),
+        _ => format!("{} returns", score),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn survival_speeds_up_until_a_ball_gets_through() {
+        let mut run = ChallengeRun::new(GameMode::Survival).unwrap();
+        run.update(42.35);
+        assert!((run.pace() - 1.4235).abs() < 1e-4);
+        run.record_goal(Player::Right);
+        assert_eq!(run.result(), None);
+        run.record_goal(Player::Left);
+        let result = run.result().unwrap();
+        assert!(!result.won);
+        assert_eq!(format_score(result.mode, result.score), "42.3 s");
+        assert_eq!(ChallengeRun::new(GameMode::Classic), None);
+    }
+
+    #[test]
+    fn time_attack_is_won_ahead_when_time_runs_out() {
+        let mut run = ChallengeRun::new(GameMode::TimeAttack).unwrap();
+        run.record_goal(Player::Left);
+        run.record_goal(Player::Right);
+        run.record_goal(Player::Right);
+        run.update(TIME_ATTACK_SECONDS - 1.0);
+        assert_eq!(run.result(), None);
+        assert_eq!(run.time_left(), Some(1.0));
+        run.update(1.0);
+        assert_eq!(run.result().map(|result| (result.score, result.won)), Some((2, true)));
+        assert_eq!(run.pace(), 1.0);
+    }
+
+    #[test]
+    fn wall_practice_counts_returns() {
+        let mut run = ChallengeRun::new(GameMode::WallPractice).unwrap();
+        for _ in 0..WALL_PRACTICE_TARGET - 1 {
+            run.record_return(Player::Left);
+        }
+        assert_eq!(run.result(), None);
+        let mut missed = run.clone();
+        missed.record_goal(Player::Left);
+        assert_eq!(
+            missed.result().map(|result| (result.score, result.won)),
+            Some((WALL_PRACTICE_TARGET - 1, false))
+        );
+        run.record_return(Player::Left);
+        assert_eq!(run.result().map(|result| result.won), Some(true));
+    }
+}
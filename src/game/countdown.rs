@@ -0,0 +1,78 @@
+// "3, 2, 1, GO" shown before each serve and when the game resumes from pause.
+//
+// Play is frozen during "3, 2, 1"; "GO" stays on screen for one more step while the ball is already moving.
+
+// Seconds each number (and "GO") is shown
+pub const COUNTDOWN_STEP: f32 = 0.6;
+const LABELS: [&str; 4] = ["3", "2", "1", "GO"];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Countdown {
+    elapsed: f32,
+}
+
+impl Countdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.elapsed += delta_time;
+    }
+
+    fn step(&self) -> usize {
+        (self.elapsed / COUNTDOWN_STEP) as usize
+    }
+
+    // What to show right now, `None` once the countdown is over.
+    pub fn label(&self) -> Option<&'static str> {
+        LABELS.get(self.step()).copied()
+    }
+
+    // How far into the current label we are, from 0 to 1 (used to animate it).
+    pub fn step_progress(&self) -> f32 {
+        (self.elapsed / COUNTDOWN_STEP).fract()
+    }
+
+    // Whether play is still frozen.
+    pub fn is_blocking(&self) -> bool {
+        self.step() < LABELS.len() - 1
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step() >= LABELS.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_down_then_lets_play_start_on_go() {
+        let mut countdown = Countdown::new();
+        let mut labels = Vec::new();
+        let mut frozen_frames = 0;
+        while !countdown.is_finished() {
+            if labels.last() != countdown.label().as_ref() {
+                labels.push(countdown.label().unwrap());
+            }
+            if countdown.is_blocking() {
+                frozen_frames += 1;
+            }
+            countdown.update(0.1);
+        }
+        assert_eq!(labels, LABELS);
+        assert_eq!(countdown.label(), None);
+        // 3 steps of 0.6 s at 10 frames per second
+        assert_eq!(frozen_frames, 18);
+    }
+
+    #[test]
+    fn progress_restarts_with_each_label() {
+        let mut countdown = Countdown::new();
+        countdown.update(COUNTDOWN_STEP * 1.5);
+        assert_eq!(countdown.label(), Some("2"));
+        assert!((countdown.step_progress() - 0.5).abs() < 1e-4);
+    }
+}
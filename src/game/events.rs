@@ -0,0 +1,138 @@
+// Typed game events.
+//
+// The simulation only emits events; everything that reacts to the game (audio, statistics, achievements, the
+// game state machine) implements `GameEventListener` and is handed the events of each step, independently of the others.
+
+use crate::game::physics::Player;
+use crate::game::powerup::PowerUpKind;
+use ggez::glam::Vec2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameEvent {
+    // The ball was put in play, travelling away from `server`
+    Serve {
+        server: Player,
+        speed: f32,
+    },
+    // `speed` is the speed the ball leaves the racket with; `offset` is where it hit, from -1 (top) to 1 (bottom)
+    BallHitRacket {
+        player: Player,
+        position: Vec2,
+        speed: f32,
+        offset: f32,
+    },
+    WallBounce {
+        position: Vec2,
+        speed: f32,
+    },
+    // Two balls bounced off each other at `position` (multi-ball); `speed` is the faster one's
+    BallsCollided {
+        position: Vec2,
+        speed: f32,
+    },
+    // The ball bounced off an arena obstacle facing `normal`; `bumper` when a bumper kicked it away faster
+    ObstacleHit {
+        position: Vec2,
+        normal: Vec2,
+        speed: f32,
+        bumper: bool,
+    },
+    // The ball went through a portal
+    BallTeleported {
+        from: Vec2,
+        to: Vec2,
+    },
+    // The ball broke one of `owner`'s bricks at `position` (Pong-Out), scoring for the other side
+    BrickBroken {
+        owner: Player,
+        position: Vec2,
+        speed: f32,
+    },
+    // An extra ball joined the rally (multi-ball)
+    BallSpawned {
+        position: Vec2,
+    },
+    // A power-up appeared in the field
+    PowerUpSpawned {
+        kind: PowerUpKind,
+        position: Vec2,
+    },
+    // `player` hit the ball that went through the power-up at `position`
+    PowerUpCollected {
+        kind: PowerUpKind,
+        player: Player,
+        position: Vec2,
+    },
+    // A timed power-up collected by `player` ran out
+    PowerUpExpired {
+        kind: PowerUpKind,
+        player: Player,
+    },
+    // Vertical distance a racket moved during the step (only emitted when it moved)
+    RacketMoved {
+        player: Player,
+        distance: f32,
+    },
+    // `position` is where the ball left the field. Scores are the left and right players', or the teams' in 2 vs 2.
+    // Goals that score for nobody (four-player own goals) emit no event. With several balls in play every ball scores
+    // on its own, and `rally_over` is only set once no ball is left.
+    PointScored {
+        scorer: Player,
+        position: Vec2,
+        left_score: u8,
+        right_score: u8,
+        rally_over: bool,
+    },
+    MatchWon {
+        winner: Player,
+        left_score: u8,
+        right_score: u8,
+    },
+}
+
+pub trait GameEventListener {
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+// Events emitted by the simulation, waiting to be handed to listeners.
+#[derive(Debug, Clone, Default)]
+pub struct EventQueue {
+    events: Vec<GameEvent>,
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn emit(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    pub fn drain(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+// Hand every event, in order, to each listener.
+pub fn dispatch(events: &[GameEvent], listeners: &mut [&mut dyn GameEventListener]) {
+    for event in events {
+        for listener in listeners.iter_mut() {
+            listener.on_event(event);
+        }
+    }
+}
+
+// Keeps every event it receives, so tests can assert on the event stream.
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct EventRecorder {
+    pub events: Vec<GameEvent>,
+}
+
+#[cfg(test)]
+impl GameEventListener for EventRecorder {
+    fn on_event(&mut self, event: &GameEvent) {
+        self.events.push(*event);
+    }
+}
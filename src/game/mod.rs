@@ -1,4 +1,15 @@
+pub mod arena;
 pub mod ball;
+pub mod bricks;
+pub mod challenge;
+pub mod countdown;
+pub mod events;
+pub mod mode;
+pub mod multiball;
 pub mod physics;
+pub mod powerup;
 pub mod racket;
 pub mod score;
+pub mod serve;
+pub mod simulation;
+pub mod stats;
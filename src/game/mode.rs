@@ -0,0 +1,240 @@
+// Game modes: classic two-player Pong, four players with a racket on every wall, doubles with a back and a
+// front racket on each side, Pong-Out with a wall of bricks in front of each goal, or one of the single-player
+// challenges (see `challenge`).
+//
+// In the four-wall modes every wall is a goal. In a free-for-all a goal scores for whoever hit the ball last;
+// own goals and untouched serves score for nobody. In teams, Player 1 (left) and Player 3 (top) play against
+// Player 2 (right) and Player 4 (bottom), and a goal scores for the team that did not concede it. Doubles keeps
+// the classic field: Player 1 (left back) and Player 3 (left front) against Player 2 and Player 4 on the right.
+// Pong-Out is classic Pong won on bricks and goals rather than on points (see `bricks`). Survival and time attack
+// pit Player 1 against an AI on the classic field; in wall practice Player 1 plays alone against a solid right wall.
+
+use crate::game::physics::Player;
+use crate::game::score::Score;
+use crate::game::serve;
+
+const CLASSIC_PLAYERS: [Player; 2] = [Player::Left, Player::Right];
+const SOLO_PLAYERS: [Player; 1] = [Player::Left];
+const DOUBLES_PLAYERS: [Player; 4] = [Player::Left, Player::Right, Player::LeftFront, Player::RightFront];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    FreeForAll,
+    Teams,
+    Doubles,
+    PongOut,
+    Survival,
+    TimeAttack,
+    WallPractice,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 8] = [
+        GameMode::Classic,
+        GameMode::FreeForAll,
+        GameMode::Teams,
+        GameMode::Doubles,
+        GameMode::PongOut,
+        GameMode::Survival,
+        GameMode::TimeAttack,
+        GameMode::WallPractice,
+    ];
+    pub const CHALLENGES: [GameMode; 3] = [GameMode::Survival, GameMode::TimeAttack, GameMode::WallPractice];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "Classic",
+            GameMode::FreeForAll => "4-player free-for-all",
+            GameMode::Teams => "2 vs 2",
+            GameMode::Doubles => "Doubles",
+            GameMode::PongOut => "Pong-Out",
+            GameMode::Survival => "Survival",
+            GameMode::TimeAttack => "Time attack",
+            GameMode::WallPractice => "Wall practice",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+            GameMode::FreeForAll => "ffa",
+            GameMode::Teams => "teams",
+            GameMode::Doubles => "doubles",
+            GameMode::PongOut => "pongout",
+            GameMode::Survival => "survival",
+            GameMode::TimeAttack => "time-attack",
+            GameMode::WallPractice => "wall",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<Self> {
+        GameMode::ALL.into_iter().find(|mode| mode.cli_name() == name.to_ascii_lowercase())
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            GameMode::Classic => GameMode::FreeForAll,
+            GameMode::FreeForAll => GameMode::Teams,
+            GameMode::Teams => GameMode::Doubles,
+            GameMode::Doubles => GameMode::PongOut,
+            GameMode::PongOut => GameMode::Survival,
+            GameMode::Survival => GameMode::TimeAttack,
+            GameMode::TimeAttack => GameMode::WallPractice,
+            GameMode::WallPractice => GameMode::Classic,
+        }
+    }
+
+    // Whether matches count for careers and achievements: only classic ones do.
+    pub fn is_ranked(&self) -> bool {
+        *self == GameMode::Classic
+    }
+
+    // Whether this is a single-player challenge, played for a high score.
+    pub fn is_challenge(&self) -> bool {
+        GameMode::CHALLENGES.contains(self)
+    }
+
+    // Whether every wall is a goal; otherwise the top and bottom walls bounce as in classic Pong.
+    pub fn guards_every_wall(&self) -> bool {
+        matches!(self, GameMode::FreeForAll | GameMode::Teams)
+    }
+
+    // Players with a racket in this mode, in serve order.
+    pub fn players(&self) -> &'static [Player] {
+        match self {
+            GameMode::Classic | GameMode::PongOut | GameMode::Survival | GameMode::TimeAttack => &CLASSIC_PLAYERS,
+            GameMode::WallPractice => &SOLO_PLAYERS,
+            GameMode::FreeForAll | GameMode::Teams => &Player::WALLS,
+            GameMode::Doubles => &DOUBLES_PLAYERS,
+        }
+    }
+
+    // Walls the ball scores through.
+    pub fn guarded_walls(&self) -> &'static [Player] {
+        match self {
+            GameMode::FreeForAll | GameMode::Teams => &Player::WALLS,
+            GameMode::WallPractice => &SOLO_PLAYERS,
+            _ => &CLASSIC_PLAYERS,
+        }
+    }
+
+    // Who scores when `conceded` lets the ball through their wall, `last_hitter` being the last racket to touch it.
+    pub fn scorer(&self, conceded: Player, last_hitter: Option<Player>) -> Option<Player> {
+        match self {
+            GameMode::Classic | GameMode::PongOut | GameMode::Survival | GameMode::TimeAttack | GameMode::WallPractice => Some(serve::opponent(conceded)),
+            GameMode::FreeForAll => last_hitter.filter(|&hitter| hitter != conceded),
+            GameMode::Teams | GameMode::Doubles => {
+                let scoring_team = 1 - team(conceded);
+                match last_hitter {
+                    Some(hitter) if team(hitter) == scoring_team => Some(hitter),
+                    _ => Some(captain(scoring_team)),
+                }
+            }
+        }
+    }
+
+    // The first player, or team (reported as its captain), to reach `winning_score`. Pong-Out and the challenges have
+    // no winning score: the bricks, or the challenge's own rules, decide.
+    pub fn winner(&self, score: &Score, winning_score: u8) -> Option<Player> {
+        match self {
+            GameMode::Teams | GameMode::Doubles => (0..2).find(|&team| team_score(score, team) >= winning_score).map(captain),
+            GameMode::PongOut | GameMode::Survival | GameMode::TimeAttack | GameMode::WallPractice => None,
+            _ => self.players().iter().copied().find(|&player| score.get(player) >= winning_score),
+        }
+    }
+
+    // Whether `first` and `second` play against each other.
+    pub fn are_opponents(&self, first: Player, second: Player) -> bool {
+        match self {
+            GameMode::Teams | GameMode::Doubles => team(first) != team(second),
+            _ => first != second,
+        }
+    }
+
+    // Title of the game-over screen.
+    pub fn winner_title(&self, winner: Player) -> String {
+        match self {
+            GameMode::Teams | GameMode::Doubles => format!("Team {} Wins!", team(winner) + 1),
+            _ => format!("{} Wins!", winner.name()),
+        }
+    }
+}
+
+// Team of a player in the team modes: 0 for left, top and left front, 1 for right, bottom and right front.
+pub fn team(player: Player) -> usize {
+    match player {
+        Player::Left | Player::Top | Player::LeftFront => 0,
+        Player::Right | Player::Bottom | Player::RightFront => 1,
+    }
+}
+
+fn captain(team: usize) -> Player {
+    if team == 0 { Player::Left } else { Player::Right }
+}
+
+pub fn team_score(score: &Score, team_index: usize) -> u8 {
+    Player::ALL
+        .into_iter()
+        .filter(|&player| team(player) == team_index)
+        .map(|player| score.get(player))
+        .fold(0, u8::saturating_add)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goals_score_by_mode() {
+        assert_eq!(GameMode::Classic.scorer(Player::Left, None), Some(Player::Right));
+        assert_eq!(GameMode::FreeForAll.scorer(Player::Top, Some(Player::Left)), Some(Player::Left));
+        assert_eq!(GameMode::FreeForAll.scorer(Player::Top, Some(Player::Top)), None);
+        assert_eq!(GameMode::FreeForAll.scorer(Player::Top, None), None);
+        assert_eq!(GameMode::Teams.scorer(Player::Top, Some(Player::Bottom)), Some(Player::Bottom));
+        assert_eq!(GameMode::Teams.scorer(Player::Top, Some(Player::Left)), Some(Player::Right));
+        assert_eq!(GameMode::Teams.scorer(Player::Right, None), Some(Player::Left));
+    }
+
+    #[test]
+    fn teams_win_on_their_combined_score() {
+        let mut score = Score::new();
+        score.increment(Player::Right);
+        score.increment(Player::Bottom);
+        assert_eq!(GameMode::FreeForAll.winner(&score, 2), None);
+        assert_eq!(GameMode::Teams.winner(&score, 2), Some(Player::Right));
+        assert_eq!(GameMode::Teams.winner_title(Player::Right), "Team 2 Wins!");
+        assert_eq!(GameMode::FreeForAll.winner_title(Player::Bottom), "Player 4 Wins!");
+    }
+
+    #[test]
+    fn doubles_sides_score_together() {
+        assert_eq!(GameMode::Doubles.guarded_walls(), &[Player::Left, Player::Right]);
+        assert_eq!(GameMode::Doubles.scorer(Player::Left, Some(Player::RightFront)), Some(Player::RightFront));
+        assert_eq!(GameMode::Doubles.scorer(Player::Left, Some(Player::LeftFront)), Some(Player::Right));
+
+        let mut score = Score::new();
+        score.increment(Player::LeftFront);
+        score.increment(Player::Left);
+        assert_eq!(GameMode::Doubles.winner(&score, 2), Some(Player::Left));
+        assert_eq!(GameMode::Doubles.winner_title(Player::Left), "Team 1 Wins!");
+        assert!(GameMode::Doubles.are_opponents(Player::LeftFront, Player::Right));
+        assert!(!GameMode::Doubles.are_opponents(Player::LeftFront, Player::Left));
+        assert!(GameMode::FreeForAll.are_opponents(Player::Top, Player::Bottom));
+    }
+
+    #[test]
+    fn cli_names_round_trip() {
+        for mode in GameMode::ALL {
+            assert_eq!(GameMode::from_cli_name(mode.cli_name()), Some(mode));
+            assert_eq!(mode.is_ranked(), mode == GameMode::Classic);
+        }
+        assert_eq!(GameMode::from_cli_name("FFA"), Some(GameMode::FreeForAll));
+        assert_eq!(GameMode::from_cli_name("solo"), None);
+        assert_eq!(GameMode::PongOut.players(), &[Player::Left, Player::Right]);
+        assert_eq!(GameMode::from_cli_name("time-attack"), Some(GameMode::TimeAttack));
+        assert!(GameMode::ALL.iter().filter(|mode| mode.is_challenge()).eq(GameMode::CHALLENGES.iter()));
+        assert_eq!(GameMode::WallPractice.guarded_walls(), &[Player::Left]);
+    }
+}
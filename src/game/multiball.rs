@@ -0,0 +1,76 @@
+// Multi-ball: several balls in play at once.
+//
+// A serve puts `balls` balls in play, fanned out from the server. Every ball that leaves the field scores on its
+// own, and the rally goes on until the last ball is out. Optionally the balls bounce off each other, and extra
+// balls join a long rally from the middle of the field.
+
+use serde::{Deserialize, Serialize};
+
+pub const MAX_BALLS: u8 = 5;
+// Seconds of rally before an extra ball joins, when spawns are on
+pub const SPAWN_INTERVAL: f32 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MultiBall {
+    // Balls put in play by each serve, from 1 to `MAX_BALLS`
+    pub balls: u8,
+    // Balls bounce off each other
+    pub collisions: bool,
+    // An extra ball joins every `SPAWN_INTERVAL` seconds of rally, up to `MAX_BALLS` in play
+    pub spawns: bool,
+}
+
+impl Default for MultiBall {
+    fn default() -> Self {
+        Self {
+            balls: 1,
+            collisions: false,
+            spawns: false,
+        }
+    }
+}
+
+impl MultiBall {
+    // Balls per serve, kept within 1 to `MAX_BALLS`.
+    pub fn ball_count(&self) -> u8 {
+        self.balls.clamp(1, MAX_BALLS)
+    }
+
+    // The next ball count on the settings screen, wrapping back to a single ball.
+    pub fn next_ball_count(&self) -> u8 {
+        self.ball_count() % MAX_BALLS + 1
+    }
+}
+
+// Aims of the balls of a serve, from -1 to 1 along the server's wall: the server's own `aim` first, then the
+// extra balls spread evenly between the two ends.
+pub fn serve_aims(count: u8, aim: f32) -> Vec<f32> {
+    let count = count.max(1);
+    let mut aims = vec![aim];
+    aims.extend((1..count).map(|index| -1.0 + 2.0 * index as f32 / count as f32));
+    aims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_fan_out_every_ball() {
+        assert_eq!(serve_aims(1, 0.5), vec![0.5]);
+        assert_eq!(serve_aims(2, 1.0), vec![1.0, 0.0]);
+        assert_eq!(serve_aims(4, -1.0), vec![-1.0, -0.5, 0.0, 0.5]);
+        assert_eq!(serve_aims(0, 0.0).len(), 1);
+    }
+
+    #[test]
+    fn ball_count_stays_in_range() {
+        let mut multi_ball = MultiBall::default();
+        assert_eq!(multi_ball.next_ball_count(), 2);
+        multi_ball.balls = MAX_BALLS;
+        assert_eq!(multi_ball.next_ball_count(), 1);
+        multi_ball.balls = 40;
+        assert_eq!(multi_ball.ball_count(), MAX_BALLS);
+    }
+}
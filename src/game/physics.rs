@@ -1,65 +1,484 @@
+use crate::game::arena::{self, Body};
 use crate::game::ball::{BALL_SIZE, BALL_SPEED_INCREMENT, BALL_SPEED_MAX, Ball};
-use crate::game::racket::{RACKET_HEIGHT_HALF, RACKET_WIDTH_HALF, Racket};
+use crate::game::racket::{Orientation, RACKET_WIDTH_HALF, Racket};
+use ggez::glam::Vec2;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+// Speed a bumper adds to the balls it kicks away
+const BUMPER_BOOST: f32 = 1.15;
+
+// A player, named after the wall their racket guards. `Top` and `Bottom` only play in the four-wall modes, and the
+// front rackets, which guard the side walls from further up the field, only in doubles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Player {
     Left,
     Right,
+    Top,
+    Bottom,
+    LeftFront,
+    RightFront,
 }
 
-// Bounce the ball off the top/bottom walls if needed.
-pub fn bounce_borders(ball: &mut Ball, screen_h: f32) -> bool {
-    if ball.position.y - BALL_SIZE / 2.0 <= 0.0 && ball.velocity.y < 0.0 || ball.position.y + BALL_SIZE / 2.0 >= screen_h && ball.velocity.y > 0.0 {
-        ball.velocity.y = -ball.velocity.y;
-        true
-    } else {
-        false
+impl Player {
+    pub const COUNT: usize = 6;
+    pub const ALL: [Player; Player::COUNT] = [Player::Left, Player::Right, Player::Top, Player::Bottom, Player::LeftFront, Player::RightFront];
+    // The players named after each wall of the field
+    pub const WALLS: [Player; 4] = [Player::Left, Player::Right, Player::Top, Player::Bottom];
+
+    // Position in `ALL`, for per-player arrays.
+    pub fn index(&self) -> usize {
+        match self {
+            Player::Left => 0,
+            Player::Right => 1,
+            Player::Top => 2,
+            Player::Bottom => 3,
+            Player::LeftFront => 4,
+            Player::RightFront => 5,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Player::Left => "Player 1",
+            Player::Right => "Player 2",
+            Player::Top => "Player 3",
+            Player::Bottom => "Player 4",
+            Player::LeftFront => "Player 3",
+            Player::RightFront => "Player 4",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            Player::Left => "left",
+            Player::Right => "right",
+            Player::Top => "top",
+            Player::Bottom => "bottom",
+            Player::LeftFront => "left-front",
+            Player::RightFront => "right-front",
+        }
+    }
+
+    // Rackets on the side walls move vertically, the others horizontally.
+    pub fn orientation(&self) -> Orientation {
+        match self {
+            Player::Left | Player::Right | Player::LeftFront | Player::RightFront => Orientation::Vertical,
+            Player::Top | Player::Bottom => Orientation::Horizontal,
+        }
+    }
+
+    // Unit vector from this player's wall into the field.
+    pub fn inward(&self) -> Vec2 {
+        match self {
+            Player::Left | Player::LeftFront => Vec2::X,
+            Player::Right | Player::RightFront => Vec2::NEG_X,
+            Player::Top => Vec2::Y,
+            Player::Bottom => Vec2::NEG_Y,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WallContact {
+    // The ball bounced off an open wall
+    Bounce,
+    // The ball left the field through the wall guarded by this player
+    Goal(Player),
+}
+
+// Bounce the ball off the open walls, or report a goal when it leaves the field through a `guarded` one.
+pub fn check_walls(ball: &mut Ball, screen_w: f32, screen_h: f32, guarded: &[Player]) -> Option<WallContact> {
+    let half = BALL_SIZE / 2.0;
+    for wall in Player::WALLS {
+        // Distance of the ball's center past the wall, and whether it is heading out through it
+        let (past, leaving) = match wall {
+            Player::Left => (-ball.position.x, ball.velocity.x < 0.0),
+            Player::Right => (ball.position.x - screen_w, ball.velocity.x > 0.0),
+            Player::Top => (-ball.position.y, ball.velocity.y < 0.0),
+            Player::Bottom => (ball.position.y - screen_h, ball.velocity.y > 0.0),
+            Player::LeftFront | Player::RightFront => unreachable!("front rackets have no wall of their own"),
+        };
+        if guarded.contains(&wall) {
+            if past > 0.0 {
+                return Some(WallContact::Goal(wall));
+            }
+        } else if past + half >= 0.0 && leaving {
+            match wall.orientation() {
+                Orientation::Vertical => ball.velocity.x = -ball.velocity.x,
+                Orientation::Horizontal => ball.velocity.y = -ball.velocity.y,
+            }
+            return Some(WallContact::Bounce);
+        }
     }
+    None
 }
 
-pub fn racket_collision(ball: &mut Ball, racket: &Racket) -> bool {
+// Bounce the ball off the racket if they touch; returns where the ball hit, from -1 to 1 along the racket
+// (top to bottom, or left to right for horizontal rackets).
+pub fn racket_collision(ball: &mut Ball, racket: &Racket) -> Option<f32> {
+    // Work in the racket's frame, where it always moves along y and the ball comes at it along x
+    let orientation = racket.orientation;
+    let position = orientation.to_local(ball.position);
+    let mut velocity = orientation.to_local(ball.velocity);
+    let racket_position = racket.local_position();
+
     // Generalized collision: determine the ball contact x (edge) and the racket edge to compare against
-    let contact_x = if ball.velocity.x < 0.0 {
-        ball.position.x - BALL_SIZE / 2.0
+    let contact_x = if velocity.x < 0.0 {
+        position.x - BALL_SIZE / 2.0
     } else {
-        ball.position.x + BALL_SIZE / 2.0
+        position.x + BALL_SIZE / 2.0
     };
 
-    let racket_edge = if ball.velocity.x < 0.0 {
-        racket.position_x + RACKET_WIDTH_HALF
+    let racket_edge = if velocity.x < 0.0 {
+        racket_position.x + RACKET_WIDTH_HALF
     } else {
-        racket.position_x - RACKET_WIDTH_HALF
+        racket_position.x - RACKET_WIDTH_HALF
     };
 
-    let horizontal_overlap = if ball.velocity.x < 0.0 {
+    let horizontal_overlap = if velocity.x < 0.0 {
         contact_x <= racket_edge
     } else {
         contact_x >= racket_edge
     };
 
-    let vertical_overlap = ball.position.y >= racket.position_y - RACKET_HEIGHT_HALF && ball.position.y <= racket.position_y + RACKET_HEIGHT_HALF;
+    let half_length = racket.half_length();
+    let vertical_overlap = position.y >= racket_position.y - half_length && position.y <= racket_position.y + half_length;
 
-    // Only reflect if ball is actually approaching the racket (prevents accidental reflections)
-    let approaching = (ball.velocity.x < 0.0 && ball.position.x > racket.position_x) || (ball.velocity.x > 0.0 && ball.position.x < racket.position_x);
+    // Only reflect if ball is actually approaching the racket (prevents accidental reflections), from the field side:
+    // a front racket lets its partner's returns through from behind
+    let approaching = (velocity.x < 0.0 && position.x > racket_position.x) || (velocity.x > 0.0 && position.x < racket_position.x);
+    let approaching = approaching && ball.velocity.dot(racket.facing) < 0.0;
 
     if horizontal_overlap && vertical_overlap && approaching {
-        ball.velocity.x = -ball.velocity.x;
-        let offset = (ball.position.y - racket.position_y) / RACKET_HEIGHT_HALF;
-        ball.velocity.y = ball.speed * offset;
+        velocity.x = -velocity.x;
+        let offset = (position.y - racket_position.y) / half_length;
+        velocity.y = ball.speed * offset;
         ball.speed = (ball.speed * BALL_SPEED_INCREMENT).min(BALL_SPEED_MAX);
-        ball.velocity = ball.velocity.normalize() * ball.speed;
-        return true;
+        ball.velocity = orientation.to_local(velocity.normalize() * ball.speed);
+        return Some(offset);
     }
 
-    false
+    None
 }
 
-pub fn check_score(ball: &Ball, screen_w: f32) -> Option<Player> {
-    if ball.position.x < 0.0 {
-        return Some(Player::Right);
+// Bounce two touching balls off each other: they trade their motion along the line between their centers, as
+// equal masses would, but each keeps its own speed. Returns whether they collided.
+pub fn ball_collision(first: &mut Ball, second: &mut Ball) -> bool {
+    let offset = second.position - first.position;
+    let distance = offset.length();
+    if distance >= BALL_SIZE || distance == 0.0 {
+        return false;
     }
-    if ball.position.x > screen_w {
-        return Some(Player::Left);
+    let normal = offset / distance;
+    let closing = (first.velocity - second.velocity).dot(normal);
+    // Balls already moving apart are left alone
+    if closing <= 0.0 {
+        return false;
+    }
+    let first_velocity = first.velocity - normal * closing;
+    let second_velocity = second.velocity + normal * closing;
+    first.velocity = first_velocity.try_normalize().unwrap_or(-normal) * first.speed;
+    second.velocity = second_velocity.try_normalize().unwrap_or(normal) * second.speed;
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObstacleContact {
+    // The ball bounced off the obstacle, whose surface faces `normal` where it hit
+    Bounce { normal: Vec2 },
+    // A bumper kicked the ball away along `normal`
+    Bumped { normal: Vec2 },
+    // The ball went into a portal at `from` and came out where the ball now is
+    Teleported { from: Vec2 },
+}
+
+// Bounce the ball off an arena obstacle, or send it through a portal. A ball caught inside an obstacle (one that
+// moved into it) is pushed back out, but only a ball heading into the obstacle bounces.
+pub fn obstacle_collision(ball: &mut Ball, body: &Body) -> Option<ObstacleContact> {
+    let half = BALL_SIZE / 2.0;
+    match *body {
+        Body::Block { center, half_size } => {
+            let offset = ball.position - center;
+            let closest = offset.clamp(-half_size, half_size);
+            let (surface, normal) = if closest != offset {
+                let gap = offset - closest;
+                if gap.length() >= half {
+                    return None;
+                }
+                (closest, gap.normalize())
+            } else {
+                // The center is inside: leave by the nearest side
+                let depth = half_size - offset.abs();
+                if depth.x < depth.y {
+                    (Vec2::new(offset.x.signum() * half_size.x, offset.y), Vec2::new(offset.x.signum(), 0.0))
+                } else {
+                    (Vec2::new(offset.x, offset.y.signum() * half_size.y), Vec2::new(0.0, offset.y.signum()))
+                }
+            };
+            ball.position = center + surface + normal * half;
+            reflect(ball, normal).then_some(ObstacleContact::Bounce { normal })
+        }
+        Body::Bumper { center, radius } => {
+            let normal = round_contact(ball, center, radius)?;
+            if !reflect(ball, normal) {
+                return None;
+            }
+            ball.speed = (ball.speed * BUMPER_BOOST).min(BALL_SPEED_MAX);
+            ball.velocity = ball.velocity.normalize() * ball.speed;
+            Some(ObstacleContact::Bumped { normal })
+        }
+        Body::Portal { center, radius, exit } => {
+            if ball.position.distance(center) >= radius {
+                return None;
+            }
+            let from = ball.position;
+            ball.position = exit;
+            Some(ObstacleContact::Teleported { from })
+        }
+        Body::Curve {
+            center,
+            radius,
+            from,
+            to,
+            half_thickness,
+        } => {
+            let offset = ball.position - center;
+            let normal = if arena::arc_angle(offset, from) <= to {
+                let distance = offset.length();
+                let band = distance - radius;
+                if band.abs() >= half_thickness + half || distance == 0.0 {
+                    return None;
+                }
+                // The ball bounces off whichever side of the wall it is on
+                let normal = offset / distance * band.signum();
+                ball.position = center + offset / distance * radius + normal * (half_thickness + half);
+                normal
+            } else {
+                // Past the ends, the wall is rounded off
+                [from, to]
+                    .into_iter()
+                    .find_map(|end| round_contact(ball, center + Vec2::from_angle(end) * radius, half_thickness))?
+            };
+            reflect(ball, normal).then_some(ObstacleContact::Bounce { normal })
+        }
+    }
+}
+
+// Bounce the ball off a Pong-Out brick of `half_size` around `center`, which faces the field along `facing`;
+// returns the normal of the side it hit. Bricks are only ever reached from the field, so a ball that got into or
+// past the front of one while heading for the goal is sent back out the front, however fast it came.
+pub fn brick_collision(ball: &mut Ball, center: Vec2, half_size: Vec2, facing: Vec2) -> Option<Vec2> {
+    let half = BALL_SIZE / 2.0;
+    let offset = ball.position - center;
+    if offset.x.abs() >= half_size.x + half || offset.y.abs() >= half_size.y + half {
+        return None;
+    }
+    // Bricks face the field along an axis: `along` picks the other one
+    let along = Vec2::ONE - facing.abs();
+    let level_with_front = (offset * along).abs().cmple(half_size * along).all();
+    if level_with_front && ball.velocity.dot(facing) < 0.0 {
+        let depth = offset.dot(facing);
+        ball.position += facing * (half_size.dot(facing.abs()) + half - depth);
+        reflect(ball, facing);
+        return Some(facing);
+    }
+    match obstacle_collision(ball, &Body::Block { center, half_size })? {
+        ObstacleContact::Bounce { normal } => Some(normal),
+        ObstacleContact::Bumped { .. } | ObstacleContact::Teleported { .. } => None,
+    }
+}
+
+// When the ball touches a circle of `radius` around `center`, push it back to the circle's edge and return the
+// normal there.
+fn round_contact(ball: &mut Ball, center: Vec2, radius: f32) -> Option<Vec2> {
+    let offset = ball.position - center;
+    let reach = radius + BALL_SIZE / 2.0;
+    if offset.length() >= reach {
+        return None;
+    }
+    let normal = offset.try_normalize().unwrap_or(Vec2::NEG_Y);
+    ball.position = center + normal * reach;
+    Some(normal)
+}
+
+// Mirror the ball's velocity off a surface facing `normal`, if it is heading into it.
+fn reflect(ball: &mut Ball, normal: Vec2) -> bool {
+    let along = ball.velocity.dot(normal);
+    if along >= 0.0 {
+        return false;
+    }
+    ball.velocity -= 2.0 * along * normal;
+    true
+}
+
+// Keep the ball crossing the field: at least `min_across` of its speed goes along x. Classic fields have no goal on
+// the top and bottom walls, so a ball going nearly straight up and down would never leave.
+pub fn limit_steepness(ball: &mut Ball, min_across: f32) {
+    let min_x = ball.speed * min_across;
+    if ball.velocity.x.abs() >= min_x {
+        return;
+    }
+    let sign_x = if ball.velocity.x < 0.0 { -1.0 } else { 1.0 };
+    let sign_y = if ball.velocity.y < 0.0 { -1.0 } else { 1.0 };
+    let y = (ball.speed * ball.speed - min_x * min_x).max(0.0).sqrt();
+    ball.velocity = Vec2::new(sign_x * min_x, sign_y * y);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ball::BALL_SPEED;
+    use crate::player::controller::HumanController;
+    use ggez::input::keyboard::KeyCode;
+
+    fn ball(position: Vec2, velocity: Vec2) -> Ball {
+        Ball {
+            position,
+            velocity,
+            speed: BALL_SPEED,
+            last_hitter: None,
+            stuck: None,
+        }
+    }
+
+    #[test]
+    fn open_walls_bounce_and_guarded_walls_concede() {
+        let classic = [Player::Left, Player::Right];
+        let mut top = ball(Vec2::new(400.0, 5.0), Vec2::new(100.0, -100.0));
+        assert_eq!(check_walls(&mut top, 800.0, 600.0, &classic), Some(WallContact::Bounce));
+        assert!(top.velocity.y > 0.0);
+
+        let mut out = ball(Vec2::new(801.0, 300.0), Vec2::new(100.0, 0.0));
+        assert_eq!(check_walls(&mut out, 800.0, 600.0, &classic), Some(WallContact::Goal(Player::Right)));
+
+        let mut guarded_top = ball(Vec2::new(400.0, -1.0), Vec2::new(0.0, -100.0));
+        assert_eq!(check_walls(&mut guarded_top, 800.0, 600.0, &Player::ALL), Some(WallContact::Goal(Player::Top)));
+
+        let mut middle = ball(Vec2::new(400.0, 300.0), Vec2::new(100.0, 100.0));
+        assert_eq!(check_walls(&mut middle, 800.0, 600.0, &Player::ALL), None);
+    }
+
+    #[test]
+    fn front_rackets_only_hit_from_the_field_side() {
+        let controller = || Box::new(HumanController::new(KeyCode::T, KeyCode::G));
+        let racket = Racket::for_player(Player::LeftFront, 800.0, 600.0, controller());
+        let at_racket = Vec2::new(racket.position_x, racket.position_y);
+
+        let mut incoming = ball(at_racket + Vec2::new(15.0, 0.0), Vec2::new(-BALL_SPEED, 0.0));
+        assert!(racket_collision(&mut incoming, &racket).is_some());
+        assert!(incoming.velocity.x > 0.0);
+
+        let mut from_behind = ball(at_racket - Vec2::new(15.0, 0.0), Vec2::new(BALL_SPEED, 0.0));
+        assert_eq!(racket_collision(&mut from_behind, &racket), None);
+        assert!(from_behind.velocity.x > 0.0);
+    }
+
+    #[test]
+    fn colliding_balls_trade_directions_and_keep_their_speed() {
+        let mut first = ball(Vec2::new(100.0, 100.0), Vec2::new(BALL_SPEED, 0.0));
+        let mut second = ball(Vec2::new(115.0, 100.0), Vec2::new(-BALL_SPEED / 2.0, 0.0));
+        second.speed = BALL_SPEED / 2.0;
+        assert!(ball_collision(&mut first, &mut second));
+        assert_eq!(first.velocity, Vec2::new(-BALL_SPEED, 0.0));
+        assert_eq!(second.velocity, Vec2::new(BALL_SPEED / 2.0, 0.0));
+        // Now moving apart
+        assert!(!ball_collision(&mut first, &mut second));
+
+        let mut far = ball(Vec2::new(200.0, 100.0), Vec2::new(-BALL_SPEED, 0.0));
+        assert!(!ball_collision(&mut first, &mut far));
+    }
+
+    #[test]
+    fn steep_balls_are_turned_across_the_field() {
+        let mut steep = ball(Vec2::ZERO, Vec2::new(-1.0, BALL_SPEED));
+        limit_steepness(&mut steep, 0.5);
+        assert!((steep.velocity.length() - BALL_SPEED).abs() < 0.01);
+        assert_eq!(steep.velocity.x, -BALL_SPEED * 0.5);
+        assert!(steep.velocity.y > 0.0);
+    }
+
+    #[test]
+    fn obstacles_bounce_kick_and_teleport_the_ball() {
+        let block = Body::Block {
+            center: Vec2::new(400.0, 300.0),
+            half_size: Vec2::new(20.0, 50.0),
+        };
+        let mut incoming = ball(Vec2::new(372.0, 310.0), Vec2::new(BALL_SPEED, 0.0));
+        assert_eq!(obstacle_collision(&mut incoming, &block), Some(ObstacleContact::Bounce { normal: Vec2::NEG_X }));
+        assert_eq!(incoming.velocity, Vec2::new(-BALL_SPEED, 0.0));
+        assert_eq!(incoming.position.x, 380.0 - BALL_SIZE / 2.0);
+        // Already bounced: no second hit
+        assert_eq!(obstacle_collision(&mut incoming, &block), None);
+
+        let bumper = Body::Bumper {
+            center: Vec2::new(400.0, 300.0),
+            radius: 30.0,
+        };
+        let mut kicked = ball(Vec2::new(400.0, 265.0), Vec2::new(0.0, BALL_SPEED));
+        assert!(matches!(obstacle_collision(&mut kicked, &bumper), Some(ObstacleContact::Bumped { .. })));
+        assert!(kicked.velocity.y < 0.0);
+        assert!(kicked.speed > BALL_SPEED);
+
+        let portal = Body::Portal {
+            center: Vec2::new(100.0, 100.0),
+            radius: 20.0,
+            exit: Vec2::new(700.0, 500.0),
+        };
+        let mut through = ball(Vec2::new(105.0, 100.0), Vec2::new(BALL_SPEED, 0.0));
+        assert_eq!(
+            obstacle_collision(&mut through, &portal),
+            Some(ObstacleContact::Teleported { from: Vec2::new(105.0, 100.0) })
+        );
+        assert_eq!((through.position, through.velocity), (Vec2::new(700.0, 500.0), Vec2::new(BALL_SPEED, 0.0)));
+    }
+
+    #[test]
+    fn bricks_send_fast_balls_back_out_the_front() {
+        let (center, half_size) = (Vec2::new(20.0, 100.0), Vec2::new(6.0, 25.0));
+        // Deep inside the brick, as a fast ball can be after one frame
+        let mut inside = ball(Vec2::new(16.0, 110.0), Vec2::new(-BALL_SPEED, 0.0));
+        assert_eq!(brick_collision(&mut inside, center, half_size, Vec2::X), Some(Vec2::X));
+        assert_eq!(inside.position.x, 36.0);
+        assert!(inside.velocity.x > 0.0);
+
+        // Through a gap, the ball bounces off the brick's side
+        let mut below = ball(Vec2::new(20.0, 133.0), Vec2::new(-100.0, -BALL_SPEED));
+        assert_eq!(brick_collision(&mut below, center, half_size, Vec2::X), Some(Vec2::Y));
+        assert!(below.velocity.y > 0.0);
+        let mut away = ball(Vec2::new(60.0, 100.0), Vec2::new(-BALL_SPEED, 0.0));
+        assert_eq!(brick_collision(&mut away, center, half_size, Vec2::X), None);
+    }
+
+    #[test]
+    fn curves_bounce_from_either_side_within_their_angles() {
+        // The lower half of a circle around the top of the field
+        let curve = Body::Curve {
+            center: Vec2::new(400.0, 0.0),
+            radius: 100.0,
+            from: 0.0,
+            to: std::f32::consts::PI,
+            half_thickness: 5.0,
+        };
+        let mut below = ball(Vec2::new(400.0, 110.0), Vec2::new(0.0, -BALL_SPEED));
+        assert!(matches!(obstacle_collision(&mut below, &curve), Some(ObstacleContact::Bounce { .. })));
+        assert!(below.velocity.y > 0.0);
+        let mut inside = ball(Vec2::new(400.0, 90.0), Vec2::new(0.0, BALL_SPEED));
+        assert!(obstacle_collision(&mut inside, &curve).is_some());
+        assert!(inside.velocity.y < 0.0);
+        // Above the field, past the curve's ends
+        let mut outside = ball(Vec2::new(400.0, -100.0), Vec2::new(0.0, BALL_SPEED));
+        assert_eq!(obstacle_collision(&mut outside, &curve), None);
+    }
+
+    #[test]
+    fn horizontal_rackets_send_the_ball_back_into_the_field() {
+        let controller = Box::new(HumanController::new(KeyCode::J, KeyCode::L));
+        let racket = Racket::for_player(Player::Bottom, 800.0, 600.0, controller);
+        let mut falling = ball(Vec2::new(racket.position_x + 30.0, racket.position_y - 15.0), Vec2::new(0.0, BALL_SPEED));
+
+        let offset = racket_collision(&mut falling, &racket).expect("the ball should hit the racket");
+        assert!(offset > 0.0);
+        assert!(falling.velocity.y < 0.0);
+        // Hitting right of center sends the ball to the right
+        assert!(falling.velocity.x > 0.0);
     }
-    None
 }
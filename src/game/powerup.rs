@@ -0,0 +1,429 @@
+// Power-ups: collectibles that appear in the field and are picked up by the last player to hit a ball that passes
+// through them.
+//
+// Every power-up is listed in `REGISTRY`. Most of them are timed: while they last they change the `Modifiers` the
+// simulation plays with (racket size and speed, ball speed, sticky rackets, hidden balls). Multi-ball acts at once.
+// To add one, add a `PowerUpKind`, its `REGISTRY` entry, and what it does in `Modifiers::apply` (or in the
+// simulation, for instant power-ups).
+
+use crate::game::mode::GameMode;
+use crate::game::physics::Player;
+use ggez::glam::Vec2;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// Diameter of a power-up in the field
+pub const POWER_UP_SIZE: f32 = 36.0;
+// Seconds a power-up waits in the field before disappearing
+pub const POWER_UP_LIFETIME: f32 = 12.0;
+// Power-ups waiting in the field at once
+const MAX_WAITING: usize = 3;
+// Share of the field, from its center, power-ups appear in
+const SPAWN_AREA: Vec2 = Vec2::new(0.5, 0.7);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerUpKind {
+    BigRacket,
+    ShrinkOpponents,
+    SpeedBoost,
+    SlowMotion,
+    MultiBall,
+    StickyRacket,
+    InvisibleBall,
+}
+
+pub struct PowerUpSpec {
+    pub kind: PowerUpKind,
+    pub name: &'static str,
+    // Drawn on the power-up in the field
+    pub symbol: &'static str,
+    // Seconds the effect lasts; 0 for power-ups that act at once
+    pub duration: f32,
+    // Relative chance of being the next one to appear
+    pub weight: u32,
+}
+
+pub const REGISTRY: [PowerUpSpec; 7] = [
+    PowerUpSpec {
+        kind: PowerUpKind::BigRacket,
+        name: "Big racket",
+        symbol: "+",
+        duration: 10.0,
+        weight: 3,
+    },
+    PowerUpSpec {
+        kind: PowerUpKind::ShrinkOpponents,
+        name: "Shrink",
+        symbol: "-",
+        duration: 8.0,
+        weight: 3,
+    },
+    PowerUpSpec {
+        kind: PowerUpKind::SpeedBoost,
+        name: "Speed boost",
+        symbol: ">",
+        duration: 10.0,
+        weight: 3,
+    },
+    PowerUpSpec {
+        kind: PowerUpKind::SlowMotion,
+        name: "Slow-mo",
+        symbol: "S",
+        duration: 6.0,
+        weight: 2,
+    },
+    PowerUpSpec {
+        kind: PowerUpKind::MultiBall,
+        name: "Multi-ball",
+        symbol: "M",
+        duration: 0.0,
+        weight: 2,
+    },
+    PowerUpSpec {
+        kind: PowerUpKind::StickyRacket,
+        name: "Sticky racket",
+        symbol: "=",
+        duration: 10.0,
+        weight: 2,
+    },
+    PowerUpSpec {
+        kind: PowerUpKind::InvisibleBall,
+        name: "Invisible ball",
+        symbol: "?",
+        duration: 5.0,
+        weight: 1,
+    },
+];
+
+impl PowerUpKind {
+    pub fn spec(&self) -> &'static PowerUpSpec {
+        REGISTRY.iter().find(|spec| spec.kind == *self).expect("every power-up is in the registry")
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.spec().name
+    }
+
+    pub fn is_timed(&self) -> bool {
+        self.spec().duration > 0.0
+    }
+}
+
+// Pick a power-up from the registry, by weight.
+pub fn random_kind(rng: &mut impl Rng) -> PowerUpKind {
+    let total: u32 = REGISTRY.iter().map(|spec| spec.weight).sum();
+    let mut roll = rng.random_range(0..total);
+    for spec in &REGISTRY {
+        if roll < spec.weight {
+            return spec.kind;
+        }
+        roll -= spec.weight;
+    }
+    unreachable!("the roll is below the total weight")
+}
+
+// How often power-ups appear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpawnRate {
+    Rare,
+    #[default]
+    Normal,
+    Frequent,
+}
+
+impl SpawnRate {
+    pub const ALL: [SpawnRate; 3] = [SpawnRate::Rare, SpawnRate::Normal, SpawnRate::Frequent];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpawnRate::Rare => "Rare",
+            SpawnRate::Normal => "Normal",
+            SpawnRate::Frequent => "Frequent",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            SpawnRate::Rare => "rare",
+            SpawnRate::Normal => "normal",
+            SpawnRate::Frequent => "frequent",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<Self> {
+        SpawnRate::ALL.into_iter().find(|rate| rate.cli_name() == name.to_ascii_lowercase())
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            SpawnRate::Rare => SpawnRate::Normal,
+            SpawnRate::Normal => SpawnRate::Frequent,
+            SpawnRate::Frequent => SpawnRate::Rare,
+        }
+    }
+
+    // Average seconds between two power-ups.
+    pub fn interval(&self) -> f32 {
+        match self {
+            SpawnRate::Rare => 16.0,
+            SpawnRate::Normal => 9.0,
+            SpawnRate::Frequent => 5.0,
+        }
+    }
+}
+
+// Match rules for power-ups; off by default.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerUpRules {
+    pub enabled: bool,
+    pub rate: SpawnRate,
+}
+
+// A power-up waiting in the field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldPowerUp {
+    pub kind: PowerUpKind,
+    pub position: Vec2,
+    // Seconds left before it disappears
+    pub remaining: f32,
+}
+
+// A timed power-up in effect.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActivePowerUp {
+    pub kind: PowerUpKind,
+    // Who collected it
+    pub owner: Player,
+    pub remaining: f32,
+}
+
+impl ActivePowerUp {
+    // From 1 when collected to 0 when it runs out.
+    pub fn progress(&self) -> f32 {
+        (self.remaining / self.kind.spec().duration).clamp(0.0, 1.0)
+    }
+}
+
+// What the timed power-ups in effect change, indexed by `Player::index` where it is per player.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Modifiers {
+    pub racket_length: [f32; Player::COUNT],
+    pub racket_speed: [f32; Player::COUNT],
+    pub sticky: [bool; Player::COUNT],
+    // Multiplies the speed the balls travel at
+    pub ball_time_scale: f32,
+    pub balls_hidden: bool,
+}
+
+impl Default for Modifiers {
+    fn default() -> Self {
+        Self {
+            racket_length: [1.0; Player::COUNT],
+            racket_speed: [1.0; Player::COUNT],
+            sticky: [false; Player::COUNT],
+            ball_time_scale: 1.0,
+            balls_hidden: false,
+        }
+    }
+}
+
+impl Modifiers {
+    fn apply(&mut self, power_up: &ActivePowerUp, mode: GameMode) {
+        let owner = power_up.owner.index();
+        match power_up.kind {
+            PowerUpKind::BigRacket => self.racket_length[owner] *= 1.5,
+            PowerUpKind::ShrinkOpponents => {
+                for &player in mode.players() {
+                    if mode.are_opponents(power_up.owner, player) {
+                        self.racket_length[player.index()] *= 0.6;
+                    }
+                }
+            }
+            PowerUpKind::SpeedBoost => self.racket_speed[owner] *= 1.6,
+            PowerUpKind::SlowMotion => self.ball_time_scale *= 0.5,
+            PowerUpKind::StickyRacket => self.sticky[owner] = true,
+            PowerUpKind::InvisibleBall => self.balls_hidden = true,
+            // Instant: nothing lasts
+            PowerUpKind::MultiBall => {}
+        }
+    }
+}
+
+// The power-ups of a match: those waiting in the field and those in effect.
+#[derive(Debug, Clone, Default)]
+pub struct PowerUps {
+    field: Vec<FieldPowerUp>,
+    active: Vec<ActivePowerUp>,
+    // Seconds until the next power-up appears
+    spawn_timer: f32,
+}
+
+// What happened to the power-ups during an update.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PowerUpUpdate {
+    pub spawned: Option<FieldPowerUp>,
+    pub expired: Vec<ActivePowerUp>,
+}
+
+impl PowerUps {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn field(&self) -> &[FieldPowerUp] {
+        &self.field
+    }
+
+    pub fn active(&self) -> &[ActivePowerUp] {
+        &self.active
+    }
+
+    // Run the timers of a `width` x `height` field: spawn new power-ups, and let old ones and effects run out.
+    pub fn update(&mut self, delta_time: f32, rules: &PowerUpRules, width: f32, height: f32, rng: &mut impl Rng) -> PowerUpUpdate {
+        let mut update = PowerUpUpdate::default();
+        for power_up in &mut self.field {
+            power_up.remaining -= delta_time;
+        }
+        self.field.retain(|power_up| power_up.remaining > 0.0);
+        for power_up in &mut self.active {
+            power_up.remaining -= delta_time;
+        }
+        let (expired, active) = self.active.drain(..).partition(|power_up| power_up.remaining <= 0.0);
+        self.active = active;
+        update.expired = expired;
+
+        if !rules.enabled {
+            self.field.clear();
+            return update;
+        }
+        self.spawn_timer -= delta_time;
+        if self.spawn_timer <= 0.0 {
+            // Intervals vary by half either way so power-ups don't appear like clockwork
+            self.spawn_timer = rules.rate.interval() * rng.random_range(0.5..1.5);
+            if self.field.len() < MAX_WAITING {
+                let center = Vec2::new(width, height) / 2.0;
+                let reach = Vec2::new(width, height) * SPAWN_AREA / 2.0;
+                let offset = Vec2::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0)) * reach;
+                let power_up = FieldPowerUp {
+                    kind: random_kind(rng),
+                    position: center + offset,
+                    remaining: POWER_UP_LIFETIME,
+                };
+                self.field.push(power_up);
+                update.spawned = Some(power_up);
+            }
+        }
+        update
+    }
+
+    // Take the power-up a ball of `ball_size` at `position` passes through, if any.
+    pub fn take_at(&mut self, position: Vec2, ball_size: f32) -> Option<FieldPowerUp> {
+        let reach = (POWER_UP_SIZE + ball_size) / 2.0;
+        let index = self.field.iter().position(|power_up| power_up.position.distance(position) <= reach)?;
+        Some(self.field.remove(index))
+    }
+
+    // Start `kind`'s effect for `owner`. Collecting one already in effect starts it over.
+    pub fn activate(&mut self, kind: PowerUpKind, owner: Player) {
+        if !kind.is_timed() {
+            return;
+        }
+        let remaining = kind.spec().duration;
+        match self.active.iter_mut().find(|power_up| power_up.kind == kind && power_up.owner == owner) {
+            Some(power_up) => power_up.remaining = remaining,
+            None => self.active.push(ActivePowerUp { kind, owner, remaining }),
+        }
+    }
+
+    pub fn modifiers(&self, mode: GameMode) -> Modifiers {
+        let mut modifiers = Modifiers::default();
+        for power_up in &self.active {
+            modifiers.apply(power_up, mode);
+        }
+        modifiers
+    }
+
+    // Seconds left of the longest invisible ball in effect.
+    pub fn invisibility_left(&self) -> f32 {
+        self.active
+            .iter()
+            .filter(|power_up| power_up.kind == PowerUpKind::InvisibleBall)
+            .map(|power_up| power_up.remaining)
+            .fold(0.0, f32::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn registry_lists_every_power_up_once() {
+        let kinds = [
+            PowerUpKind::BigRacket,
+            PowerUpKind::ShrinkOpponents,
+            PowerUpKind::SpeedBoost,
+            PowerUpKind::SlowMotion,
+            PowerUpKind::MultiBall,
+            PowerUpKind::StickyRacket,
+            PowerUpKind::InvisibleBall,
+        ];
+        for kind in kinds {
+            assert_eq!(REGISTRY.iter().filter(|spec| spec.kind == kind).count(), 1);
+            assert_eq!(kind.spec().kind, kind);
+        }
+        let mut rng = StdRng::seed_from_u64(2);
+        let rolled: Vec<PowerUpKind> = (0..500).map(|_| random_kind(&mut rng)).collect();
+        assert!(kinds.iter().all(|kind| rolled.contains(kind)));
+    }
+
+    #[test]
+    fn power_ups_spawn_wait_and_run_out() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let rules = PowerUpRules {
+            enabled: true,
+            rate: SpawnRate::Frequent,
+        };
+        let mut power_ups = PowerUps::new();
+        let update = power_ups.update(0.1, &rules, 800.0, 600.0, &mut rng);
+        let spawned = update.spawned.expect("the first power-up appears at once");
+        assert!(spawned.position.x > 200.0 && spawned.position.x < 600.0);
+
+        assert_eq!(power_ups.take_at(spawned.position + Vec2::new(100.0, 0.0), 20.0), None);
+        assert_eq!(power_ups.take_at(spawned.position + Vec2::new(20.0, 0.0), 20.0), Some(spawned));
+        assert!(power_ups.field().is_empty());
+
+        power_ups.activate(PowerUpKind::BigRacket, Player::Left);
+        power_ups.activate(PowerUpKind::MultiBall, Player::Left);
+        assert_eq!(power_ups.active().len(), 1);
+        let update = power_ups.update(11.0, &PowerUpRules::default(), 800.0, 600.0, &mut rng);
+        assert_eq!(update.expired.len(), 1);
+        assert!(power_ups.active().is_empty());
+    }
+
+    #[test]
+    fn modifiers_follow_the_effects_in_play() {
+        let mut power_ups = PowerUps::new();
+        power_ups.activate(PowerUpKind::BigRacket, Player::Left);
+        power_ups.activate(PowerUpKind::ShrinkOpponents, Player::Left);
+        power_ups.activate(PowerUpKind::SlowMotion, Player::Right);
+        let modifiers = power_ups.modifiers(GameMode::Doubles);
+        assert_eq!(modifiers.racket_length[Player::Left.index()], 1.5);
+        assert_eq!(modifiers.racket_length[Player::LeftFront.index()], 1.0);
+        assert_eq!(modifiers.racket_length[Player::RightFront.index()], 0.6);
+        assert_eq!(modifiers.ball_time_scale, 0.5);
+        assert!(!modifiers.balls_hidden);
+
+        // Collecting it again starts it over instead of stacking
+        power_ups.activate(PowerUpKind::BigRacket, Player::Left);
+        assert_eq!(power_ups.modifiers(GameMode::Classic).racket_length[Player::Left.index()], 1.5);
+    }
+}
@@ -1,6 +1,8 @@
+use crate::game::physics::Player;
 use crate::player::controller::{Controller, RacketAction::*};
 use ggez::graphics::{Canvas, Color, DrawParam, Mesh, Rect};
-use ggez::{Context, GameResult};
+use ggez::{Context, GameResult, glam::Vec2};
+use std::f32::consts::FRAC_PI_2;
 
 const RACKET_SPEED: f32 = 650.0;
 pub const RACKET_HEIGHT: f32 = 150.0;
@@ -8,50 +10,135 @@ pub const RACKET_WIDTH: f32 = 20.0;
 pub const RACKET_HEIGHT_HALF: f32 = RACKET_HEIGHT / 2.0;
 pub const RACKET_WIDTH_HALF: f32 = RACKET_WIDTH / 2.0;
 pub const RACKET_OFFSET: f32 = RACKET_WIDTH * 2.0;
+// Distance of a doubles front racket from its wall, as a fraction of the field width
+pub const FRONT_RACKET_RATIO: f32 = 0.3;
+
+// Which way a racket moves. Horizontal rackets guard the top and bottom walls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Vertical,
+    Horizontal,
+}
+
+impl Orientation {
+    // Convert between screen coordinates and the racket's frame, where the racket moves along y.
+    // Swapping the axes is its own inverse, so this works both ways.
+    pub fn to_local(self, vector: Vec2) -> Vec2 {
+        match self {
+            Orientation::Vertical => vector,
+            Orientation::Horizontal => Vec2::new(vector.y, vector.x),
+        }
+    }
+}
 
 pub struct Racket {
     pub position_y: f32,
     pub position_x: f32,
-    racket_mesh: Mesh,
+    pub orientation: Orientation,
+    // Unit vector the hitting face points to: the racket only returns balls coming at it from that side
+    pub facing: Vec2,
+    // Power-ups stretch or shrink the racket and speed it up; both 1 normally
+    pub length_scale: f32,
+    pub speed_scale: f32,
     pub controller: Box<dyn Controller>,
 }
 
 impl Racket {
-    pub fn new(x: f32, y: f32, context: &mut Context, controller: Box<dyn Controller>) -> GameResult<Self> {
-        let rect = Rect::new(-RACKET_WIDTH / 2.0, -RACKET_HEIGHT / 2.0, RACKET_WIDTH, RACKET_HEIGHT);
-        let racket_mesh = Mesh::new_rectangle(context, ggez::graphics::DrawMode::fill(), rect, Color::WHITE)?;
-
-        Ok(Self {
-            position_x: x,
-            position_y: y,
-            racket_mesh,
+    // A racket centered on `player`'s wall of a `width` x `height` field.
+    pub fn for_player(player: Player, width: f32, height: f32, controller: Box<dyn Controller>) -> Self {
+        let mut racket = Self {
+            position_x: 0.0,
+            position_y: 0.0,
+            orientation: player.orientation(),
+            facing: player.inward(),
+            length_scale: 1.0,
+            speed_scale: 1.0,
             controller,
-        })
+        };
+        racket.place(player, width, height);
+        racket.center(width, height);
+        racket
+    }
+
+    // Put the racket against `player`'s wall, e.g. after the field is resized.
+    pub fn place(&mut self, player: Player, width: f32, height: f32) {
+        match player {
+            Player::Left => self.position_x = RACKET_OFFSET,
+            Player::Right => self.position_x = width - RACKET_OFFSET,
+            Player::Top => self.position_y = RACKET_OFFSET,
+            Player::Bottom => self.position_y = height - RACKET_OFFSET,
+            Player::LeftFront => self.position_x = width * FRONT_RACKET_RATIO,
+            Player::RightFront => self.position_x = width * (1.0 - FRONT_RACKET_RATIO),
+        }
+    }
+
+    // Move the racket to the middle of its wall.
+    pub fn center(&mut self, width: f32, height: f32) {
+        match self.orientation {
+            Orientation::Vertical => self.position_y = height / 2.0,
+            Orientation::Horizontal => self.position_x = width / 2.0,
+        }
+    }
+
+    // Position in the racket's frame: x across the wall, y along it.
+    pub fn local_position(&self) -> Vec2 {
+        self.orientation.to_local(Vec2::new(self.position_x, self.position_y))
+    }
+
+    pub fn half_length(&self) -> f32 {
+        RACKET_HEIGHT_HALF * self.length_scale
     }
 
-    pub fn draw_on_canvas(&self, canvas: &mut Canvas) {
-        canvas.draw(&self.racket_mesh, DrawParam::default().dest([self.position_x, self.position_y]));
+    // Position along the wall, the coordinate the racket moves on.
+    pub fn travel_position(&self) -> f32 {
+        self.local_position().y
     }
 
+    // Build the mesh used to draw any racket; rackets themselves hold no graphics state.
+    pub fn build_mesh(context: &mut Context, color: Color) -> GameResult<Mesh> {
+        let rect = Rect::new(-RACKET_WIDTH / 2.0, -RACKET_HEIGHT / 2.0, RACKET_WIDTH, RACKET_HEIGHT);
+        Mesh::new_rectangle(context, ggez::graphics::DrawMode::fill(), rect, color)
+    }
+
+    // `scale` squashes or stretches the racket around its center, in the racket's frame, on top of its length.
+    pub fn draw_on_canvas(&self, canvas: &mut Canvas, mesh: &Mesh, scale: Vec2) {
+        let rotation = match self.orientation {
+            Orientation::Vertical => 0.0,
+            Orientation::Horizontal => FRAC_PI_2,
+        };
+        let scale = scale * Vec2::new(1.0, self.length_scale);
+        canvas.draw(
+            mesh,
+            DrawParam::default().dest([self.position_x, self.position_y]).rotation(rotation).scale(scale),
+        );
+    }
+
+    // `input` is in the racket's frame: "up" moves towards the start of the wall (left for horizontal rackets).
     pub fn update(&mut self, input: &crate::player::controller::ControllerInput, delta_time: f32) {
+        let mut position = self.local_position();
+        let speed = RACKET_SPEED * self.speed_scale;
         match self.controller.get_action(input) {
             MoveUp => {
-                self.position_y -= RACKET_SPEED * delta_time;
+                position.y -= speed * delta_time;
             }
             MoveDown => {
-                self.position_y += RACKET_SPEED * delta_time;
+                position.y += speed * delta_time;
             }
             Stay => {}
         }
 
         // Keep the racket inside the screen bounds
-        let half_height = RACKET_HEIGHT / 2.0;
-        if self.position_y < half_height {
-            self.position_y = half_height;
+        let half_height = self.half_length();
+        if position.y < half_height {
+            position.y = half_height;
         }
         let lower_limit = input.screen_height - half_height;
-        if self.position_y > lower_limit {
-            self.position_y = lower_limit;
+        if position.y > lower_limit {
+            position.y = lower_limit;
         }
+
+        let position = self.orientation.to_local(position);
+        self.position_x = position.x;
+        self.position_y = position.y;
     }
 }
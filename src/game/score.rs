@@ -1,60 +1,55 @@
+use crate::game::mode::{self, GameMode};
+use crate::game::physics::Player;
 use ggez::graphics::{Canvas, Color, DrawParam, PxScale, Text};
 use ggez::{Context, GameResult, glam::Vec2};
 
+#[derive(Debug, Clone, Default)]
 pub struct Score {
-    p1: u8,
-    p2: u8,
-    text: Text,
-    position: Vec2,
-    scale: f32,
+    // Indexed by `Player::index`
+    points: [u8; Player::COUNT],
 }
 
 impl Score {
-    pub fn new(context: &mut Context) -> GameResult<Self> {
-        let p1 = 0;
-        let p2 = 0;
-        let scale = context.gfx.drawable_size().1 / 3.0;
-        let mut text = Text::new(format!("{}   {}", p1, p2));
-        text.set_scale(PxScale::from(scale));
-        let text_dimensions = text.measure(context)?;
-        let position = Vec2::new(
-            context.gfx.drawable_size().0 / 2.0 - text_dimensions.x / 2.0,
-            context.gfx.drawable_size().1 / 2.0 - text_dimensions.y / 2.0,
-        );
-
-        Ok(Self { p1, p2, text, position, scale })
-    }
-
-    fn update_text(&mut self, context: &mut Context) -> GameResult<()> {
-        self.text = Text::new(format!("{}   {}", self.p1, self.p2));
-        self.text.set_scale(PxScale::from(self.scale));
-        let text_dimensions = self.text.measure(context)?;
-        self.position = Vec2::new(
-            context.gfx.drawable_size().0 / 2.0 - text_dimensions.x / 2.0,
-            context.gfx.drawable_size().1 / 2.0 - text_dimensions.y / 2.0,
-        );
-        Ok(())
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    pub fn increment_p1(&mut self, context: &mut Context) -> GameResult<()> {
-        self.p1 = self.p1.saturating_add(1);
-        self.update_text(context)
+    // Award a point to the given player.
+    pub fn increment(&mut self, player: Player) {
+        let points = &mut self.points[player.index()];
+        *points = points.saturating_add(1);
     }
 
-    pub fn increment_p2(&mut self, context: &mut Context) -> GameResult<()> {
-        self.p2 = self.p2.saturating_add(1);
-        self.update_text(context)
-    }
-
-    pub fn draw_on_canvas(&self, canvas: &mut Canvas) {
-        canvas.draw(&self.text, DrawParam::default().dest(self.position).color(Color::from_rgb(50, 50, 50)));
-    }
-
-    pub fn get_p1_score(&self) -> u8 {
-        self.p1
+    // Classic and team scores are shown large in the middle; free-for-all scores sit next to each player's wall.
+    // Survival and wall practice have no score of their own; the challenge status shows how the run is going.
+    pub fn draw_on_canvas(&self, context: &mut Context, canvas: &mut Canvas, color: Color, game_mode: GameMode) -> GameResult {
+        let (screen_width, screen_height) = context.gfx.drawable_size();
+        let center = Vec2::new(screen_width / 2.0, screen_height / 2.0);
+        let (left, right) = match game_mode {
+            GameMode::Survival | GameMode::WallPractice => return Ok(()),
+            GameMode::Classic | GameMode::PongOut | GameMode::TimeAttack => (self.get(Player::Left), self.get(Player::Right)),
+            GameMode::Teams | GameMode::Doubles => (mode::team_score(self, 0), mode::team_score(self, 1)),
+            GameMode::FreeForAll => {
+                for player in Player::WALLS {
+                    let mut text = Text::new(self.get(player).to_string());
+                    text.set_scale(PxScale::from(screen_height / 6.0));
+                    let text_dimensions = text.measure(context)?;
+                    let offset = -player.inward() * Vec2::new(screen_width, screen_height) / 5.0;
+                    let position = center + offset - Vec2::new(text_dimensions.x, text_dimensions.y) / 2.0;
+                    canvas.draw(&text, DrawParam::default().dest(position).color(color));
+                }
+                return Ok(());
+            }
+        };
+        let mut text = Text::new(format!("{}   {}", left, right));
+        text.set_scale(PxScale::from(screen_height / 3.0));
+        let text_dimensions = text.measure(context)?;
+        let position = Vec2::new(center.x - text_dimensions.x / 2.0, center.y - text_dimensions.y / 2.0);
+        canvas.draw(&text, DrawParam::default().dest(position).color(color));
+        Ok(())
     }
 
-    pub fn get_p2_score(&self) -> u8 {
-        self.p2
+    pub fn get(&self, player: Player) -> u8 {
+        self.points[player.index()]
     }
 }
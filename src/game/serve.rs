@@ -0,0 +1,177 @@
+// Serve rules: who serves after each point, and the keys a human uses to serve themselves.
+//
+// The server is the player the ball travels away from. By default the ball waits in the middle for a countdown and
+// then leaves towards the opposite wall; with human serves on, a human server holds the ball on their racket, aims
+// with their movement keys and serves with their serve key.
+
+use crate::game::physics::Player;
+use crate::player::player_type::movement_keys;
+use ggez::input::keyboard::KeyCode;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// Steepest human serve: the vertical speed at full aim, relative to the horizontal speed
+pub const MAX_SERVE_AIM: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServeRule {
+    // A coin toss before every serve
+    #[default]
+    Random,
+    // The player who let the ball through their wall serves the next one
+    Loser,
+    // Players take turns, in the order of `GameMode::players`
+    Alternate,
+}
+
+impl ServeRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ServeRule::Random => "Random",
+            ServeRule::Loser => "Loser serves",
+            ServeRule::Alternate => "Alternate",
+        }
+    }
+
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            ServeRule::Random => "random",
+            ServeRule::Loser => "loser",
+            ServeRule::Alternate => "alternate",
+        }
+    }
+
+    pub fn from_cli_name(name: &str) -> Option<Self> {
+        [ServeRule::Random, ServeRule::Loser, ServeRule::Alternate]
+            .into_iter()
+            .find(|rule| rule.cli_name() == name.to_ascii_lowercase())
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ServeRule::Random => ServeRule::Loser,
+            ServeRule::Loser => ServeRule::Alternate,
+            ServeRule::Alternate => ServeRule::Random,
+        }
+    }
+
+    // Who of `players` serves next. `previous` is the last server and `conceded` who let the last ball through, both
+    // `None` before the first serve of a match, which is always drawn at random.
+    pub fn next_server(&self, previous: Option<Player>, conceded: Option<Player>, players: &[Player], rng: &mut impl Rng) -> Player {
+        match (self, previous, conceded) {
+            (ServeRule::Loser, _, Some(conceded)) => conceded,
+            (ServeRule::Alternate, Some(previous), _) => {
+                let index = players.iter().position(|&player| player == previous).unwrap_or(0);
+                players[(index + 1) % players.len()]
+            }
+            _ => players[rng.random_range(0..players.len())],
+        }
+    }
+}
+
+// The player across the field.
+pub fn opponent(player: Player) -> Player {
+    match player {
+        Player::Left => Player::Right,
+        Player::Right => Player::Left,
+        Player::Top => Player::Bottom,
+        Player::Bottom => Player::Top,
+        Player::LeftFront => Player::RightFront,
+        Player::RightFront => Player::LeftFront,
+    }
+}
+
+// Each serve key points into the field from the server's wall.
+pub fn serve_key(player: Player) -> KeyCode {
+    match player {
+        Player::Left => KeyCode::D,
+        Player::Right => KeyCode::Left,
+        Player::Top => KeyCode::K,
+        Player::Bottom => KeyCode::Numpad8,
+        Player::LeftFront => KeyCode::H,
+        Player::RightFront => KeyCode::Numpad4,
+    }
+}
+
+// What a holding server is told on screen.
+pub fn serve_prompt(player: Player) -> &'static str {
+    match player {
+        Player::Left => "Press D to serve (W/S to aim)",
+        Player::Right => "Press LEFT to serve (UP/DOWN to aim)",
+        Player::Top => "Press K to serve (J/L to aim)",
+        Player::Bottom => "Press NUMPAD 8 to serve (NUMPAD 4/6 to aim)",
+        Player::LeftFront => "Press H to serve (T/G to aim)",
+        Player::RightFront => "Press NUMPAD 4 to serve (NUMPAD 8/5 to aim)",
+    }
+}
+
+// Aim of a held serve from the server's movement keys: -1 serves upwards (or left), 1 downwards (or right), 0 straight.
+pub fn aim(player: Player, pressed_keys: &HashSet<KeyCode>) -> f32 {
+    let (up, down) = movement_keys(player);
+    let direction = pressed_keys.contains(&down) as i32 - pressed_keys.contains(&up) as i32;
+    direction as f32 * MAX_SERVE_AIM
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn rules_pick_the_server() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let classic = [Player::Left, Player::Right];
+        assert_eq!(
+            ServeRule::Loser.next_server(Some(Player::Left), Some(Player::Right), &classic, &mut rng),
+            Player::Right
+        );
+        assert_eq!(
+            ServeRule::Loser.next_server(Some(Player::Left), Some(Player::Left), &classic, &mut rng),
+            Player::Left
+        );
+        assert_eq!(
+            ServeRule::Alternate.next_server(Some(Player::Left), Some(Player::Left), &classic, &mut rng),
+            Player::Right
+        );
+        assert_eq!(
+            ServeRule::Alternate.next_server(Some(Player::Right), Some(Player::Left), &classic, &mut rng),
+            Player::Left
+        );
+        assert_eq!(
+            ServeRule::Alternate.next_server(Some(Player::Top), None, &Player::WALLS, &mut rng),
+            Player::Bottom
+        );
+        assert_eq!(
+            ServeRule::Alternate.next_server(Some(Player::Bottom), None, &Player::WALLS, &mut rng),
+            Player::Left
+        );
+
+        let servers: Vec<Player> = (0..80)
+            .map(|_| ServeRule::Random.next_server(Some(Player::Left), None, &Player::WALLS, &mut rng))
+            .collect();
+        assert!(Player::WALLS.iter().all(|player| servers.contains(player)));
+    }
+
+    #[test]
+    fn aim_follows_the_movement_keys() {
+        let keys = |keys: &[KeyCode]| keys.iter().copied().collect::<HashSet<_>>();
+        assert_eq!(aim(Player::Left, &keys(&[KeyCode::W])), -MAX_SERVE_AIM);
+        assert_eq!(aim(Player::Left, &keys(&[KeyCode::Down])), 0.0);
+        assert_eq!(aim(Player::Right, &keys(&[KeyCode::Down])), MAX_SERVE_AIM);
+        assert_eq!(aim(Player::Right, &keys(&[KeyCode::Up, KeyCode::Down])), 0.0);
+        assert_eq!(aim(Player::Top, &keys(&[KeyCode::J])), -MAX_SERVE_AIM);
+    }
+
+    #[test]
+    fn cli_names_round_trip() {
+        for rule in [ServeRule::Random, ServeRule::Loser, ServeRule::Alternate] {
+            assert_eq!(ServeRule::from_cli_name(rule.cli_name()), Some(rule));
+            assert_ne!(rule.next(), rule);
+        }
+        assert_eq!(ServeRule::from_cli_name("ALTERNATE"), Some(ServeRule::Alternate));
+        assert_eq!(ServeRule::from_cli_name("winner"), None);
+    }
+}
@@ -0,0 +1,1071 @@
+use crate::game::arena::{Arena, Body};
+use crate::game::ball::{BALL_SIZE, BALL_SPEED, BALL_SPEED_MAX, Ball, Stuck};
+use crate::game::bricks::{Brick, BrickWalls};
+use crate::game::challenge::{ChallengeResult, ChallengeRun};
+use crate::game::countdown::Countdown;
+use crate::game::events::{EventQueue, GameEvent};
+use crate::game::mode::{self, GameMode};
+use crate::game::multiball::{self, MAX_BALLS, MultiBall, SPAWN_INTERVAL};
+use crate::game::physics::*;
+use crate::game::powerup::{FieldPowerUp, Modifiers, PowerUpKind, PowerUpRules, PowerUps};
+use crate::game::racket::{Orientation, RACKET_WIDTH_HALF, Racket};
+use crate::game::score::Score;
+use crate::game::serve::{self, ServeRule};
+use crate::player::controller::{BallView, ControllerInput};
+use crate::player::player_type::PlayerType;
+use ggez::glam::Vec2;
+use ggez::input::keyboard::KeyCode;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+
+pub const DEFAULT_WINNING_SCORE: u8 = 10;
+// Space between a held ball and the server's racket
+const HELD_BALL_GAP: f32 = 4.0;
+// Share of a ball's speed that always goes across a classic field, after balls collide
+const MIN_ACROSS: f32 = 0.35;
+// Largest random turn (radians) given to balls bouncing off obstacles or going through portals, so no ball gets
+// caught in a loop between them
+const OBSTACLE_JITTER: f32 = 0.08;
+// Seconds a sticky racket holds the ball
+const STICK_TIME: f32 = 0.6;
+// Balls picked up from a multi-ball power-up, and how far apart they fan out (radians)
+const POWER_UP_EXTRA_BALLS: usize = 2;
+const POWER_UP_BALL_SPREAD: f32 = 0.35;
+// An invisible ball flashes into view for `INVISIBLE_FLASH` seconds every `INVISIBLE_FLASH_PERIOD`
+const INVISIBLE_FLASH_PERIOD: f32 = 1.0;
+const INVISIBLE_FLASH: f32 = 0.12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ServePhase {
+    // The ball waits for `server` to serve: after the countdown, or when a human server presses their serve key
+    Waiting { server: Player },
+    InPlay,
+}
+
+// A match of Pong with no graphics or audio attached.
+// The windowed game drives it once per frame; headless runs and tests drive it directly.
+// Everything notable that happens is reported as `GameEvent`s.
+pub struct Simulation {
+    pub width: f32,
+    pub height: f32,
+    // Balls in play; the first one is served (and held by a human server), the others join it with multi-ball.
+    // Never empty.
+    pub balls: Vec<Ball>,
+    pub score: Score,
+    pub winning_score: u8,
+    pub serve_rule: ServeRule,
+    // Human servers hold the ball on their racket and serve with a key instead of waiting for the countdown
+    pub human_serve: bool,
+    pub multi_ball: MultiBall,
+    pub power_up_rules: PowerUpRules,
+    mode: GameMode,
+    // Indexed by `Player::index`; only the players of the current mode have a racket
+    rackets: [Option<Racket>; Player::COUNT],
+    player_types: [PlayerType; Player::COUNT],
+    phase: ServePhase,
+    last_server: Option<Player>,
+    // Seconds of rally since the serve or the last extra ball
+    spawn_timer: f32,
+    power_ups: PowerUps,
+    arena: Arena,
+    // Pong-Out's walls; empty in the other modes
+    bricks: BrickWalls,
+    // The run of a single-player challenge
+    challenge: Option<ChallengeRun>,
+    // Seconds since the match started, which moving obstacles follow
+    arena_time: f32,
+    // Runs while a timed serve waits, and shows "GO" once the ball is in play
+    countdown: Option<Countdown>,
+    events: EventQueue,
+    rng: StdRng,
+}
+
+impl Simulation {
+    pub fn new(width: f32, height: f32, left_type: PlayerType, right_type: PlayerType, winning_score: u8, seed: Option<u64>) -> Self {
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        let ball = Ball::new(width / 2.0, height / 2.0, &mut rng);
+
+        let mut simulation = Self {
+            width,
+            height,
+            balls: vec![ball],
+            score: Score::new(),
+            winning_score,
+            serve_rule: ServeRule::default(),
+            human_serve: false,
+            multi_ball: MultiBall::default(),
+            power_up_rules: PowerUpRules::default(),
+            mode: GameMode::Classic,
+            rackets: Default::default(),
+            player_types: [PlayerType::AIMedium; Player::COUNT],
+            phase: ServePhase::InPlay,
+            last_server: None,
+            spawn_timer: 0.0,
+            power_ups: PowerUps::new(),
+            arena: Arena::open(),
+            bricks: BrickWalls::new(),
+            challenge: None,
+            arena_time: 0.0,
+            countdown: None,
+            events: EventQueue::new(),
+            rng,
+        };
+        simulation.set_player_types(left_type, right_type);
+        simulation.prepare_serve(None);
+        simulation
+    }
+
+    // Recreate both side rackets, centered, with controllers for the given player types.
+    pub fn set_player_types(&mut self, left_type: PlayerType, right_type: PlayerType) {
+        for (player, player_type) in [(Player::Left, left_type), (Player::Right, right_type)] {
+            self.player_types[player.index()] = player_type;
+            self.rackets[player.index()] = Some(self.create_racket(player));
+        }
+    }
+
+    // Switch game mode. `third_type` and `fourth_type` control Players 3 and 4 in the four-player modes: the top
+    // and bottom rackets, or the front rackets in doubles. Call `reset` afterwards to start a match in the new mode.
+    pub fn set_mode(&mut self, mode: GameMode, third_type: PlayerType, fourth_type: PlayerType) {
+        self.mode = mode;
+        for (&player, player_type) in mode.players().iter().skip(2).zip([third_type, fourth_type]) {
+            self.player_types[player.index()] = player_type;
+        }
+        for player in Player::ALL {
+            self.rackets[player.index()] = mode.players().contains(&player).then(|| self.create_racket(player));
+        }
+    }
+
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
+
+    fn create_racket(&self, player: Player) -> Racket {
+        let controller = self.player_types[player.index()].create_controller_for_player(player);
+        Racket::for_player(player, self.width, self.height, controller)
+    }
+
+    pub fn racket(&self, player: Player) -> Option<&Racket> {
+        self.rackets[player.index()].as_ref()
+    }
+
+    fn racket_mut(&mut self, player: Player) -> Option<&mut Racket> {
+        self.rackets[player.index()].as_mut()
+    }
+
+    // Every racket in play, with its player.
+    pub fn rackets(&self) -> impl Iterator<Item = (Player, &Racket)> {
+        Player::ALL.into_iter().filter_map(|player| self.racket(player).map(|racket| (player, racket)))
+    }
+
+    // Play in `arena` from now on; obstacles start over where they begin.
+    pub fn set_arena(&mut self, arena: Arena) {
+        self.arena = arena;
+        self.arena_time = 0.0;
+    }
+
+    pub fn arena(&self) -> &Arena {
+        &self.arena
+    }
+
+    // The obstacles of the arena, where they are now.
+    pub fn obstacles(&self) -> Vec<Body> {
+        self.arena.bodies(self.arena_time, self.width, self.height)
+    }
+
+    // Update the arena size, keeping every racket against its wall.
+    pub fn resize(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+        for &player in self.mode.players() {
+            if let Some(racket) = self.racket_mut(player) {
+                racket.place(player, width, height);
+            }
+        }
+    }
+
+    // Start a new match: center everything and clear the score.
+    pub fn reset(&mut self) {
+        let (width, height) = (self.width, self.height);
+        for &player in self.mode.players() {
+            if let Some(racket) = self.racket_mut(player) {
+                racket.center(width, height);
+            }
+        }
+        self.score = Score::new();
+        self.events.drain();
+        self.last_server = None;
+        self.power_ups.clear();
+        self.bricks = if self.mode == GameMode::PongOut {
+            BrickWalls::build()
+        } else {
+            BrickWalls::new()
+        };
+        self.challenge = ChallengeRun::new(self.mode);
+        self.arena_time = 0.0;
+        self.prepare_serve(None);
+    }
+
+    // Stop a single ball in the middle and wait for the next server, chosen by the serve rule.
+    fn prepare_serve(&mut self, conceded: Option<Player>) {
+        let server = self.serve_rule.next_server(self.last_server, conceded, self.mode.players(), &mut self.rng);
+        let center = self.center();
+        if self.balls.is_empty() {
+            self.balls.push(Ball::new(center.x, center.y, &mut self.rng));
+        }
+        self.balls.truncate(1);
+        let ball = &mut self.balls[0];
+        ball.position = center;
+        ball.velocity = Vec2::ZERO;
+        ball.speed = BALL_SPEED;
+        ball.last_hitter = None;
+        self.phase = ServePhase::Waiting { server };
+        self.countdown = None;
+    }
+
+    fn center(&self) -> Vec2 {
+        Vec2::new(self.width / 2.0, self.height / 2.0)
+    }
+
+    fn holds_serve(&self, server: Player) -> bool {
+        self.human_serve && self.player_types[server.index()] == PlayerType::Human
+    }
+
+    // Launch the ball away from `server`. `aim` sets the direction along the server's wall, from -1 (up, or left)
+    // to 1 (down, or right). With multi-ball the extra balls leave the middle of the field at the same time.
+    fn launch(&mut self, server: Player, aim: f32) {
+        let inward = server.inward();
+        let along = server.orientation().to_local(Vec2::Y);
+        let center = self.center();
+        let speed = (BALL_SPEED * self.pace()).min(BALL_SPEED_MAX);
+        for (index, aim) in multiball::serve_aims(self.multi_ball.ball_count(), aim).into_iter().enumerate() {
+            if index == self.balls.len() {
+                self.balls.push(Ball::new(center.x, center.y, &mut self.rng));
+            }
+            let ball = &mut self.balls[index];
+            ball.speed = speed;
+            ball.velocity = (inward + along * aim).normalize() * ball.speed;
+        }
+        self.phase = ServePhase::InPlay;
+        self.last_server = Some(server);
+        self.spawn_timer = 0.0;
+        self.events.emit(GameEvent::Serve { server, speed });
+    }
+
+    // The ball waits for the serve: held on a human server's racket, or in the middle during the countdown.
+    fn wait_for_serve(&mut self, server: Player, pressed_keys: &HashSet<KeyCode>) {
+        let held_by = self
+            .racket(server)
+            .filter(|_| self.holds_serve(server))
+            .map(|racket| Vec2::new(racket.position_x, racket.position_y));
+        if let Some(racket_center) = held_by {
+            self.countdown = None;
+            let gap = RACKET_WIDTH_HALF + BALL_SIZE / 2.0 + HELD_BALL_GAP;
+            self.balls[0].position = racket_center + server.inward() * gap;
+            if pressed_keys.contains(&serve::serve_key(server)) {
+                self.launch(server, serve::aim(server, pressed_keys));
+            }
+            return;
+        }
+
+        let countdown = self.countdown.get_or_insert_with(Countdown::new);
+        if !countdown.is_blocking() {
+            let aim = if self.rng.random_bool(0.5) { 1.0 } else { -1.0 };
+            self.launch(server, aim);
+        }
+    }
+
+    // The countdown of a timed serve, until its "GO" is over.
+    pub fn serve_countdown(&self) -> Option<&Countdown> {
+        self.countdown.as_ref()
+    }
+
+    // The human server holding the ball, if any.
+    pub fn held_serve(&self) -> Option<Player> {
+        match self.phase {
+            ServePhase::Waiting { server } if self.holds_serve(server) => Some(server),
+            _ => None,
+        }
+    }
+
+    // The winner of the match. A challenge is won by its player on the left, or lost to the other side.
+    pub fn winner(&self) -> Option<Player> {
+        if let Some(challenge) = &self.challenge {
+            return challenge.result().map(|result| if result.won { Player::Left } else { Player::Right });
+        }
+        match self.mode {
+            GameMode::PongOut => self.bricks.winner(),
+            _ => self.mode.winner(&self.score, self.winning_score),
+        }
+    }
+
+    pub fn challenge(&self) -> Option<&ChallengeRun> {
+        self.challenge.as_ref()
+    }
+
+    // How the challenge went, once it is over.
+    pub fn challenge_result(&self) -> Option<ChallengeResult> {
+        self.challenge.as_ref()?.result()
+    }
+
+    // How much faster the survival AI and serves are than normal; 1 outside of survival.
+    fn pace(&self) -> f32 {
+        self.challenge.as_ref().map_or(1.0, ChallengeRun::pace)
+    }
+
+    // Pong-Out bricks still standing.
+    pub fn bricks(&self) -> &[Brick] {
+        self.bricks.bricks()
+    }
+
+    // The two scores reported with point and match events: the left and right players', or the teams'.
+    fn side_scores(&self) -> (u8, u8) {
+        match self.mode {
+            GameMode::Teams | GameMode::Doubles => (mode::team_score(&self.score, 0), mode::team_score(&self.score, 1)),
+            _ => (self.score.get(Player::Left), self.score.get(Player::Right)),
+        }
+    }
+
+    // Advance the match by `delta_time` seconds and return the events that happened,
+    // including any emitted since the last step (e.g. the serve after a reset).
+    pub fn step(&mut self, delta_time: f32, pressed_keys: &HashSet<KeyCode>) -> Vec<GameEvent> {
+        self.advance(delta_time, pressed_keys);
+        self.events.drain()
+    }
+
+    fn advance(&mut self, delta_time: f32, pressed_keys: &HashSet<KeyCode>) {
+        self.arena_time += delta_time;
+        let modifiers = self.power_ups.modifiers(self.mode);
+        let pace = self.pace();
+        let balls: Vec<(Vec2, Vec2)> = self.balls.iter().map(|ball| (ball.position, ball.velocity)).collect();
+        for &player in self.mode.players() {
+            // Controllers see the field from their racket, which always moves along y
+            let wall_length = match player.orientation() {
+                Orientation::Vertical => self.height,
+                Orientation::Horizontal => self.width,
+            };
+            let Some(racket) = self.racket_mut(player) else {
+                continue;
+            };
+            racket.length_scale = modifiers.racket_length[player.index()];
+            racket.speed_scale = modifiers.racket_speed[player.index()];
+            if player == Player::Right {
+                racket.speed_scale *= pace;
+            }
+            let input = ControllerInput {
+                balls: balls
+                    .iter()
+                    .map(|&(position, velocity)| BallView {
+                        position: racket.orientation.to_local(position),
+                        velocity: racket.orientation.to_local(velocity),
+                    })
+                    .collect(),
+                racket_position: racket.travel_position(),
+                racket_x: racket.local_position().x,
+                racket_half_length: racket.half_length(),
+                screen_height: wall_length,
+                pressed_keys: pressed_keys.clone(),
+            };
+            let before = racket.travel_position();
+            racket.update(&input, delta_time);
+            let distance = racket.travel_position() - before;
+            if distance != 0.0 {
+                self.events.emit(GameEvent::RacketMoved { player, distance });
+            }
+        }
+
+        if let Some(countdown) = &mut self.countdown {
+            countdown.update(delta_time);
+            if countdown.is_finished() {
+                self.countdown = None;
+            }
+        }
+        if let ServePhase::Waiting { server } = self.phase {
+            self.wait_for_serve(server, pressed_keys);
+            return;
+        }
+        if let Some(challenge) = &mut self.challenge {
+            challenge.update(delta_time);
+            // Time attacks end on the clock
+            if let Some(winner) = self.winner() {
+                self.end_match(winner);
+                return;
+            }
+        }
+
+        let update = self.power_ups.update(delta_time, &self.power_up_rules, self.width, self.height, &mut self.rng);
+        if let Some(FieldPowerUp { kind, position, .. }) = update.spawned {
+            self.events.emit(GameEvent::PowerUpSpawned { kind, position });
+        }
+        for power_up in update.expired {
+            self.events.emit(GameEvent::PowerUpExpired {
+                kind: power_up.kind,
+                player: power_up.owner,
+            });
+        }
+
+        let obstacles = self.obstacles();
+        let mut index = 0;
+        while index < self.balls.len() {
+            if self.balls[index].stuck.is_some() {
+                self.hold_stuck_ball(index, delta_time);
+                index += 1;
+                continue;
+            }
+            // Before the walls, so a fast ball cannot slip past a brick into the goal
+            if self.hit_bricks(index) {
+                return;
+            }
+            match check_walls(&mut self.balls[index], self.width, self.height, self.mode.guarded_walls()) {
+                Some(WallContact::Bounce) => self.events.emit(GameEvent::WallBounce {
+                    position: self.balls[index].position,
+                    speed: self.balls[index].speed,
+                }),
+                Some(WallContact::Goal(conceded)) => {
+                    let ball = self.balls.remove(index);
+                    if self.goal(conceded, ball) {
+                        return;
+                    }
+                    continue;
+                }
+                None => {}
+            }
+
+            self.hit_obstacles(index, &obstacles);
+            if self.hit_rackets(index, &modifiers) {
+                return;
+            }
+            self.pick_up_power_up(index);
+            index += 1;
+        }
+        if self.balls.is_empty() {
+            return;
+        }
+
+        if self.multi_ball.collisions {
+            self.collide_balls();
+        }
+        if self.multi_ball.spawns {
+            self.spawn_timer += delta_time;
+            if self.spawn_timer >= SPAWN_INTERVAL && self.balls.len() < MAX_BALLS as usize {
+                self.spawn_timer = 0.0;
+                let center = self.center();
+                self.balls.push(Ball::new(center.x, center.y, &mut self.rng));
+                self.events.emit(GameEvent::BallSpawned { position: center });
+            }
+        }
+        for ball in self.balls.iter_mut().filter(|ball| ball.stuck.is_none()) {
+            ball.move_ball(delta_time * modifiers.ball_time_scale);
+        }
+    }
+
+    // Bounce the ball at `index` off the arena's obstacles, or send it through a portal.
+    fn hit_obstacles(&mut self, index: usize, obstacles: &[Body]) {
+        let ball = &mut self.balls[index];
+        for body in obstacles {
+            let Some(contact) = obstacle_collision(ball, body) else {
+                continue;
+            };
+            ball.velocity = Vec2::from_angle(self.rng.random_range(-OBSTACLE_JITTER..=OBSTACLE_JITTER)).rotate(ball.velocity);
+            let (normal, bumper) = match contact {
+                ObstacleContact::Bounce { normal } => (normal, false),
+                ObstacleContact::Bumped { normal } => (normal, true),
+                ObstacleContact::Teleported { from } => {
+                    self.events.emit(GameEvent::BallTeleported { from, to: ball.position });
+                    continue;
+                }
+            };
+            if !self.mode.guards_every_wall() {
+                limit_steepness(ball, MIN_ACROSS);
+            }
+            self.events.emit(GameEvent::ObstacleHit {
+                position: ball.position - normal * BALL_SIZE / 2.0,
+                normal,
+                speed: ball.speed,
+                bumper,
+            });
+        }
+    }
+
+    // Break the first brick the ball at `index` touches, bouncing the ball back into the field, and score the brick
+    // for the other side. Returns whether that won the match.
+    fn hit_bricks(&mut self, index: usize) -> bool {
+        let (width, height) = (self.width, self.height);
+        let ball = &mut self.balls[index];
+        let hit = self.bricks.bricks().iter().position(|brick| {
+            let (center, half_size) = brick.rect(width, height);
+            brick_collision(ball, center, half_size, brick.owner.inward()).is_some()
+        });
+        let Some(hit) = hit else {
+            return false;
+        };
+        limit_steepness(ball, MIN_ACROSS);
+        let (speed, last_hitter) = (ball.speed, ball.last_hitter);
+        let brick = self.bricks.break_brick(hit);
+        self.events.emit(GameEvent::BrickBroken {
+            owner: brick.owner,
+            position: brick.rect(width, height).0,
+            speed,
+        });
+        let winner = self.winner();
+        if let Some(scorer) = self.mode.scorer(brick.owner, last_hitter) {
+            self.score.increment(scorer);
+            let (left_score, right_score) = self.side_scores();
+            // The ball plays on after a brick, unless that cleared the wall
+            self.events.emit(GameEvent::PointScored {
+                scorer,
+                position: brick.rect(width, height).0,
+                left_score,
+                right_score,
+                rally_over: winner.is_some(),
+            });
+        }
+        let Some(winner) = winner else {
+            return false;
+        };
+        self.end_match(winner);
+        true
+    }
+
+    // Resolve the ball at `index` against every racket in play; the first one it touches returns it, or holds it
+    // for a moment if the racket is sticky. Returns whether the return completed a wall practice.
+    fn hit_rackets(&mut self, index: usize, modifiers: &Modifiers) -> bool {
+        let ball = &mut self.balls[index];
+        let hit = Player::ALL.into_iter().zip(&self.rackets).find_map(|(player, racket)| {
+            let racket = racket.as_ref()?;
+            racket_collision(ball, racket).map(|offset| (player, racket, offset))
+        });
+        let Some((player, racket, offset)) = hit else {
+            return false;
+        };
+        ball.last_hitter = Some(player);
+        if modifiers.sticky[player.index()] {
+            ball.stuck = Some(Stuck {
+                player,
+                along: offset * racket.half_length(),
+                remaining: STICK_TIME,
+            });
+        }
+        self.events.emit(GameEvent::BallHitRacket {
+            player,
+            position: ball.position,
+            speed: ball.speed,
+            offset,
+        });
+        let Some(challenge) = &mut self.challenge else {
+            return false;
+        };
+        challenge.record_return(player);
+        let Some(winner) = self.winner() else {
+            return false;
+        };
+        self.end_match(winner);
+        true
+    }
+
+    // Keep a stuck ball on its racket, then let it go the way it was hit.
+    fn hold_stuck_ball(&mut self, index: usize, delta_time: f32) {
+        let ball = &mut self.balls[index];
+        let Some(stuck) = &mut ball.stuck else {
+            return;
+        };
+        stuck.remaining -= delta_time;
+        let Some(racket) = self.rackets[stuck.player.index()].as_ref() else {
+            ball.stuck = None;
+            return;
+        };
+        let center = Vec2::new(racket.position_x, racket.position_y);
+        let along = racket.orientation.to_local(Vec2::Y) * stuck.along.clamp(-racket.half_length(), racket.half_length());
+        ball.position = center + racket.facing * (RACKET_WIDTH_HALF + BALL_SIZE / 2.0 + HELD_BALL_GAP) + along;
+        if stuck.remaining <= 0.0 {
+            ball.stuck = None;
+        }
+    }
+
+    // The last player to hit the ball at `index` collects any power-up it passes through.
+    fn pick_up_power_up(&mut self, index: usize) {
+        let ball = &self.balls[index];
+        let Some(player) = ball.last_hitter else {
+            return;
+        };
+        let Some(power_up) = self.power_ups.take_at(ball.position, BALL_SIZE) else {
+            return;
+        };
+        let (position, velocity, speed) = (ball.position, ball.velocity, ball.speed);
+        self.power_ups.activate(power_up.kind, player);
+        self.events.emit(GameEvent::PowerUpCollected {
+            kind: power_up.kind,
+            player,
+            position: power_up.position,
+        });
+        if power_up.kind == PowerUpKind::MultiBall {
+            // Extra balls split off the collecting ball, on either side of its path
+            let room = (MAX_BALLS as usize).saturating_sub(self.balls.len()).min(POWER_UP_EXTRA_BALLS);
+            for side in [-1.0, 1.0].into_iter().take(room) {
+                let mut ball = Ball::new(position.x, position.y, &mut self.rng);
+                ball.velocity = Vec2::from_angle(side * POWER_UP_BALL_SPREAD).rotate(velocity);
+                ball.speed = speed;
+                ball.last_hitter = Some(player);
+                self.balls.push(ball);
+                self.events.emit(GameEvent::BallSpawned { position });
+            }
+        }
+    }
+
+    pub fn power_ups(&self) -> &PowerUps {
+        &self.power_ups
+    }
+
+    // Whether the balls can be seen: invisible balls only flash into view now and then.
+    pub fn balls_visible(&self) -> bool {
+        let left = self.power_ups.invisibility_left();
+        left <= 0.0 || left % INVISIBLE_FLASH_PERIOD < INVISIBLE_FLASH
+    }
+
+    // Bounce every pair of touching balls off each other. Stuck balls stay out of it.
+    fn collide_balls(&mut self) {
+        for first in 0..self.balls.len() {
+            let (head, tail) = self.balls.split_at_mut(first + 1);
+            let ball = &mut head[first];
+            if ball.stuck.is_some() {
+                continue;
+            }
+            for other in tail.iter_mut().filter(|other| other.stuck.is_none()) {
+                if ball_collision(ball, other) {
+                    if !self.mode.guards_every_wall() {
+                        limit_steepness(ball, MIN_ACROSS);
+                        limit_steepness(other, MIN_ACROSS);
+                    }
+                    self.events.emit(GameEvent::BallsCollided {
+                        position: (ball.position + other.position) / 2.0,
+                        speed: ball.speed.max(other.speed),
+                    });
+                }
+            }
+        }
+    }
+
+    // `ball` went through `conceded`'s wall: score the point, then end the match, or prepare the next serve once
+    // no ball is left. Returns whether the match is over.
+    fn goal(&mut self, conceded: Player, ball: Ball) -> bool {
+        let rally_over = self.balls.is_empty();
+        if let Some(challenge) = &mut self.challenge {
+            challenge.record_goal(conceded);
+        }
+        if let Some(scorer) = self.mode.scorer(conceded, ball.last_hitter) {
+            self.score.increment(scorer);
+            if self.mode == GameMode::PongOut {
+                self.bricks.breach(scorer);
+            }
+            let (left_score, right_score) = self.side_scores();
+            let winner = self.winner();
+            self.events.emit(GameEvent::PointScored {
+                scorer,
+                position: ball.position,
+                left_score,
+                right_score,
+                rally_over: rally_over || winner.is_some(),
+            });
+            if let Some(winner) = winner {
+                self.end_match(winner);
+                // The ball that ended the match stays where it left the field
+                self.balls.push(ball);
+                return true;
+            }
+        }
+        if rally_over {
+            self.prepare_serve(Some(conceded));
+        }
+        false
+    }
+
+    fn end_match(&mut self, winner: Player) {
+        let (left_score, right_score) = self.side_scores();
+        self.events.emit(GameEvent::MatchWon {
+            winner,
+            left_score,
+            right_score,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::events::{EventRecorder, GameEventListener};
+    use crate::game::powerup::SpawnRate;
+    use crate::game::stats::MatchStats;
+
+    const FRAME: f32 = 1.0 / 60.0;
+
+    // Play until someone wins, feeding every event to `listener`.
+    fn run_to_end(simulation: &mut Simulation, listener: &mut dyn GameEventListener) -> Option<Player> {
+        let keys = HashSet::new();
+        for _ in 0..200_000 {
+            for event in simulation.step(FRAME, &keys) {
+                listener.on_event(&event);
+            }
+            if let Some(winner) = simulation.winner() {
+                return Some(winner);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn ai_match_reaches_winning_score() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIEasy, PlayerType::AIHard, 3, Some(7));
+        let mut recorder = EventRecorder::default();
+        let winner = run_to_end(&mut simulation, &mut recorder).expect("match should finish");
+        assert_eq!(simulation.score.get(winner), 3);
+        assert!(simulation.score.get(Player::Left) < 3 || simulation.score.get(Player::Right) < 3);
+        assert!(matches!(recorder.events.last(), Some(GameEvent::MatchWon { winner: w, .. }) if *w == winner));
+    }
+
+    #[test]
+    fn rallies_alternate_between_rackets() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIHard, PlayerType::AIHard, 3, Some(5));
+        let mut recorder = EventRecorder::default();
+        run_to_end(&mut simulation, &mut recorder).expect("match should finish");
+
+        // Every point is preceded by a serve, and within a rally the rackets take turns
+        let mut last_hitter = None;
+        let mut serves = 0;
+        let mut points = 0;
+        for event in &recorder.events {
+            match *event {
+                GameEvent::Serve { .. } => {
+                    serves += 1;
+                    last_hitter = None;
+                }
+                GameEvent::BallHitRacket { player, offset, speed, .. } => {
+                    assert_ne!(last_hitter, Some(player));
+                    assert!((-1.0..=1.0).contains(&offset));
+                    assert!(speed > 0.0);
+                    last_hitter = Some(player);
+                }
+                GameEvent::PointScored { .. } => points += 1,
+                _ => {}
+            }
+        }
+        assert_eq!(serves, points);
+    }
+
+    #[test]
+    fn held_serve_waits_for_the_serve_key() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::Human, PlayerType::Human, 3, Some(9));
+        simulation.human_serve = true;
+        simulation.reset();
+        let server = simulation.held_serve().expect("a human holds the first serve");
+
+        let mut keys = HashSet::new();
+        for _ in 0..300 {
+            assert!(simulation.step(FRAME, &keys).is_empty());
+        }
+        assert_eq!(simulation.balls[0].velocity, Vec2::ZERO);
+        assert!(simulation.serve_countdown().is_none());
+
+        let (_, down) = crate::player::player_type::movement_keys(server);
+        keys.extend([serve::serve_key(server), down]);
+        let events = simulation.step(FRAME, &keys);
+        // The aim key also moves the racket, carrying the ball with it
+        assert!(events.iter().any(|event| matches!(event, GameEvent::Serve { server: s, .. } if *s == server)));
+        assert_eq!(simulation.held_serve(), None);
+        let direction = if server == Player::Left { 1.0 } else { -1.0 };
+        assert_eq!(simulation.balls[0].velocity.x.signum(), direction);
+        assert!(simulation.balls[0].velocity.y > 0.0);
+    }
+
+    #[test]
+    fn loser_serves_after_each_point() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIEasy, PlayerType::AIHard, 3, Some(11));
+        simulation.serve_rule = ServeRule::Loser;
+        simulation.reset();
+        let mut recorder = EventRecorder::default();
+        run_to_end(&mut simulation, &mut recorder).expect("match should finish");
+
+        let mut last_scorer = None;
+        for event in &recorder.events {
+            match *event {
+                GameEvent::PointScored { scorer, .. } => last_scorer = Some(scorer),
+                GameEvent::Serve { server, .. } => {
+                    if let Some(scorer) = last_scorer {
+                        assert_eq!(server, serve::opponent(scorer));
+                    }
+                }
+                _ => {}
+            }
+        }
+        assert!(last_scorer.is_some());
+    }
+
+    #[test]
+    fn four_player_match_uses_every_wall() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIHard, PlayerType::AIHard, 3, Some(4));
+        simulation.set_mode(GameMode::FreeForAll, PlayerType::AIMedium, PlayerType::AIEasy);
+        simulation.reset();
+        let mut recorder = EventRecorder::default();
+        let winner = run_to_end(&mut simulation, &mut recorder).expect("match should finish");
+        assert_eq!(simulation.score.get(winner), 3);
+        assert_eq!(simulation.rackets().count(), 4);
+
+        // Rackets stay against their walls, and the horizontal ones play too
+        let top = simulation.racket(Player::Top).unwrap();
+        assert_eq!(top.position_y, crate::game::racket::RACKET_OFFSET);
+        let hitters: HashSet<Player> = recorder
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::BallHitRacket { player, .. } => Some(*player),
+                _ => None,
+            })
+            .collect();
+        assert!(hitters.contains(&Player::Top) || hitters.contains(&Player::Bottom));
+        assert!(recorder.events.iter().all(|event| !matches!(event, GameEvent::WallBounce { .. })));
+    }
+
+    #[test]
+    fn teams_share_their_points() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIEasy, PlayerType::AIHard, 4, Some(6));
+        simulation.set_mode(GameMode::Teams, PlayerType::AIEasy, PlayerType::AIHard);
+        simulation.reset();
+        let mut recorder = EventRecorder::default();
+        let winner = run_to_end(&mut simulation, &mut recorder).expect("match should finish");
+        assert!(matches!(winner, Player::Left | Player::Right));
+        let Some(GameEvent::MatchWon { left_score, right_score, .. }) = recorder.events.last() else {
+            panic!("the match should end with MatchWon");
+        };
+        assert_eq!(left_score.max(right_score), &4);
+        assert_eq!(*left_score, mode::team_score(&simulation.score, 0));
+    }
+
+    #[test]
+    fn doubles_front_rackets_join_the_rallies() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIMedium, PlayerType::AIMedium, 4, Some(12));
+        simulation.set_mode(GameMode::Doubles, PlayerType::AIEasy, PlayerType::AIEasy);
+        simulation.reset();
+        let mut recorder = EventRecorder::default();
+        let winner = run_to_end(&mut simulation, &mut recorder).expect("match should finish");
+        assert!(matches!(winner, Player::Left | Player::Right));
+        assert_eq!(simulation.rackets().count(), 4);
+        assert_eq!(simulation.racket(Player::Top).map(|_| ()), None);
+
+        // Top and bottom walls still bounce, and the front rackets get their hits in
+        assert!(recorder.events.iter().any(|event| matches!(event, GameEvent::WallBounce { .. })));
+        let front_hits = recorder
+            .events
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    GameEvent::BallHitRacket {
+                        player: Player::LeftFront | Player::RightFront,
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert!(front_hits > 0);
+        let Some(GameEvent::MatchWon { left_score, right_score, .. }) = recorder.events.last() else {
+            panic!("the match should end with MatchWon");
+        };
+        assert_eq!(
+            (*left_score, *right_score),
+            (mode::team_score(&simulation.score, 0), mode::team_score(&simulation.score, 1))
+        );
+    }
+
+    #[test]
+    fn multi_ball_scores_every_ball() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIMedium, PlayerType::AIMedium, 6, Some(21));
+        simulation.multi_ball = MultiBall {
+            balls: 3,
+            collisions: true,
+            spawns: true,
+        };
+        simulation.reset();
+        let keys = HashSet::new();
+        let mut events = Vec::new();
+        let mut most_balls = 0;
+        while simulation.winner().is_none() {
+            events.extend(simulation.step(FRAME, &keys));
+            most_balls = most_balls.max(simulation.balls.len());
+            assert!(!simulation.balls.is_empty());
+        }
+        assert!(most_balls >= 3);
+
+        // Every ball scores on its own, and only the last one of a rally ends it
+        let points = events.iter().filter(|event| matches!(event, GameEvent::PointScored { .. })).count();
+        let total = simulation.score.get(Player::Left) + simulation.score.get(Player::Right);
+        assert_eq!(points, total as usize);
+        let serves = events.iter().filter(|event| matches!(event, GameEvent::Serve { .. })).count();
+        let rallies = events
+            .iter()
+            .filter(|event| matches!(event, GameEvent::PointScored { rally_over: true, .. }))
+            .count();
+        assert_eq!(serves, rallies);
+        assert!(points > rallies);
+    }
+
+    #[test]
+    fn power_ups_are_collected_by_the_last_hitter() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIMedium, PlayerType::AIMedium, 8, Some(17));
+        simulation.power_up_rules = PowerUpRules {
+            enabled: true,
+            rate: SpawnRate::Frequent,
+        };
+        simulation.reset();
+        let mut recorder = EventRecorder::default();
+        run_to_end(&mut simulation, &mut recorder).expect("match should finish");
+
+        // Until a multi-ball power-up puts more balls in play, the collector is whoever hit the ball last
+        let mut last_hitter = None;
+        let mut single_ball = true;
+        let mut spawned = 0;
+        let mut collected = 0;
+        for event in &recorder.events {
+            match *event {
+                GameEvent::BallHitRacket { player, .. } => last_hitter = Some(player),
+                GameEvent::BallSpawned { .. } => single_ball = false,
+                GameEvent::PowerUpSpawned { .. } => spawned += 1,
+                GameEvent::PowerUpCollected { player, .. } => {
+                    if single_ball {
+                        assert_eq!(Some(player), last_hitter);
+                    }
+                    collected += 1;
+                }
+                _ => {}
+            }
+        }
+        assert!(spawned > 0 && collected > 0);
+        assert!(collected <= spawned);
+    }
+
+    #[test]
+    fn obstacles_stay_in_play_across_arenas() {
+        for arena in [Arena::bumpers(), Arena::portals(), Arena::pillars(), Arena::crescents()] {
+            let name = arena.name.clone();
+            let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIHard, PlayerType::AIHard, 3, Some(31));
+            simulation.set_arena(arena);
+            simulation.reset();
+            let mut recorder = EventRecorder::default();
+            let winner = run_to_end(&mut simulation, &mut recorder).expect("match should finish");
+            assert_eq!(simulation.score.get(winner), 3, "{}", name);
+            let contacts = recorder
+                .events
+                .iter()
+                .filter(|event| matches!(event, GameEvent::ObstacleHit { .. } | GameEvent::BallTeleported { .. }))
+                .count();
+            assert!(contacts > 0, "no ball reached an obstacle in {}", name);
+        }
+    }
+
+    #[test]
+    fn pong_out_is_won_on_bricks_or_a_goal() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIEasy, PlayerType::AIHard, 3, Some(5));
+        simulation.set_mode(GameMode::PongOut, PlayerType::AIMedium, PlayerType::AIMedium);
+        simulation.reset();
+        let full_wall = simulation.bricks().len() / 2;
+        let mut recorder = EventRecorder::default();
+        let winner = run_to_end(&mut simulation, &mut recorder).expect("match should finish");
+
+        let loser = serve::opponent(winner);
+        let broken = |owner| {
+            recorder
+                .events
+                .iter()
+                .filter(|event| matches!(event, GameEvent::BrickBroken { owner: o, .. } if *o == owner))
+                .count()
+        };
+        // Brick points come right after their brick
+        let goals = recorder
+            .events
+            .windows(2)
+            .filter(|pair| matches!(pair, [previous, GameEvent::PointScored { .. }] if !matches!(previous, GameEvent::BrickBroken { .. })))
+            .count();
+        assert!(broken(loser) > 0, "the loser's wall was never reached");
+        assert!(broken(loser) == full_wall || goals == 1);
+        // Every brick scored for the side that broke it, plus the goal
+        assert_eq!(simulation.score.get(winner) as usize, broken(loser) + goals);
+        assert_eq!(simulation.score.get(loser) as usize, broken(winner));
+        assert!(matches!(recorder.events.last(), Some(GameEvent::MatchWon { winner: w, .. }) if *w == winner));
+    }
+
+    #[test]
+    fn pong_out_stats_count_every_point() {
+        // Matches won on a goal
+        for seed in 1..=4 {
+            let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIEasy, PlayerType::AIHard, 3, Some(seed));
+            simulation.set_mode(GameMode::PongOut, PlayerType::AIMedium, PlayerType::AIMedium);
+            simulation.reset();
+            let mut stats = MatchStats::new();
+            run_to_end(&mut simulation, &mut stats).expect("match should finish");
+            for player in [Player::Left, Player::Right] {
+                assert_eq!(stats.points_won(player), simulation.score.get(player) as u32, "seed {}", seed);
+            }
+        }
+
+        // A match won by breaking the last brick of a wall closes its rally
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIEasy, PlayerType::AIEasy, 3, Some(1));
+        simulation.set_mode(GameMode::PongOut, PlayerType::AIMedium, PlayerType::AIMedium);
+        simulation.reset();
+        while simulation.bricks.remaining(Player::Right) > 1 {
+            let index = simulation.bricks().iter().position(|brick| brick.owner == Player::Right).unwrap();
+            simulation.bricks.break_brick(index);
+        }
+        let mut stats = MatchStats::new();
+        let keys = HashSet::new();
+        while !simulation
+            .step(FRAME, &keys)
+            .iter()
+            .inspect(|event| stats.on_event(event))
+            .any(|event| matches!(event, GameEvent::Serve { .. }))
+        {}
+        let last = *simulation.bricks().iter().find(|brick| brick.owner == Player::Right).unwrap();
+        let (center, half_size) = last.rect(simulation.width, simulation.height);
+        let ball = &mut simulation.balls[0];
+        ball.position = center - Vec2::new(half_size.x + BALL_SIZE, 0.0);
+        ball.velocity = Vec2::X;
+        ball.last_hitter = Some(Player::Left);
+        assert_eq!(run_to_end(&mut simulation, &mut stats), Some(Player::Left));
+        assert_eq!(simulation.score.get(Player::Left), 1);
+        assert_eq!(stats.points_won(Player::Left), 1);
+        assert_eq!(stats.rallies().len(), 1);
+    }
+
+    #[test]
+    fn challenges_end_on_their_own_rules() {
+        for mode in GameMode::CHALLENGES {
+            let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIHard, PlayerType::AIEasy, 3, Some(9));
+            simulation.set_mode(mode, PlayerType::AIMedium, PlayerType::AIMedium);
+            simulation.reset();
+            let mut recorder = EventRecorder::default();
+            let winner = run_to_end(&mut simulation, &mut recorder).expect("challenge should end");
+
+            let result = simulation.challenge_result().unwrap();
+            assert_eq!(result.mode, mode);
+            assert_eq!(result.won, winner == Player::Left);
+            assert!(matches!(recorder.events.last(), Some(GameEvent::MatchWon { winner: w, .. }) if *w == winner));
+            let run = simulation.challenge().unwrap();
+            match mode {
+                GameMode::Survival => assert!(!result.won && result.score == run.score()),
+                GameMode::TimeAttack => assert_eq!(run.time_left(), Some(0.0)),
+                _ => assert!(simulation.racket(Player::Right).is_none() && result.score == run.returns()),
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_replays_identically() {
+        let mut first = Simulation::new(1280.0, 720.0, PlayerType::AIMedium, PlayerType::AIHard, 2, Some(42));
+        let mut second = Simulation::new(1280.0, 720.0, PlayerType::AIMedium, PlayerType::AIHard, 2, Some(42));
+        let keys = HashSet::new();
+        for _ in 0..5_000 {
+            assert_eq!(first.step(FRAME, &keys), second.step(FRAME, &keys));
+            assert_eq!(first.balls[0].position, second.balls[0].position);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::game::events::{GameEvent, GameEventListener};
+use crate::game::physics::Player;
+
+// Summary of a single rally, from the serve to the point that ended it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RallyStats {
+    pub server: Player,
+    pub winner: Player,
+    // Racket hits, indexed by `Player::index`
+    pub hits: [u32; Player::COUNT],
+    // Points scored, indexed by `Player::index`: one for the winner, unless several balls were in play
+    pub points: [u32; Player::COUNT],
+    pub max_speed: f32,
+}
+
+impl RallyStats {
+    fn new(server: Player, initial_speed: f32) -> Self {
+        Self {
+            server,
+            winner: server,
+            hits: [0; Player::COUNT],
+            points: [0; Player::COUNT],
+            max_speed: initial_speed,
+        }
+    }
+
+    // Number of racket hits in the rally.
+    pub fn length(&self) -> u32 {
+        self.hits.iter().sum()
+    }
+
+    pub fn hits(&self, player: Player) -> u32 {
+        self.hits[player.index()]
+    }
+}
+
+// Statistics collected over a whole match.
+#[derive(Debug, Clone, Default)]
+pub struct MatchStats {
+    rallies: Vec<RallyStats>,
+    current: Option<RallyStats>,
+    // Indexed by `Player::index`
+    distance: [f32; Player::COUNT],
+}
+
+impl MatchStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // A new rally starts: `server` is the player the ball travels away from.
+    pub fn record_serve(&mut self, server: Player, speed: f32) {
+        self.current = Some(RallyStats::new(server, speed));
+    }
+
+    pub fn record_hit(&mut self, player: Player, speed: f32) {
+        if let Some(rally) = &mut self.current {
+            rally.hits[player.index()] += 1;
+            rally.max_speed = rally.max_speed.max(speed);
+        }
+    }
+
+    // Close the current rally, won by `winner`.
+    pub fn record_point(&mut self, winner: Player) {
+        self.record_ball_point(winner);
+        if let Some(rally) = self.current.take() {
+            self.rallies.push(rally);
+        }
+    }
+
+    // A ball of a multi-ball rally scored for `scorer`, but other balls are still in play.
+    pub fn record_ball_point(&mut self, scorer: Player) {
+        if let Some(rally) = &mut self.current {
+            rally.winner = scorer;
+            rally.points[scorer.index()] += 1;
+        }
+    }
+
+    pub fn record_racket_movement(&mut self, player: Player, distance: f32) {
+        self.distance[player.index()] += distance.abs();
+    }
+
+    // Completed rallies, in order.
+    pub fn rallies(&self) -> &[RallyStats] {
+        &self.rallies
+    }
+
+    pub fn longest_rally(&self) -> u32 {
+        self.rallies.iter().map(RallyStats::length).max().unwrap_or(0)
+    }
+
+    pub fn average_rally_length(&self) -> f32 {
+        if self.rallies.is_empty() {
+            return 0.0;
+        }
+        self.rallies.iter().map(RallyStats::length).sum::<u32>() as f32 / self.rallies.len() as f32
+    }
+
+    pub fn max_ball_speed(&self) -> f32 {
+        self.rallies.iter().map(|rally| rally.max_speed).fold(0.0, f32::max)
+    }
+
+    pub fn hits(&self, player: Player) -> u32 {
+        self.rallies.iter().map(|rally| rally.hits(player)).sum()
+    }
+
+    pub fn points_won(&self, player: Player) -> u32 {
+        self.rallies.iter().map(|rally| rally.points[player.index()]).sum()
+    }
+
+    // Points won by `player` on rallies they served.
+    pub fn points_won_on_serve(&self, player: Player) -> u32 {
+        self.rallies
+            .iter()
+            .filter(|rally| rally.server == player)
+            .map(|rally| rally.points[player.index()])
+            .sum()
+    }
+
+    pub fn distance_travelled(&self, player: Player) -> f32 {
+        self.distance[player.index()]
+    }
+}
+
+impl GameEventListener for MatchStats {
+    fn on_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::Serve { server, speed } => self.record_serve(server, speed),
+            GameEvent::BallHitRacket { player, speed, .. } => self.record_hit(player, speed),
+            GameEvent::RacketMoved { player, distance } => self.record_racket_movement(player, distance),
+            GameEvent::PointScored { scorer, rally_over: true, .. } => self.record_point(scorer),
+            GameEvent::PointScored { scorer, rally_over: false, .. } => self.record_ball_point(scorer),
+            GameEvent::WallBounce { .. }
+            | GameEvent::BallsCollided { .. }
+            | GameEvent::BallSpawned { .. }
+            | GameEvent::PowerUpSpawned { .. }
+            | GameEvent::PowerUpCollected { .. }
+            | GameEvent::PowerUpExpired { .. }
+            | GameEvent::ObstacleHit { .. }
+            | GameEvent::BallTeleported { .. }
+            | GameEvent::BrickBroken { .. }
+            | GameEvent::MatchWon { .. } => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::simulation::Simulation;
+    use crate::player::player_type::PlayerType;
+    use std::collections::HashSet;
+
+    #[test]
+    fn rallies_are_tracked_per_player() {
+        let mut stats = MatchStats::new();
+
+        stats.record_serve(Player::Left, 750.0);
+        stats.record_hit(Player::Right, 825.0);
+        stats.record_hit(Player::Left, 907.5);
+        stats.record_point(Player::Left);
+
+        stats.record_serve(Player::Right, 750.0);
+        stats.record_point(Player::Left);
+
+        assert_eq!(stats.rallies().len(), 2);
+        assert_eq!(stats.longest_rally(), 2);
+        assert_eq!(stats.average_rally_length(), 1.0);
+        assert_eq!(stats.max_ball_speed(), 907.5);
+        assert_eq!(stats.hits(Player::Left), 1);
+        assert_eq!(stats.hits(Player::Right), 1);
+        assert_eq!(stats.points_won(Player::Left), 2);
+        assert_eq!(stats.points_won_on_serve(Player::Left), 1);
+        assert_eq!(stats.points_won_on_serve(Player::Right), 0);
+    }
+
+    #[test]
+    fn multi_ball_rallies_count_every_point() {
+        let mut stats = MatchStats::new();
+        stats.record_serve(Player::Left, 750.0);
+        stats.record_ball_point(Player::Right);
+        stats.record_hit(Player::Left, 825.0);
+        stats.record_point(Player::Left);
+
+        assert_eq!(stats.rallies().len(), 1);
+        assert_eq!(stats.rallies()[0].winner, Player::Left);
+        assert_eq!(stats.points_won(Player::Right), 1);
+        assert_eq!(stats.points_won(Player::Left), 1);
+        assert_eq!(stats.points_won_on_serve(Player::Left), 1);
+    }
+
+    #[test]
+    fn racket_distance_ignores_direction() {
+        let mut stats = MatchStats::new();
+        stats.record_racket_movement(Player::Left, -10.0);
+        stats.record_racket_movement(Player::Left, 5.0);
+        assert_eq!(stats.distance_travelled(Player::Left), 15.0);
+        assert_eq!(stats.distance_travelled(Player::Right), 0.0);
+    }
+
+    #[test]
+    fn stats_follow_a_simulated_match() {
+        let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::AIMedium, PlayerType::AIHard, 4, Some(11));
+        let mut stats = MatchStats::new();
+        let keys = HashSet::new();
+        while simulation.winner().is_none() {
+            for event in simulation.step(1.0 / 60.0, &keys) {
+                stats.on_event(&event);
+            }
+        }
+
+        let points = simulation.score.get(Player::Left) as usize + simulation.score.get(Player::Right) as usize;
+        assert_eq!(stats.rallies().len(), points);
+        assert_eq!(stats.points_won(Player::Left), simulation.score.get(Player::Left) as u32);
+        assert!(stats.hits(Player::Left) + stats.hits(Player::Right) >= stats.longest_rally());
+        assert!(stats.distance_travelled(Player::Right) > 0.0);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::cli::CliOptions;
+use crate::game::arena::{self, Arena, ArenaError};
+use crate::game::challenge::ChallengeResult;
+use crate::game::mode::GameMode;
+use crate::game::physics::Player;
+use crate::game::simulation::Simulation;
+use crate::player::player_type::PlayerType;
+use serde::{Serialize, Serializer};
+use std::collections::HashSet;
+use std::path::Path;
+
+// Fixed time step used when there is no window to pace the frames.
+const HEADLESS_FRAME_TIME: f32 = 1.0 / 60.0;
+// Safety cap for matches that never end on their own (e.g. two idle humans): 30 simulated minutes.
+const HEADLESS_MAX_FRAMES: u64 = 60 * 60 * 30;
+
+// One player's line of the report. Players beyond the left and right ones are keyed by their wall.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlayerReport {
+    #[serde(skip)]
+    pub player: Player,
+    #[serde(rename = "type")]
+    pub player_type: String,
+    pub score: u8,
+}
+
+// The outcome of a headless run, printed as JSON. The mode is left out of classic matches.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeadlessReport {
+    #[serde(skip_serializing_if = "is_classic", serialize_with = "mode_name")]
+    pub mode: GameMode,
+    pub left: PlayerReport,
+    pub right: PlayerReport,
+    // Players 3 and 4, only in four-player modes
+    #[serde(flatten, serialize_with = "players_by_name")]
+    pub others: Vec<PlayerReport>,
+    // How a single-player challenge ended
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<ChallengeResult>,
+    #[serde(serialize_with = "player_name")]
+    pub winner: Option<Player>,
+    pub frames: u64,
+}
+
+fn is_classic(mode: &GameMode) -> bool {
+    *mode == GameMode::Classic
+}
+
+fn mode_name<S: Serializer>(mode: &GameMode, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(mode.cli_name())
+}
+
+fn players_by_name<S: Serializer>(players: &[PlayerReport], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(players.iter().map(|report| (report.player.cli_name(), report)))
+}
+
+fn player_name<S: Serializer>(player: &Option<Player>, serializer: S) -> Result<S::Ok, S::Error> {
+    player.map(|player| player.cli_name()).serialize(serializer)
+}
+
+// The arena given with --arena: a built-in one, or a level file path relative to the working directory.
+pub fn load_arena(options: &CliOptions) -> Result<Arena, ArenaError> {
+    match &options.arena {
+        Some(id) => arena::load_arena(Path::new(""), id),
+        None => Ok(Arena::open()),
+    }
+}
+
+// Play a whole match in `arena` without a window. Human players receive no input.
+pub fn run(options: &CliOptions, arena: Arena) -> HeadlessReport {
+    let (width, height) = options.headless_size();
+    let mut simulation = Simulation::new(width, height, options.left, options.right, options.winning_score, options.seed);
+    let (third_type, fourth_type) = options.extra_player_types();
+    simulation.set_mode(options.mode, third_type, fourth_type);
+    simulation.serve_rule = options.serve_rule.unwrap_or_default();
+    simulation.human_serve = options.human_serve;
+    options.apply_multi_ball(&mut simulation.multi_ball);
+    options.apply_power_ups(&mut simulation.power_up_rules);
+    simulation.set_arena(arena);
+    simulation.reset();
+    let max_frames = options.frames.unwrap_or(HEADLESS_MAX_FRAMES);
+    let pressed_keys = HashSet::new();
+
+    let mut frames = 0;
+    let mut winner = None;
+    while frames < max_frames && winner.is_none() {
+        simulation.step(HEADLESS_FRAME_TIME, &pressed_keys);
+        winner = simulation.winner();
+        frames += 1;
+    }
+
+    let report = |player: Player, player_type: PlayerType| PlayerReport {
+        player,
+        player_type: player_type.cli_name().to_string(),
+        score: simulation.score.get(player),
+    };
+    let others = options
+        .mode
+        .players()
+        .iter()
+        .skip(2)
+        .zip([third_type, fourth_type])
+        .map(|(&player, player_type)| report(player, player_type))
+        .collect();
+    HeadlessReport {
+        mode: options.mode,
+        left: report(Player::Left, options.left),
+        right: report(Player::Right, options.right),
+        others,
+        challenge: simulation.challenge_result(),
+        winner,
+        frames,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::challenge::WALL_PRACTICE_TARGET;
+
+    #[test]
+    fn frame_limit_stops_the_match() {
+        let options = CliOptions {
+            headless: true,
+            frames: Some(30),
+            seed: Some(1),
+            ..CliOptions::default()
+        };
+        let report = run(&options, Arena::open());
+        assert_eq!(report.frames, 30);
+        assert_eq!(report.winner, None);
+        assert_eq!(
+            serde_json::to_string(&report).unwrap(),
+            "{\"left\":{\"type\":\"human\",\"score\":0},\"right\":{\"type\":\"ai-hard\",\"score\":0},\"winner\":null,\"frames\":30}"
+        );
+    }
+
+    #[test]
+    fn idle_human_loses_to_ai() {
+        let options = CliOptions {
+            right: PlayerType::AIMedium,
+            winning_score: 2,
+            seed: Some(3),
+            headless: true,
+            ..CliOptions::default()
+        };
+        let report = run(&options, Arena::open());
+        assert_eq!(report.winner, Some(Player::Right));
+        assert_eq!(report.right.score, 2);
+    }
+
+    #[test]
+    fn arenas_load_by_id() {
+        let options = CliOptions {
+            arena: Some("bumpers".to_string()),
+            ..CliOptions::default()
+        };
+        assert_eq!(load_arena(&options).unwrap().name, "Bumpers");
+        assert_eq!(load_arena(&CliOptions::default()).unwrap(), Arena::open());
+        let missing = CliOptions {
+            arena: Some("no-such-arena.json".to_string()),
+            ..CliOptions::default()
+        };
+        assert!(matches!(load_arena(&missing), Err(ArenaError::Io(_))));
+    }
+
+    #[test]
+    fn four_player_match_reports_every_wall() {
+        let options = CliOptions {
+            left: PlayerType::AIHard,
+            mode: GameMode::FreeForAll,
+            winning_score: 3,
+            seed: Some(8),
+            headless: true,
+            ..CliOptions::default()
+        };
+        let report = run(&options, Arena::open());
+        let winner = report.winner.expect("the match should finish");
+        let scores = [report.left.score, report.right.score, report.others[0].score, report.others[1].score];
+        assert_eq!(report.others[1].player, Player::Bottom);
+        assert_eq!(scores[winner.index()], 3);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.starts_with("{\"mode\":\"ffa\",\"left\":"));
+        assert!(json.contains("\"bottom\":{\"type\":\"ai-medium\""));
+    }
+
+    #[test]
+    fn reports_parse_back_as_json() {
+        let options = CliOptions {
+            mode: GameMode::Teams,
+            winning_score: 2,
+            seed: Some(6),
+            headless: true,
+            ..CliOptions::default()
+        };
+        let report = run(&options, Arena::open());
+        let json: serde_json::Value = serde_json::from_str(&serde_json::to_string(&report).unwrap()).unwrap();
+        assert_eq!(json["mode"], "teams");
+        assert_eq!(json["left"]["type"], report.left.player_type);
+        assert_eq!(json["right"]["score"], report.right.score);
+        for other in &report.others {
+            assert_eq!(json[other.player.cli_name()]["score"], other.score);
+        }
+        assert_eq!(json["winner"].as_str(), report.winner.map(|player| player.cli_name()));
+        assert_eq!(json["frames"], report.frames);
+        assert!(json.get("challenge").is_none());
+    }
+
+    #[test]
+    fn doubles_report_the_front_players() {
+        let options = CliOptions {
+            mode: GameMode::Doubles,
+            left: PlayerType::AIMedium,
+            left_front: PlayerType::AIHard,
+            winning_score: 2,
+            seed: Some(5),
+            headless: true,
+            ..CliOptions::default()
+        };
+        let report = run(&options, Arena::open());
+        assert!(matches!(report.winner, Some(Player::Left | Player::Right)));
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.starts_with("{\"mode\":\"doubles\",\"left\":"));
+        assert!(json.contains("\"left-front\":{\"type\":\"ai-hard\""));
+        assert!(json.contains("\"right-front\":{\"type\":\"ai-medium\""));
+    }
+
+    #[test]
+    fn challenges_report_their_result() {
+        let options = CliOptions {
+            mode: GameMode::Survival,
+            seed: Some(4),
+            headless: true,
+            ..CliOptions::default()
+        };
+        let report = run(&options, Arena::open());
+        assert_eq!(report.winner, Some(Player::Right));
+        let result = report.challenge.expect("survival should end");
+        assert!(!result.won && result.score > 0);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.starts_with("{\"mode\":\"survival\",\"left\":"));
+        assert!(json.contains(&format!("\"challenge\":{{\"score\":{},\"won\":false}}", result.score)));
+
+        // Wall practice has no Players 2 to 4
+        let wall = CliOptions {
+            mode: GameMode::WallPractice,
+            left: PlayerType::AIHard,
+            ..options
+        };
+        let report = run(&wall, Arena::open());
+        assert!(report.others.is_empty());
+        assert_eq!(report.challenge.map(|result| (result.score, result.won)), Some((WALL_PRACTICE_TARGET, true)));
+    }
+}
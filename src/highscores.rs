@@ -0,0 +1,118 @@
+// High-score tables of the single-player challenges, one per challenge, kept between sessions.
+
+use crate::game::mode::GameMode;
+use crate::storage::{self, Migration, StorageError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const HIGH_SCORES_FILE: &str = "highscores.json";
+pub const TABLE_SIZE: usize = 10;
+
+// Schema history of the high-scores file (see `storage`); the first format is v0.
+const MIGRATIONS: &[Migration] = &[];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub name: String,
+    // As kept by `ChallengeRun::score`
+    pub score: u32,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HighScoresDocument {
+    // Keyed by the challenge's `cli_name`, best first
+    tables: BTreeMap<String, Vec<HighScore>>,
+}
+
+#[derive(Debug, Default)]
+pub struct HighScores {
+    // `None` when the tables could not be loaded: new scores then stay in memory
+    path: Option<PathBuf>,
+    tables: BTreeMap<String, Vec<HighScore>>,
+}
+
+impl HighScores {
+    pub fn load(path: &Path) -> Result<Self, StorageError> {
+        let document: HighScoresDocument = storage::load_document(path, MIGRATIONS)?.unwrap_or_default();
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            tables: document.tables,
+        })
+    }
+
+    // Tables that are never written to disk.
+    pub fn detached() -> Self {
+        Self::default()
+    }
+
+    pub fn save(&self) -> Result<(), StorageError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let document = HighScoresDocument { tables: self.tables.clone() };
+        storage::save_document(path, MIGRATIONS, &document)
+    }
+
+    // The best scores of `mode`, best first.
+    pub fn table(&self, mode: GameMode) -> &[HighScore] {
+        self.tables.get(mode.cli_name()).map_or(&[], Vec::as_slice)
+    }
+
+    // Enter a score into the table of `mode`. Returns its place from 0, or `None` if it did not make the table.
+    // Ties keep the older score ahead.
+    pub fn record(&mut self, mode: GameMode, name: &str, score: u32, timestamp: u64) -> Option<usize> {
+        if score == 0 {
+            return None;
+        }
+        let table = self.tables.entry(mode.cli_name().to_string()).or_default();
+        let place = table.iter().position(|entry| entry.score < score).unwrap_or(table.len());
+        if place >= TABLE_SIZE {
+            return None;
+        }
+        table.insert(
+            place,
+            HighScore {
+                name: name.to_string(),
+                score,
+                timestamp,
+            },
+        );
+        table.truncate(TABLE_SIZE);
+        Some(place)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_are_ranked_and_kept_per_challenge() {
+        let mut scores = HighScores::detached();
+        assert_eq!(scores.record(GameMode::Survival, "Ada", 0, 1), None);
+        for score in 1..=TABLE_SIZE as u32 {
+            assert_eq!(scores.record(GameMode::Survival, "Ada", score * 10, 1), Some(0));
+        }
+        assert_eq!(scores.record(GameMode::Survival, "Grace", 5, 2), None);
+        assert_eq!(scores.record(GameMode::Survival, "Grace", 50, 2), Some(6));
+        let table = scores.table(GameMode::Survival);
+        assert_eq!(table.len(), TABLE_SIZE);
+        assert_eq!((table[0].score, table[6].name.as_str(), table[TABLE_SIZE - 1].score), (100, "Grace", 20));
+        assert!(scores.table(GameMode::TimeAttack).is_empty());
+    }
+
+    #[test]
+    fn tables_survive_a_save_and_load() {
+        let directory = std::env::temp_dir().join(format!("rust-pong-highscores-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let path = directory.join(HIGH_SCORES_FILE);
+        let mut scores = HighScores::load(&path).unwrap();
+        scores.record(GameMode::WallPractice, "Ada", 42, 7);
+        scores.save().unwrap();
+
+        let reloaded = HighScores::load(&path).unwrap();
+        assert_eq!(reloaded.table(GameMode::WallPractice), scores.table(GameMode::WallPractice));
+    }
+}
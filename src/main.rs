@@ -1,25 +1,85 @@
 #![windows_subsystem = "windows"]
 
+mod achievements;
 mod audio;
+mod campaign;
+mod career;
+mod cli;
 mod debug;
+mod editor;
+mod effects;
 mod game;
+mod headless;
+mod highscores;
 mod main_state;
+mod packs;
 mod player;
+mod settings;
+mod storage;
+mod tournament;
 mod ui;
 
+use crate::cli::{CliError, CliOptions};
 use crate::main_state::MainState;
 use ggez::conf::{FullscreenType, WindowMode};
-use ggez::{ContextBuilder, GameResult, event};
+use ggez::{ContextBuilder, GameError, GameResult, event};
 
 const TITLE: &str = "Pong";
 
+// Windows builds have no console of their own. Started from one with options, the game writes to it, so that
+// --help, errors and headless reports are seen.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // Fails without harm when there is no console to attach to, e.g. when started from Explorer
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
 fn main() -> GameResult {
-    let window_mode = WindowMode::default().fullscreen_type(FullscreenType::Desktop);
+    #[cfg(windows)]
+    if std::env::args().len() > 1 {
+        attach_parent_console();
+    }
+    let options = match CliOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(CliError::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Err(CliError::Invalid(message)) => {
+            eprintln!("{}\n\n{}", message, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if options.headless {
+        let arena = headless::load_arena(&options).unwrap_or_else(|error| {
+            eprintln!("Could not load arena '{}': {}", options.arena.as_deref().unwrap_or_default(), error);
+            std::process::exit(2);
+        });
+        let report = serde_json::to_string(&headless::run(&options, arena)).map_err(|error| GameError::CustomError(error.to_string()))?;
+        println!("{}", report);
+        return Ok(());
+    }
+
+    let mut window_mode = WindowMode::default();
+    if !options.windowed {
+        window_mode = window_mode.fullscreen_type(FullscreenType::Desktop);
+    }
+    if let Some((width, height)) = options.size {
+        window_mode = window_mode.dimensions(width, height);
+    }
     let context_builder = ContextBuilder::new(TITLE, "Vianpyro").window_mode(window_mode).add_resource_path("./assets");
 
     let (mut context, event_loop) = context_builder.build()?;
     context.gfx.set_window_title(TITLE);
 
-    let state = MainState::new(&mut context)?;
+    let state = MainState::new(&mut context, &options)?;
     event::run(context, event_loop, state);
 }
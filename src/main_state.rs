@@ -1,24 +1,89 @@
 // Menu Navigation Controls:
-// - Left/Right Arrow or TAB: Switch between Player 1 and Player 2
+// - Left/Right Arrow or TAB: Switch between players
+// - G: Change game mode (classic, four-player free-for-all, 2 vs 2, doubles, Pong-Out, and the single-player
+//   survival, time attack and wall practice challenges)
 // - Up/Down Arrow: Cycle through player types for selected player
 // - Number keys (1-4): Directly select player type (1=Human, 2=Easy, 3=Medium, 4=Hard)
 // - Mouse Click: Select and cycle player type
+// - [ / ]: Switch the selected human player's career profile
+// - N: Create a new profile for the selected player
+// - L: Show the leaderboard
+// - A: Show the achievements
+// - H: Show the challenge high scores
+// - C: Campaign (Up/Down: Select an opponent, SPACE/ENTER: Play)
+// - T: Tournament (type names, Up/Down: Human or AI, Left/Right: Format, ENTER: Add, or start on an empty line;
+//   on the bracket, SPACE/ENTER: Play the next match, S: Skip an AI match to its result, X twice: Abandon)
+// - S: Settings (theme, CRT effects, serves, sound, asset pack, volumes)
+// - R: Change arena
+// - E: Arena editor
 // - SPACE/ENTER: Start game
+//
+// Arena Editor Controls:
+// - 1-4: Choose what a click places (block, bumper, portal, curve)
+// - Left Click: Place, or select and drag to move; drag the handle (or a portal's exit) to resize or move it
+// - Mouse Wheel: Resize the selection; Right Click or Delete: Remove an obstacle
+// - G: Snap to the grid; O: Make the selection slide sideways, up and down, or stand still
+// - Ctrl+Z / Ctrl+Y (or Ctrl+Shift+Z): Undo / Redo; Ctrl+S: Save; N: New arena
+// - T: Test play the arena (Esc returns to the editor)
+//
+// Audio Controls (any screen):
+// - M: Mute/unmute
+// - -/=: Master volume down/up (hold Shift for the music volume)
+// - F2: Switch between sampled and synthesized sound effects
 
-use crate::game::ball::*;
-use crate::game::physics::*;
+use crate::achievements::{ACHIEVEMENTS_FILE, AchievementTracker};
+use crate::audio::manager::AudioManager;
+use crate::audio::mixer::{Channel, VOLUME_STEP};
+use crate::audio::music::MusicScene;
+use crate::campaign::{CAMPAIGN_FILE, Campaign, LADDER};
+use crate::career::store::{MAX_NAME_LENGTH, PROFILES_FILE, ProfileStore};
+use crate::career::{self, Participant};
+use crate::cli::CliOptions;
+use crate::debug::DebugInfo;
+use crate::editor::{Editor, Tool};
+use crate::effects::Effects;
+use crate::game::arena::{self, ARENAS_DIR, Arena, DEFAULT_ARENA};
+use crate::game::ball::Ball;
+use crate::game::challenge::ChallengeResult;
+use crate::game::countdown::Countdown;
+use crate::game::events::{self, GameEvent};
+use crate::game::mode::GameMode;
+use crate::game::physics::Player;
 use crate::game::racket::*;
-use crate::game::score::Score;
-use crate::player::controller::ControllerInput;
+use crate::game::simulation::{DEFAULT_WINNING_SCORE, Simulation};
+use crate::game::stats::MatchStats;
+use crate::highscores::{HIGH_SCORES_FILE, HighScores};
+use crate::packs::{self, AssetPack, PACKS_DIR};
 use crate::player::player_type::PlayerType;
+use crate::settings::{SETTINGS_FILE, Settings, SettingsItem};
+use crate::storage;
+use crate::tournament::bracket::Slot;
+use crate::tournament::{self, AbandonPrompt, TOURNAMENT_FILE, Tournament, TournamentSetup};
+use crate::ui::arena as ui_arena;
+use crate::ui::challenge as ui_challenge;
+use crate::ui::crt::PostProcess;
+use crate::ui::editor as ui_editor;
+use crate::ui::effects as ui_effects;
 use crate::ui::menu as ui_menu;
-use crate::{audio::play_embedded_sound, debug::DebugInfo};
-use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Mesh, Rect};
-use ggez::{Context, GameResult, event, glam::Vec2, input::keyboard::KeyCode};
-use std::collections::HashSet;
+use crate::ui::powerups as ui_powerups;
+use crate::ui::settings::SettingsLine;
+use crate::ui::theme::{self, THEMES_DIR, Theme};
+use crate::ui::toast::Toasts;
+use crate::ui::tournament as ui_tournament;
+use crate::ui::transition::{Transition, TransitionKind};
+use ggez::event::MouseButton;
+use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, FontData, Mesh, Rect};
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::{Context, GameResult, event, glam::Vec2};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
 const MIDDLE_LINE_WIDTH: f32 = RACKET_WIDTH / 4.0;
-const WINNING_SCORE: u8 = 10;
+// Font every `Text` uses unless told otherwise; asset packs replace it
+const DEFAULT_FONT: &str = "LiberationMono-Regular";
+// Players of the attract mode match behind the menu
+const ATTRACT_LEFT: PlayerType = PlayerType::AIMedium;
+const ATTRACT_RIGHT: PlayerType = PlayerType::AIHard;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum GameState {
@@ -26,129 +91,996 @@ pub enum GameState {
     Playing,
     Paused,
     GameOver { winner: Player },
+    // The end of a single-player challenge, with the place its score took in the high scores
+    ChallengeOver { result: ChallengeResult, place: Option<usize> },
+    Leaderboard,
+    Achievements,
+    HighScores,
+    Campaign,
+    TournamentSetup,
+    Tournament,
+    Settings,
+    Editor,
 }
 
 pub struct MainState {
     state: GameState,
-    player_left: Racket,
-    player_right: Racket,
+    game_mode: GameMode,
     player_left_type: PlayerType,
     player_right_type: PlayerType,
-    selected_player: u8, // 1 for left, 2 for right (for keyboard navigation)
-    ball: Ball,
+    // Only play in four-player modes: the top and bottom players, or the front players in doubles
+    player_three_type: PlayerType,
+    player_four_type: PlayerType,
+    selected_player: u8, // Player number from 1, in the order of `GameMode::players` (for keyboard navigation)
+    player_left_profile: Option<String>,
+    player_right_profile: Option<String>,
+    profiles: ProfileStore,
+    name_entry: Option<String>, // Name being typed for a new profile
+    achievements: AchievementTracker,
+    high_scores: HighScores,
+    campaign: Campaign,
+    campaign_selected: usize,
+    // The rung being played, from the campaign screen until it is left
+    campaign_rung: Option<usize>,
+    // Points to win a match outside of the campaign and tournaments
+    winning_score: u8,
+    // The tournament being played, kept on disk at `tournament_path` until its champion is crowned
+    tournament: Option<Tournament>,
+    tournament_path: PathBuf,
+    tournament_setup: TournamentSetup,
+    // The bracket match being played, until its result has been seen
+    tournament_match: Option<usize>,
+    // Whether X was pressed once on the bracket, so that a second press abandons the tournament
+    tournament_abandon: AbandonPrompt,
+    toasts: Toasts,
+    settings: Settings,
+    settings_selected: usize,
+    packs_dir: PathBuf,
+    // Asset packs found in `packs_dir` when the settings screen was last opened
+    available_packs: Vec<String>,
+    // Level files for arenas beyond the built-in ones
+    arenas_dir: PathBuf,
+    // Open from the menu until left, including while its arena is test played
+    editor: Option<Editor>,
+    themes_dir: PathBuf,
+    // Themes found when the settings screen was last opened: the built-in ones, then the files in `themes_dir`
+    available_themes: Vec<String>,
+    // The selected theme, and the same theme with the asset pack colors applied on top
+    base_theme: Theme,
+    theme: Theme,
+    pack_colors: Vec<(String, Color)>,
+    post_process: PostProcess,
+    effects: Effects,
+    // The default font cannot be restored once a pack replaced it
+    pack_font_loaded: bool,
+    simulation: Simulation,
+    // Frozen play before a serve or after unpausing
+    countdown: Option<Countdown>,
+    // AI vs AI match played behind the menu
+    attract: Simulation,
+    transition: Option<Transition>,
+    stats: MatchStats,
+    audio: AudioManager,
+    ball_mesh: Mesh,
+    racket_mesh: Mesh,
     middle_line_mesh: Mesh,
-    score: Score,
+    particle_mesh: Mesh,
     debug: DebugInfo,
 }
 
 impl MainState {
-    pub fn new(context: &mut Context) -> GameResult<Self> {
-        let player_left_type = PlayerType::Human;
-        let player_right_type = PlayerType::AIHard;
-
-        let left_controller = player_left_type.create_controller_for_player(Player::Left);
-        let right_controller = player_right_type.create_controller_for_player(Player::Right);
+    pub fn new(context: &mut Context, options: &CliOptions) -> GameResult<Self> {
+        let player_left_type = options.left;
+        let player_right_type = options.right;
         let (screen_width, screen_height) = context.gfx.drawable_size();
-        let (screen_width_center, screen_height_center) = (screen_width / 2.0, screen_height / 2.0);
 
-        let middle_line_rectangle = Rect::new(
-            context.gfx.drawable_size().0 / 2.0 - MIDDLE_LINE_WIDTH / 2.0,
-            0.0,
-            MIDDLE_LINE_WIDTH,
-            context.gfx.drawable_size().1,
+        let mut simulation = Simulation::new(
+            screen_width,
+            screen_height,
+            player_left_type,
+            player_right_type,
+            options.winning_score,
+            options.seed,
         );
-        let middle_line_mesh = Mesh::new_rectangle(context, DrawMode::fill(), middle_line_rectangle, Color::from_rgb(127, 127, 127))?;
+        let (player_three_type, player_four_type) = options.extra_player_types();
+        simulation.set_mode(options.mode, player_three_type, player_four_type);
+
+        let profiles_path = context.fs.user_data_dir().join(PROFILES_FILE);
+        let profiles = storage::load_or(&profiles_path, "profiles", ProfileStore::load, ProfileStore::detached);
+
+        let achievements_path = context.fs.user_data_dir().join(ACHIEVEMENTS_FILE);
+        let achievements = storage::load_or(&achievements_path, "achievements", AchievementTracker::load, AchievementTracker::detached);
+
+        let high_scores_path = context.fs.user_data_dir().join(HIGH_SCORES_FILE);
+        let high_scores = storage::load_or(&high_scores_path, "high scores", HighScores::load, HighScores::detached);
+
+        let campaign_path = context.fs.user_data_dir().join(CAMPAIGN_FILE);
+        let campaign = storage::load_or(&campaign_path, "the campaign", Campaign::load, Campaign::detached);
+
+        let tournament_path = context.fs.user_data_dir().join(TOURNAMENT_FILE);
+        let tournament = storage::load_or(&tournament_path, "the tournament", Tournament::load, || None);
+
+        let settings_path = context.fs.user_data_dir().join(SETTINGS_FILE);
+        let mut settings = storage::load_or(&settings_path, "settings", Settings::load, Settings::detached);
+        settings.apply_cli(options);
+
+        let arenas_dir = context.fs.user_data_dir().join(ARENAS_DIR);
+        let arena = arena::load_arena(&arenas_dir, &settings.arena).unwrap_or_else(|error| {
+            eprintln!("Could not load arena '{}': {}. Using the open arena.", settings.arena, error);
+            settings.arena = DEFAULT_ARENA.to_string();
+            Arena::open()
+        });
+        simulation.set_arena(arena);
 
-        let score = Score::new(context)?;
+        let effects_intensity = settings.effects_intensity;
+        let themes_dir = context.fs.user_data_dir().join(THEMES_DIR);
+        let base_theme = theme::load_theme(&themes_dir, &settings.theme).unwrap_or_else(|error| {
+            eprintln!("Could not load theme '{}': {}. Using the classic theme.", settings.theme, error);
+            Theme::classic()
+        });
 
-        Ok(MainState {
+        let audio = AudioManager::new(settings.mixer, settings.sound_pack);
+        for problem in audio.asset_problems() {
+            eprintln!("{}", problem);
+        }
+
+        let mut state = MainState {
             state: GameState::Menu,
-            player_left: Racket::new(RACKET_OFFSET, screen_height_center, context, left_controller)?,
-            player_right: Racket::new(screen_width - RACKET_OFFSET, screen_height_center, context, right_controller)?,
+            game_mode: options.mode,
             player_left_type,
             player_right_type,
+            player_three_type,
+            player_four_type,
             selected_player: 1, // Start with player 1 selected
-            ball: Ball::new(screen_width_center, screen_height_center, context)?,
-            middle_line_mesh,
-            score,
+            player_left_profile: None,
+            player_right_profile: None,
+            profiles,
+            name_entry: None,
+            achievements,
+            high_scores,
+            campaign,
+            campaign_selected: 0,
+            campaign_rung: None,
+            winning_score: options.winning_score,
+            tournament,
+            tournament_path,
+            tournament_setup: TournamentSetup::default(),
+            tournament_match: None,
+            tournament_abandon: AbandonPrompt::default(),
+            toasts: Toasts::new(),
+            settings,
+            settings_selected: 0,
+            packs_dir: context.fs.user_data_dir().join(PACKS_DIR),
+            available_packs: Vec::new(),
+            arenas_dir,
+            editor: None,
+            themes_dir,
+            available_themes: Vec::new(),
+            theme: base_theme.clone(),
+            base_theme,
+            pack_colors: Vec::new(),
+            post_process: PostProcess::new(),
+            effects: Effects::new(effects_intensity),
+            pack_font_loaded: false,
+            simulation,
+            countdown: None,
+            attract: Simulation::new(screen_width, screen_height, ATTRACT_LEFT, ATTRACT_RIGHT, DEFAULT_WINNING_SCORE, None),
+            transition: None,
+            stats: MatchStats::new(),
+            audio,
+            ball_mesh: Ball::build_mesh(context, Color::WHITE)?,
+            racket_mesh: Racket::build_mesh(context, Color::WHITE)?,
+            middle_line_mesh: build_middle_line_mesh(context, Color::WHITE)?,
+            particle_mesh: ui_effects::build_particle_mesh(context)?,
             debug: DebugInfo::new(),
-        })
-    }
+        };
 
-    fn update_controllers(&mut self, context: &mut Context) -> GameResult {
-        let screen_height = context.gfx.drawable_size().1;
-        let screen_height_center = screen_height / 2.0;
+        if let Some(id) = state.settings.asset_pack.clone() {
+            match packs::load(&state.packs_dir, &id) {
+                Ok(pack) => state.apply_asset_pack(context, Some(pack))?,
+                Err(error) => {
+                    eprintln!("Could not load asset pack '{}': {}. Using the embedded assets.", id, error);
+                    state.settings.asset_pack = None;
+                }
+            }
+        }
+        state.refresh_theme(context)?;
+        state.prepare_post_process(context);
 
-        // Recreate rackets with new controllers
-        self.player_left = Racket::new(
-            RACKET_OFFSET,
-            screen_height_center,
-            context,
-            self.player_left_type.create_controller_for_player(Player::Left),
-        )?;
-
-        self.player_right = Racket::new(
-            context.gfx.drawable_size().0 - RACKET_OFFSET,
-            screen_height_center,
-            context,
-            self.player_right_type.create_controller_for_player(Player::Right),
-        )?;
+        // Match flags on the command line skip the menu
+        if options.skip_menu {
+            state.reset_game(context);
+            state.state = GameState::Playing;
+        }
 
+        Ok(state)
+    }
+
+    fn update_controllers(&mut self) -> GameResult {
+        // Recreate rackets with new controllers
+        self.simulation.set_player_types(self.player_left_type, self.player_right_type);
+        self.simulation.set_mode(self.game_mode, self.player_three_type, self.player_four_type);
         Ok(())
     }
 
-    fn reset_game(&mut self, context: &mut Context) -> GameResult {
+    // Players taking part in the selected game mode.
+    fn player_count(&self) -> u8 {
+        self.game_mode.players().len() as u8
+    }
+
+    fn player_type_mut(&mut self, number: u8) -> &mut PlayerType {
+        match number {
+            1 => &mut self.player_left_type,
+            2 => &mut self.player_right_type,
+            3 => &mut self.player_three_type,
+            _ => &mut self.player_four_type,
+        }
+    }
+
+    // The next or previous player type after `player_type`, skipping the personalities not yet beaten in the campaign.
+    fn unlocked_player_type(&self, mut player_type: PlayerType, forward: bool) -> PlayerType {
+        loop {
+            player_type = if forward { player_type.next() } else { player_type.previous() };
+            if self.campaign.is_unlocked(player_type) {
+                return player_type;
+            }
+        }
+    }
+
+    fn cycle_player_type(&mut self, number: u8, forward: bool) {
+        let current = *self.player_type_mut(number);
+        *self.player_type_mut(number) = self.unlocked_player_type(current, forward);
+    }
+
+    fn reset_game(&mut self, context: &mut Context) {
+        let (screen_width, screen_height) = context.gfx.drawable_size();
+        self.audio.set_arena_width(screen_width);
+        self.audio.start_match();
+        self.simulation.resize(screen_width, screen_height);
+        self.settings.apply_rules(&mut self.simulation);
+        if let Some(index) = self.campaign_rung {
+            LADDER[index].prepare(&mut self.simulation);
+        }
+        if let (Some(index), Some(tournament)) = (self.tournament_match, &self.tournament) {
+            tournament.prepare(index, &mut self.simulation);
+        }
+        self.simulation.reset();
+        self.stats = MatchStats::new();
+        self.effects.set_arena(screen_width, screen_height);
+        self.effects.clear();
+        // Achievements and career records only count classic matches, outside of the editor's test plays, the
+        // campaign and tournaments
+        if !self.is_ranked() {
+            self.achievements.start_unranked_match();
+        } else {
+            self.achievements.start_match(self.player_left_type, self.player_right_type);
+        }
+    }
+
+    fn is_ranked(&self) -> bool {
+        self.game_mode.is_ranked() && self.editor.is_none() && self.campaign_rung.is_none() && self.tournament_match.is_none()
+    }
+
+    // Switch screens with the transition that suits the change.
+    fn change_state(&mut self, state: GameState) {
+        if self.state == GameState::Paused && state == GameState::Playing {
+            self.countdown = Some(Countdown::new());
+        }
+        self.transition = transition_for(&self.state, &state).map(Transition::new);
+        self.state = state;
+    }
+
+    // Keep the attract mode match going, starting a new one when it ends.
+    fn update_attract(&mut self, context: &mut Context, delta_time: f32) {
         let (screen_width, screen_height) = context.gfx.drawable_size();
-        let (screen_width_center, screen_height_center) = (screen_width / 2.0, screen_height / 2.0);
+        self.attract.resize(screen_width, screen_height);
+        self.attract.step(delta_time, &HashSet::new());
+        if self.attract.winner().is_some() {
+            self.attract.reset();
+        }
+    }
+
+    fn participants(&self) -> (Participant, Participant) {
+        (
+            Participant {
+                player_type: self.player_left_type,
+                profile: self.player_left_profile.clone(),
+            },
+            Participant {
+                player_type: self.player_right_type,
+                profile: self.player_right_profile.clone(),
+            },
+        )
+    }
+
+    // Only the classic players have career profiles.
+    fn selected_profile_mut(&mut self) -> Option<&mut Option<String>> {
+        match self.selected_player {
+            1 => Some(&mut self.player_left_profile),
+            2 => Some(&mut self.player_right_profile),
+            _ => None,
+        }
+    }
+
+    // Step the selected player's profile through Guest and every saved profile.
+    fn cycle_profile(&mut self, forward: bool) {
+        let mut choices: Vec<Option<String>> = vec![None];
+        choices.extend(self.profiles.names().into_iter().map(Some));
+
+        let Some(selected) = self.selected_profile_mut() else {
+            return;
+        };
+        let current = choices.iter().position(|choice| choice == selected).unwrap_or(0);
+        let next = if forward {
+            (current + 1) % choices.len()
+        } else {
+            (current + choices.len() - 1) % choices.len()
+        };
+        *selected = choices[next].clone();
+    }
+
+    fn update_name_entry(&mut self, context: &mut Context) -> GameResult {
+        if context.keyboard.is_key_just_pressed(KeyCode::Back)
+            && let Some(name) = &mut self.name_entry
+        {
+            name.pop();
+        }
+
+        if context.keyboard.is_key_just_pressed(KeyCode::Return) {
+            let name = self.name_entry.take().unwrap_or_default();
+            if name.trim().is_empty() {
+                return Ok(());
+            }
+            match self.profiles.create(&name, career::now()) {
+                Ok(name) => {
+                    if let Some(profile) = self.selected_profile_mut() {
+                        *profile = Some(name);
+                        *self.player_type_mut(self.selected_player) = PlayerType::Human;
+                        self.update_controllers()?;
+                    }
+                    self.save_profiles();
+                }
+                // Keep the prompt open so the name can be fixed
+                Err(_) => self.name_entry = Some(name),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_audio_keys(&mut self, context: &mut Context) {
+        let keyboard = &context.keyboard;
+        if keyboard.is_key_just_pressed(KeyCode::F2) {
+            let pack = self.audio.sound_pack().next();
+            self.audio.set_sound_pack(pack);
+            self.toasts.replace("Audio", &format!("Sound pack: {}", pack.name()));
+            self.save_settings();
+            return;
+        }
+        if keyboard.is_key_just_pressed(KeyCode::M) {
+            let message = if self.audio.toggle_mute() { "Muted" } else { "Unmuted" };
+            self.toasts.replace("Audio", message);
+            self.save_settings();
+            return;
+        }
+
+        let delta = if keyboard.is_key_just_pressed(KeyCode::Minus) || keyboard.is_key_just_pressed(KeyCode::NumpadSubtract) {
+            -VOLUME_STEP
+        } else if keyboard.is_key_just_pressed(KeyCode::Equals) || keyboard.is_key_just_pressed(KeyCode::NumpadAdd) {
+            VOLUME_STEP
+        } else {
+            return;
+        };
+        let (channel, label) = if keyboard.is_key_pressed(KeyCode::LShift) || keyboard.is_key_pressed(KeyCode::RShift) {
+            (Channel::Music, "Music volume")
+        } else {
+            (Channel::Master, "Volume")
+        };
+        let level = self.audio.adjust_volume(channel, delta);
+        let muted = if self.audio.mixer().is_muted() { " (muted)" } else { "" };
+        self.toasts.replace("Audio", &format!("{} {}%{}", label, (level * 100.0).round(), muted));
+        self.save_settings();
+    }
+
+    fn save_settings(&mut self) {
+        self.settings.mixer = *self.audio.mixer();
+        self.settings.sound_pack = self.audio.sound_pack();
+        if let Err(error) = self.settings.save() {
+            eprintln!("Could not save settings: {}", error);
+        }
+    }
+
+    // Use `pack`'s sounds, font and colors, or the embedded ones for `None`.
+    fn apply_asset_pack(&mut self, context: &mut Context, pack: Option<AssetPack>) -> GameResult {
+        let (sounds, font, colors) = match pack {
+            Some(pack) => (pack.sounds, pack.font, pack.colors),
+            None => (HashMap::new(), None, Vec::new()),
+        };
+
+        self.audio.set_sound_overrides(sounds);
+        match font {
+            Some(bytes) => {
+                context.gfx.add_font(DEFAULT_FONT, FontData::from_vec(bytes)?);
+                self.pack_font_loaded = true;
+            }
+            None if self.pack_font_loaded => {
+                self.toasts.push("Asset pack", "Restart the game to restore the default font");
+            }
+            None => {}
+        }
+        self.pack_colors = colors;
+        self.refresh_theme(context)
+    }
+
+    // Apply the asset pack colors to the selected theme and rebuild the meshes drawn with it.
+    fn refresh_theme(&mut self, context: &mut Context) -> GameResult {
+        self.theme = self.base_theme.clone();
+        self.theme.apply(&self.pack_colors);
+        self.ball_mesh = Ball::build_mesh(context, self.theme.ball)?;
+        self.racket_mesh = Racket::build_mesh(context, self.theme.racket)?;
+        self.middle_line_mesh = build_middle_line_mesh(context, self.theme.net)?;
+        Ok(())
+    }
+
+    fn settings_lines(&self) -> Vec<SettingsLine<'static>> {
+        let percent = |channel| format!("{}%", (self.audio.mixer().level(channel) * 100.0).round());
+        SettingsItem::ALL
+            .iter()
+            .map(|item| SettingsLine {
+                label: item.label(),
+                value: match item {
+                    SettingsItem::Theme => self.base_theme.name.clone(),
+                    SettingsItem::Scanlines => on_off(self.settings.crt.scanlines),
+                    SettingsItem::Bloom => on_off(self.settings.crt.bloom),
+                    SettingsItem::Curvature => on_off(self.settings.crt.curvature),
+                    SettingsItem::Aberration => on_off(self.settings.crt.aberration),
+                    SettingsItem::BallTrail => on_off(self.settings.crt.trail),
+                    SettingsItem::EffectsIntensity => format!("{}%", (self.effects.intensity() * 100.0).round()),
+                    SettingsItem::ServeRule => self.settings.serve_rule.name().to_string(),
+                    SettingsItem::HumanServe => on_off(self.settings.human_serve),
+                    SettingsItem::Balls => self.settings.multi_ball.ball_count().to_string(),
+                    SettingsItem::BallCollisions => on_off(self.settings.multi_ball.collisions),
+                    SettingsItem::BallSpawns => on_off(self.settings.multi_ball.spawns),
+                    SettingsItem::PowerUps => on_off(self.settings.power_ups.enabled),
+                    SettingsItem::PowerUpRate => self.settings.power_ups.rate.name().to_string(),
+                    SettingsItem::SoundPack => self.audio.sound_pack().name().to_string(),
+                    SettingsItem::AssetPack => self.settings.asset_pack.clone().unwrap_or_else(|| "None".to_string()),
+                    SettingsItem::MasterVolume => percent(Channel::Master),
+                    SettingsItem::SfxVolume => percent(Channel::Sfx),
+                    SettingsItem::MusicVolume => percent(Channel::Music),
+                    SettingsItem::Mute => on_off(self.audio.mixer().is_muted()),
+                },
+            })
+            .collect()
+    }
+
+    fn change_setting(&mut self, context: &mut Context, forward: bool) -> GameResult {
+        let step = if forward { VOLUME_STEP } else { -VOLUME_STEP };
+        match SettingsItem::ALL[self.settings_selected] {
+            SettingsItem::SoundPack => self.audio.set_sound_pack(self.audio.sound_pack().next()),
+            SettingsItem::Theme => return self.cycle_theme(context, forward),
+            SettingsItem::Scanlines => self.settings.crt.scanlines = !self.settings.crt.scanlines,
+            SettingsItem::Bloom => self.settings.crt.bloom = !self.settings.crt.bloom,
+            SettingsItem::Curvature => self.settings.crt.curvature = !self.settings.crt.curvature,
+            SettingsItem::Aberration => self.settings.crt.aberration = !self.settings.crt.aberration,
+            SettingsItem::BallTrail => self.settings.crt.trail = !self.settings.crt.trail,
+            SettingsItem::EffectsIntensity => {
+                let intensity = ((self.effects.intensity() + step) / VOLUME_STEP).round() * VOLUME_STEP;
+                self.effects.set_intensity(intensity);
+                self.settings.effects_intensity = self.effects.intensity();
+            }
+            SettingsItem::ServeRule => self.settings.serve_rule = self.settings.serve_rule.next(),
+            SettingsItem::HumanServe => self.settings.human_serve = !self.settings.human_serve,
+            SettingsItem::Balls => self.settings.multi_ball.balls = self.settings.multi_ball.next_ball_count(),
+            SettingsItem::BallCollisions => self.settings.multi_ball.collisions = !self.settings.multi_ball.collisions,
+            SettingsItem::BallSpawns => self.settings.multi_ball.spawns = !self.settings.multi_ball.spawns,
+            SettingsItem::PowerUps => self.settings.power_ups.enabled = !self.settings.power_ups.enabled,
+            SettingsItem::PowerUpRate => self.settings.power_ups.rate = self.settings.power_ups.rate.next(),
+            SettingsItem::AssetPack => return self.cycle_asset_pack(context, forward),
+            SettingsItem::MasterVolume => {
+                self.audio.adjust_volume(Channel::Master, step);
+            }
+            SettingsItem::SfxVolume => {
+                self.audio.adjust_volume(Channel::Sfx, step);
+            }
+            SettingsItem::MusicVolume => {
+                self.audio.adjust_volume(Channel::Music, step);
+            }
+            SettingsItem::Mute => {
+                self.audio.toggle_mute();
+            }
+        }
+        self.prepare_post_process(context);
+        self.save_settings();
+        Ok(())
+    }
+
+    // Build the CRT shader when an effect that needs it is on. If it cannot be built, frames are drawn without it.
+    fn prepare_post_process(&mut self, context: &mut Context) {
+        if let Some(error) = self.post_process.prepare(context, self.settings.crt) {
+            eprintln!("Could not build the CRT shader: {}. Drawing without it.", error);
+            self.toasts.push("CRT effects unavailable", "The shader could not be built");
+        }
+    }
+
+    // Switch to the next arena, built-in or found on disk. Level files that fail to load are reported and skipped.
+    fn cycle_arena(&mut self) {
+        let available = arena::available_arenas(&self.arenas_dir);
+        let current = available.iter().position(|id| *id == self.settings.arena).unwrap_or(0);
+        for offset in 1..available.len() {
+            let id = &available[(current + offset) % available.len()];
+            match arena::load_arena(&self.arenas_dir, id) {
+                Ok(arena) => {
+                    self.simulation.set_arena(arena);
+                    self.settings.arena = id.clone();
+                    self.save_settings();
+                    return;
+                }
+                Err(error) => {
+                    eprintln!("Could not load arena '{}': {}", id, error);
+                    self.toasts.push(&format!("Arena '{}' is invalid", id), &error.to_string());
+                }
+            }
+        }
+    }
+
+    fn update_editor(&mut self, context: &mut Context) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+        let (width, height) = context.gfx.drawable_size();
+        let mouse = context.mouse.position();
+        let mouse = Vec2::new(mouse.x, mouse.y);
+        if context.mouse.button_just_pressed(MouseButton::Left) {
+            editor.press(mouse, width, height);
+        } else if context.mouse.button_pressed(MouseButton::Left) {
+            editor.drag(mouse, width, height);
+        } else if context.mouse.button_just_released(MouseButton::Left) {
+            editor.release();
+        }
+        if context.mouse.button_just_pressed(MouseButton::Right) {
+            editor.delete_at(mouse, width, height);
+        }
+
+        let keyboard = &context.keyboard;
+        let control = keyboard.is_mod_active(KeyMods::CTRL);
+        let tool_keys = [
+            (KeyCode::Key1, KeyCode::Numpad1),
+            (KeyCode::Key2, KeyCode::Numpad2),
+            (KeyCode::Key3, KeyCode::Numpad3),
+            (KeyCode::Key4, KeyCode::Numpad4),
+        ];
+        for (tool, (key, numpad)) in Tool::ALL.into_iter().zip(tool_keys) {
+            if keyboard.is_key_just_pressed(key) || keyboard.is_key_just_pressed(numpad) {
+                editor.tool = tool;
+            }
+        }
+        if control && keyboard.is_key_just_pressed(KeyCode::Z) {
+            if keyboard.is_mod_active(KeyMods::SHIFT) {
+                editor.redo();
+            } else {
+                editor.undo();
+            }
+        } else if control && keyboard.is_key_just_pressed(KeyCode::Y) {
+            editor.redo();
+        } else if control && keyboard.is_key_just_pressed(KeyCode::S) {
+            self.save_editor_arena();
+        } else if keyboard.is_key_just_pressed(KeyCode::G) {
+            editor.snap = !editor.snap;
+        } else if keyboard.is_key_just_pressed(KeyCode::O) {
+            editor.cycle_motion();
+        } else if keyboard.is_key_just_pressed(KeyCode::Delete) || keyboard.is_key_just_pressed(KeyCode::Back) {
+            editor.delete_selected();
+        } else if keyboard.is_key_just_pressed(KeyCode::N) {
+            // The first free "custom-N.json" name
+            let available = arena::available_arenas(&self.arenas_dir);
+            let file = (1..)
+                .map(|number| format!("custom-{}.json", number))
+                .find(|file| !available.contains(file))
+                .unwrap_or_default();
+            editor.clear(file);
+        } else if keyboard.is_key_just_pressed(KeyCode::T) {
+            self.test_editor_arena(context);
+        }
+    }
+
+    // Save the edited arena as a level file and make it the selected arena.
+    fn save_editor_arena(&mut self) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+        match arena::save_arena(&self.arenas_dir, &editor.file, &editor.arena) {
+            Ok(()) => {
+                editor.mark_saved();
+                self.toasts.push("Arena saved", &editor.file);
+                self.settings.arena = editor.file.clone();
+                self.save_settings();
+            }
+            Err(error) => self.toasts.push("Could not save the arena", &error.to_string()),
+        }
+    }
+
+    // Play a match in the edited arena right away, unless it cannot be played in.
+    fn test_editor_arena(&mut self, context: &mut Context) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+        if let Some(problem) = editor.arena.problem() {
+            self.toasts.push("Cannot test this arena", &problem);
+            return;
+        }
+        self.simulation.set_arena(editor.arena.clone());
+        self.reset_game(context);
+        self.change_state(GameState::Playing);
+    }
+
+    // Back to the menu with the selected arena, asking first when the editor has unsaved changes.
+    fn leave_editor(&mut self) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+        if !editor.confirm_leave() {
+            self.toasts
+                .push("Unsaved changes", "Press Esc again to leave without saving, or Ctrl+S to save");
+            return;
+        }
+        self.editor = None;
+        self.restore_selected_arena();
+        self.change_state(GameState::Menu);
+    }
+
+    // Put the arena picked in the menu back into play.
+    fn restore_selected_arena(&mut self) {
+        let arena = arena::load_arena(&self.arenas_dir, &self.settings.arena).unwrap_or_else(|error| {
+            eprintln!("Could not load arena '{}': {}. Using the open arena.", self.settings.arena, error);
+            Arena::open()
+        });
+        self.simulation.set_arena(arena);
+    }
+
+    fn update_campaign(&mut self, context: &mut Context) -> GameResult {
+        let keyboard = &context.keyboard;
+        if keyboard.is_key_just_pressed(KeyCode::Up) {
+            self.campaign_selected = self.campaign_selected.saturating_sub(1);
+        } else if keyboard.is_key_just_pressed(KeyCode::Down) && self.campaign.is_open(self.campaign_selected + 1) {
+            self.campaign_selected += 1;
+        } else if keyboard.is_key_just_pressed(KeyCode::Space) || keyboard.is_key_just_pressed(KeyCode::Return) {
+            self.campaign_rung = Some(self.campaign_selected);
+            self.reset_game(context);
+            self.change_state(GameState::Playing);
+        } else if keyboard.is_key_just_pressed(KeyCode::C) {
+            self.leave_campaign()?;
+        }
+        Ok(())
+    }
+
+    fn leave_campaign(&mut self) -> GameResult {
+        self.campaign_rung = None;
+        self.return_to_menu()
+    }
+
+    // Back to the menu with the players, arena and rules picked there.
+    fn return_to_menu(&mut self) -> GameResult {
+        self.simulation.winning_score = self.winning_score;
+        self.restore_selected_arena();
+        self.update_controllers()?;
+        self.change_state(GameState::Menu);
+        Ok(())
+    }
+
+    fn update_tournament_setup(&mut self, context: &mut Context) {
+        let keyboard = &context.keyboard;
+        if keyboard.is_key_just_pressed(KeyCode::Back) {
+            self.tournament_setup.name.pop();
+        } else if keyboard.is_key_just_pressed(KeyCode::Delete) {
+            self.tournament_setup.entrants.pop();
+        } else if keyboard.is_key_just_pressed(KeyCode::Up) || keyboard.is_key_just_pressed(KeyCode::Down) {
+            let forward = keyboard.is_key_just_pressed(KeyCode::Up);
+            self.tournament_setup.player_type = self.unlocked_player_type(self.tournament_setup.player_type, forward);
+        } else if keyboard.is_key_just_pressed(KeyCode::Left) || keyboard.is_key_just_pressed(KeyCode::Right) {
+            self.tournament_setup.format = self.tournament_setup.format.next();
+        } else if keyboard.is_key_just_pressed(KeyCode::Return) {
+            // An empty line for a person starts the tournament; anything else enters someone
+            let setup = &mut self.tournament_setup;
+            let result = if setup.name.trim().is_empty() && setup.player_type == PlayerType::Human {
+                setup.start(self.winning_score).map(|tournament| {
+                    self.tournament = Some(tournament);
+                    self.save_tournament();
+                    self.tournament_abandon.dismiss();
+                    self.change_state(GameState::Tournament);
+                })
+            } else {
+                setup.add()
+            };
+            if let Err(problem) = result {
+                self.toasts.replace("Tournament", &problem);
+            }
+        }
+    }
+
+    fn update_tournament(&mut self, context: &mut Context) -> GameResult {
+        let Some(tournament) = &self.tournament else {
+            self.change_state(GameState::TournamentSetup);
+            return Ok(());
+        };
+        let keyboard = &context.keyboard;
+        // Only a tournament still going on can be abandoned; a finished one is replaced by the next
+        let abandon = keyboard.is_key_just_pressed(KeyCode::X) && tournament.champion().is_none();
+        if keyboard.is_key_just_pressed(KeyCode::T) {
+            self.tournament_abandon.dismiss();
+            self.return_to_menu()?;
+        } else if keyboard.is_key_just_pressed(KeyCode::Space) || keyboard.is_key_just_pressed(KeyCode::Return) {
+            self.tournament_abandon.dismiss();
+            if let Some((index, _)) = tournament.next_match() {
+                self.tournament_match = Some(index);
+                self.reset_game(context);
+                self.change_state(GameState::Playing);
+            } else {
+                // The same party, ready for another go
+                self.tournament_setup = TournamentSetup::rematch(tournament);
+                self.tournament = None;
+                self.change_state(GameState::TournamentSetup);
+            }
+        } else if keyboard.is_key_just_pressed(KeyCode::S)
+            && let Some((index, entrants)) = tournament.next_match()
+            && entrants.iter().all(|entrant| entrant.player_type != PlayerType::Human)
+        {
+            self.tournament_abandon.dismiss();
+            let (screen_width, screen_height) = context.gfx.drawable_size();
+            let mut simulation = Simulation::new(
+                screen_width,
+                screen_height,
+                PlayerType::AIMedium,
+                PlayerType::AIMedium,
+                DEFAULT_WINNING_SCORE,
+                None,
+            );
+            simulation.set_arena(self.simulation.arena().clone());
+            // Skipped matches keep the rules the watched ones are played by
+            self.settings.apply_rules(&mut simulation);
+            tournament.prepare(index, &mut simulation);
+            let winner = tournament::play_out(&mut simulation);
+            let score = format!("{} - {}", simulation.score.get(Player::Left), simulation.score.get(Player::Right));
+            let names = entrants.map(|entrant| entrant.name.clone());
+            self.toasts
+                .push(&format!("{} vs {}", names[0], names[1]), &format!("{}, {} wins", score, names[winner.index()]));
+            self.record_tournament_result(index, winner);
+        } else if abandon && self.tournament_abandon.confirm() {
+            // The entrants stay on the setup screen, to start over
+            self.tournament_setup = TournamentSetup::rematch(tournament);
+            self.tournament = None;
+            self.tournament_abandon.dismiss();
+            if let Err(error) = Tournament::remove(&self.tournament_path) {
+                eprintln!("Could not remove the saved tournament: {}", error);
+            }
+            self.change_state(GameState::TournamentSetup);
+        } else if abandon {
+            self.toasts.push("Abandon the tournament?", "Press X again to throw the bracket away");
+        }
+        Ok(())
+    }
+
+    // `winner` won the tournament match at `index`: move the bracket on, and crown the champion after the final.
+    fn record_tournament_result(&mut self, index: usize, winner: Player) {
+        let Some(tournament) = &mut self.tournament else {
+            return;
+        };
+        tournament.record(index, winner);
+        if let Some(champion) = tournament.champion() {
+            self.toasts.push("Tournament champion", &champion.name);
+        }
+        self.save_tournament();
+    }
+
+    // Keep the tournament on disk while it goes on; once it has a champion there is nothing left to resume.
+    fn save_tournament(&self) {
+        let Some(tournament) = &self.tournament else {
+            return;
+        };
+        let result = if tournament.champion().is_some() {
+            Tournament::remove(&self.tournament_path)
+        } else {
+            tournament.save(&self.tournament_path)
+        };
+        if let Err(error) = result {
+            eprintln!("Could not save the tournament: {}", error);
+        }
+    }
+
+    // Player 1 won the campaign match being played: open the next rung and announce what that unlocked.
+    fn record_campaign_win(&mut self, index: usize) {
+        let Some(rung) = self.campaign.record_win(index) else {
+            return;
+        };
+        if rung.opponent.is_personality() {
+            self.toasts.push("Unlocked for the menu", rung.opponent.display_name());
+        }
+        if self.campaign.is_complete() {
+            self.toasts.push("Campaign complete!", &format!("{} is beaten", rung.name));
+        } else {
+            self.campaign_selected = self.campaign.cleared();
+            self.toasts
+                .push(&format!("{} beaten", rung.name), &format!("Next up: {}", LADDER[self.campaign.cleared()].name));
+        }
+        if let Err(error) = self.campaign.save() {
+            eprintln!("Could not save the campaign: {}", error);
+        }
+    }
 
-        self.ball.reset(screen_width_center, screen_height_center);
-        self.player_left.position_y = screen_height_center;
-        self.player_right.position_y = screen_height_center;
-        self.score = Score::new(context)?;
+    // Switch to the previous or next theme, built-in or found on disk.
+    fn cycle_theme(&mut self, context: &mut Context, forward: bool) -> GameResult {
+        let count = self.available_themes.len();
+        if count == 0 {
+            return Ok(());
+        }
+        let current = self.available_themes.iter().position(|id| *id == self.settings.theme).unwrap_or(0);
+        let next = if forward { (current + 1) % count } else { (current + count - 1) % count };
 
+        let id = self.available_themes[next].clone();
+        match theme::load_theme(&self.themes_dir, &id) {
+            Ok(theme) => self.base_theme = theme,
+            Err(error) => {
+                eprintln!("Could not load theme '{}': {}", id, error);
+                self.toasts.push(&format!("Theme '{}' is invalid", id), &error.to_string());
+                return Ok(());
+            }
+        }
+        self.settings.theme = id;
+        self.refresh_theme(context)?;
+        self.save_settings();
         Ok(())
     }
+
+    // Switch to the previous or next pack found on disk, "None" being the first choice.
+    fn cycle_asset_pack(&mut self, context: &mut Context, forward: bool) -> GameResult {
+        let mut choices: Vec<Option<String>> = vec![None];
+        choices.extend(self.available_packs.iter().cloned().map(Some));
+        let current = choices.iter().position(|choice| *choice == self.settings.asset_pack).unwrap_or(0);
+        let next = if forward {
+            (current + 1) % choices.len()
+        } else {
+            (current + choices.len() - 1) % choices.len()
+        };
+
+        let choice = choices[next].clone();
+        let pack = match &choice {
+            Some(id) => match packs::load(&self.packs_dir, id) {
+                Ok(pack) => Some(pack),
+                Err(error) => {
+                    eprintln!("Could not load asset pack '{}': {}", id, error);
+                    self.toasts.push(&format!("Asset pack '{}' is invalid", id), &error.to_string());
+                    return Ok(());
+                }
+            },
+            None => None,
+        };
+        if let Some(pack) = &pack {
+            self.toasts.replace("Asset pack", &format!("Using {}", pack.name));
+        }
+        self.apply_asset_pack(context, pack)?;
+        self.settings.asset_pack = choice;
+        self.save_settings();
+        Ok(())
+    }
+
+    fn save_profiles(&self) {
+        if let Err(error) = self.profiles.save() {
+            eprintln!("Could not save profiles: {}", error);
+        }
+    }
+
+    fn announce_achievements(&mut self, events: &[GameEvent]) {
+        let unlocked = self.achievements.take_unlocked();
+        for achievement in &unlocked {
+            self.toasts.push("Achievement unlocked!", achievement.name);
+        }
+
+        // Progress towards multi-match achievements moves at the end of each match
+        let match_won = events.iter().any(|event| matches!(event, GameEvent::MatchWon { .. }));
+        if (!unlocked.is_empty() || match_won)
+            && let Err(error) = self.achievements.save()
+        {
+            eprintln!("Could not save achievements: {}", error);
+        }
+    }
+
+    // Enter a challenge run into its high-score table, if a human played it outside of the editor's test plays.
+    // Returns the place it took.
+    fn record_high_score(&mut self, result: &ChallengeResult) -> Option<usize> {
+        if self.player_left_type != PlayerType::Human || self.editor.is_some() {
+            return None;
+        }
+        let name = self.player_left_profile.clone().unwrap_or_else(|| Player::Left.name().to_string());
+        let place = self.high_scores.record(result.mode, &name, result.score, career::now())?;
+        if let Err(error) = self.high_scores.save() {
+            eprintln!("Could not save high scores: {}", error);
+        }
+        Some(place)
+    }
+
+    fn record_career_match(&mut self) {
+        if !self.is_ranked() {
+            return;
+        }
+        let (left, right) = self.participants();
+        if career::record_match(&mut self.profiles, &left, &right, &self.simulation.score, &self.stats, career::now()) {
+            self.save_profiles();
+        }
+    }
 }
 
 impl event::EventHandler for MainState {
     fn update(&mut self, context: &mut Context) -> GameResult {
         let delta_time = context.time.delta().as_secs_f32();
         self.debug.update(context)?;
+        self.toasts.update(delta_time);
 
         // F1 toggles debug in all states
         if context.keyboard.is_key_just_pressed(KeyCode::F1) {
             self.debug.toggle();
         }
+        // Audio keys, except while they are typed into a profile or entrant name
+        if self.name_entry.is_none() && self.state != GameState::TournamentSetup {
+            self.update_audio_keys(context);
+        }
+        self.audio.set_music_scene(match self.state {
+            GameState::Menu
+            | GameState::Leaderboard
+            | GameState::Achievements
+            | GameState::HighScores
+            | GameState::Campaign
+            | GameState::TournamentSetup
+            | GameState::Tournament
+            | GameState::Settings
+            | GameState::Editor => MusicScene::Menu,
+            GameState::Playing => MusicScene::Match,
+            GameState::Paused => MusicScene::Paused,
+            GameState::GameOver { .. } | GameState::ChallengeOver { .. } => MusicScene::GameOver,
+        });
+        self.audio.update_music(context, delta_time);
+
+        if let Some(transition) = &mut self.transition {
+            transition.update(delta_time);
+            if transition.is_done() {
+                self.transition = None;
+            }
+        }
+        if self.state == GameState::Menu {
+            self.update_attract(context, delta_time);
+        }
 
         match self.state {
             GameState::Menu => {
+                // While typing a profile name, keys go to the name entry only
+                if self.name_entry.is_some() {
+                    return self.update_name_entry(context);
+                }
+
+                // G cycles through the game modes
+                if context.keyboard.is_key_just_pressed(KeyCode::G) {
+                    self.game_mode = self.game_mode.next();
+                    self.selected_player = self.selected_player.min(self.player_count());
+                    self.update_controllers()?;
+                }
+
                 // Keyboard navigation: Left/Right arrows to switch between players
+                let player_count = self.player_count();
                 if context.keyboard.is_key_just_pressed(KeyCode::Left) {
-                    self.selected_player = 1;
+                    self.selected_player = (self.selected_player - 1).max(1);
                 } else if context.keyboard.is_key_just_pressed(KeyCode::Right) {
-                    self.selected_player = 2;
+                    self.selected_player = (self.selected_player + 1).min(player_count);
                 } else if context.keyboard.is_key_just_pressed(KeyCode::Tab) {
-                    // Tab to toggle between players
-                    self.selected_player = if self.selected_player == 1 { 2 } else { 1 };
+                    // Tab to cycle through players
+                    self.selected_player = self.selected_player % player_count + 1;
                 }
 
                 // Up/Down arrows or number keys to change player type
                 if context.keyboard.is_key_just_pressed(KeyCode::Up) {
-                    if self.selected_player == 1 {
-                        self.player_left_type = self.player_left_type.next();
-                        self.update_controllers(context)?;
-                    } else {
-                        self.player_right_type = self.player_right_type.next();
-                        self.update_controllers(context)?;
-                    }
+                    self.cycle_player_type(self.selected_player, true);
+                    self.update_controllers()?;
                 } else if context.keyboard.is_key_just_pressed(KeyCode::Down) {
-                    if self.selected_player == 1 {
-                        self.player_left_type = self.player_left_type.previous();
-                        self.update_controllers(context)?;
-                    } else {
-                        self.player_right_type = self.player_right_type.previous();
-                        self.update_controllers(context)?;
-                    }
+                    self.cycle_player_type(self.selected_player, false);
+                    self.update_controllers()?;
                 }
 
                 // Number keys for direct selection (1=Human, 2=Easy, 3=Medium, 4=Hard)
@@ -165,45 +1097,123 @@ impl event::EventHandler for MainState {
                 };
 
                 if let Some(new_type) = selected_type {
-                    if self.selected_player == 1 {
-                        self.player_left_type = new_type;
-                        self.update_controllers(context)?;
-                    } else {
-                        self.player_right_type = new_type;
-                        self.update_controllers(context)?;
-                    }
+                    *self.player_type_mut(self.selected_player) = new_type;
+                    self.update_controllers()?;
                 }
 
                 // Handle mouse clicks for player type selection
                 if context.mouse.button_just_pressed(ggez::event::MouseButton::Left) {
                     let mouse_position = context.mouse.position();
                     let mouse_vec = Vec2::new(mouse_position.x, mouse_position.y);
-                    if let Some(clicked_player) = ui_menu::hit_test_player(context, mouse_vec) {
+                    if let Some(clicked_player) = ui_menu::hit_test_player(context, mouse_vec, player_count as usize) {
                         self.selected_player = clicked_player; // Update selected player
-                        match clicked_player {
-                            1 => {
-                                self.player_left_type = self.player_left_type.next();
-                                self.update_controllers(context)?;
-                            }
-                            2 => {
-                                self.player_right_type = self.player_right_type.next();
-                                self.update_controllers(context)?;
-                            }
-                            _ => {}
-                        }
+                        self.cycle_player_type(clicked_player, true);
+                        self.update_controllers()?;
                     }
                 }
 
+                // Career profiles
+                if context.keyboard.is_key_just_pressed(KeyCode::LBracket) {
+                    self.cycle_profile(false);
+                } else if context.keyboard.is_key_just_pressed(KeyCode::RBracket) {
+                    self.cycle_profile(true);
+                } else if context.keyboard.is_key_just_pressed(KeyCode::N) {
+                    self.name_entry = Some(String::new());
+                    return Ok(());
+                } else if context.keyboard.is_key_just_pressed(KeyCode::L) {
+                    self.change_state(GameState::Leaderboard);
+                    return Ok(());
+                } else if context.keyboard.is_key_just_pressed(KeyCode::S) {
+                    self.available_packs = packs::discover(&self.packs_dir);
+                    self.available_themes = theme::available_themes(&self.themes_dir);
+                    self.settings_selected = 0;
+                    self.change_state(GameState::Settings);
+                    return Ok(());
+                } else if context.keyboard.is_key_just_pressed(KeyCode::A) {
+                    self.change_state(GameState::Achievements);
+                    return Ok(());
+                } else if context.keyboard.is_key_just_pressed(KeyCode::H) {
+                    self.change_state(GameState::HighScores);
+                    return Ok(());
+                } else if context.keyboard.is_key_just_pressed(KeyCode::C) {
+                    self.campaign_selected = self.campaign.cleared().min(LADDER.len() - 1);
+                    self.change_state(GameState::Campaign);
+                    return Ok(());
+                } else if context.keyboard.is_key_just_pressed(KeyCode::T) {
+                    // A tournament in progress picks up where it was left
+                    self.tournament_abandon.dismiss();
+                    self.change_state(if self.tournament.is_some() {
+                        GameState::Tournament
+                    } else {
+                        GameState::TournamentSetup
+                    });
+                    return Ok(());
+                } else if context.keyboard.is_key_just_pressed(KeyCode::R) {
+                    self.cycle_arena();
+                } else if context.keyboard.is_key_just_pressed(KeyCode::E) {
+                    self.editor = Some(Editor::open(self.simulation.arena().clone(), &self.settings.arena));
+                    self.change_state(GameState::Editor);
+                    return Ok(());
+                }
+
                 // Press SPACE or ENTER to start the game
                 if context.keyboard.is_key_just_pressed(KeyCode::Space) || context.keyboard.is_key_just_pressed(KeyCode::Return) {
-                    self.reset_game(context)?;
-                    self.state = GameState::Playing;
+                    self.reset_game(context);
+                    self.change_state(GameState::Playing);
                 }
             }
+            GameState::Leaderboard => {
+                // Press SPACE, ENTER or L to return to menu (Esc is handled by quit_event)
+                if context.keyboard.is_key_just_pressed(KeyCode::Space)
+                    || context.keyboard.is_key_just_pressed(KeyCode::Return)
+                    || context.keyboard.is_key_just_pressed(KeyCode::L)
+                {
+                    self.change_state(GameState::Menu);
+                }
+            }
+            GameState::Achievements => {
+                // Press SPACE, ENTER or A to return to menu (Esc is handled by quit_event)
+                if context.keyboard.is_key_just_pressed(KeyCode::Space)
+                    || context.keyboard.is_key_just_pressed(KeyCode::Return)
+                    || context.keyboard.is_key_just_pressed(KeyCode::A)
+                {
+                    self.change_state(GameState::Menu);
+                }
+            }
+            GameState::HighScores => {
+                // Press SPACE, ENTER or H to return to menu (Esc is handled by quit_event)
+                if context.keyboard.is_key_just_pressed(KeyCode::Space)
+                    || context.keyboard.is_key_just_pressed(KeyCode::Return)
+                    || context.keyboard.is_key_just_pressed(KeyCode::H)
+                {
+                    self.change_state(GameState::Menu);
+                }
+            }
+            GameState::Settings => {
+                let rows = SettingsItem::ALL.len();
+                if context.keyboard.is_key_just_pressed(KeyCode::Up) {
+                    self.settings_selected = (self.settings_selected + rows - 1) % rows;
+                } else if context.keyboard.is_key_just_pressed(KeyCode::Down) {
+                    self.settings_selected = (self.settings_selected + 1) % rows;
+                } else if context.keyboard.is_key_just_pressed(KeyCode::Left) {
+                    self.change_setting(context, false)?;
+                } else if context.keyboard.is_key_just_pressed(KeyCode::Right) {
+                    self.change_setting(context, true)?;
+                } else if context.keyboard.is_key_just_pressed(KeyCode::Space)
+                    || context.keyboard.is_key_just_pressed(KeyCode::Return)
+                    || context.keyboard.is_key_just_pressed(KeyCode::S)
+                {
+                    self.change_state(GameState::Menu);
+                }
+            }
+            GameState::Editor => self.update_editor(context),
+            GameState::Campaign => self.update_campaign(context)?,
+            GameState::TournamentSetup => self.update_tournament_setup(context),
+            GameState::Tournament => self.update_tournament(context)?,
             GameState::Playing => {
                 // Toggle pause with P
                 if context.keyboard.is_key_just_pressed(KeyCode::P) {
-                    self.state = GameState::Paused;
+                    self.change_state(GameState::Paused);
                     return Ok(());
                 }
                 self.update_playing(context, delta_time)?;
@@ -211,18 +1221,25 @@ impl event::EventHandler for MainState {
             GameState::Paused => {
                 // P resumes from pause
                 if context.keyboard.is_key_just_pressed(KeyCode::P) {
-                    self.state = GameState::Playing;
+                    self.change_state(GameState::Playing);
                 }
             }
-            GameState::GameOver { .. } => {
-                // Press SPACE or ENTER to return to menu
+            GameState::GameOver { .. } | GameState::ChallengeOver { .. } => {
+                // Let the last goal's particles settle behind the results
+                self.effects.update(delta_time);
+                // Press SPACE or ENTER to return to menu, to the editor after a test play, to the campaign ladder or to
+                // the tournament bracket
                 if context.keyboard.is_key_just_pressed(KeyCode::Space) || context.keyboard.is_key_just_pressed(KeyCode::Return) {
-                    self.state = GameState::Menu;
+                    self.leave_match();
+                }
+                // Press R to restart round immediately, except in a tournament where the result stands
+                if context.keyboard.is_key_just_pressed(KeyCode::R) && self.tournament_match.is_none() {
+                    self.reset_game(context);
+                    self.change_state(GameState::Playing);
                 }
-                // Press R to restart round immediately
-                if context.keyboard.is_key_just_pressed(KeyCode::R) {
-                    self.reset_game(context)?;
-                    self.state = GameState::Playing;
+                // Press H after a challenge to see where it stands
+                if matches!(self.state, GameState::ChallengeOver { .. }) && self.editor.is_none() && context.keyboard.is_key_just_pressed(KeyCode::H) {
+                    self.change_state(GameState::HighScores);
                 }
             }
         }
@@ -231,37 +1248,166 @@ impl event::EventHandler for MainState {
     }
 
     fn draw(&mut self, context: &mut Context) -> GameResult {
-        let mut canvas = Canvas::from_frame(context, Color::BLACK);
+        let mut canvas = self.post_process.begin(context, self.settings.crt, self.theme.background);
+
+        // The attract mode stays in place while the menu slides over it
+        if self.state == GameState::Menu {
+            self.draw_attract(context, &mut canvas)?;
+        }
+        let (screen_width, _) = context.gfx.drawable_size();
+        let origin = Vec2::new(self.transition.as_ref().map_or(0.0, |transition| transition.offset(screen_width)), 0.0);
+        canvas.set_screen_coordinates(view(context, origin));
 
         match &self.state {
             GameState::Menu => {
-                ui_menu::draw_menu(context, &mut canvas, self.player_left_type, self.player_right_type, self.selected_player)?;
+                let slots = [
+                    (self.player_left_type, self.player_left_profile.as_deref()),
+                    (self.player_right_type, self.player_right_profile.as_deref()),
+                    (self.player_three_type, None),
+                    (self.player_four_type, None),
+                ]
+                .map(|(player_type, profile)| ui_menu::PlayerSlot { player_type, profile });
+                let slots = &slots[..self.player_count() as usize];
+                let setup = ui_menu::MatchSetup {
+                    mode: self.game_mode,
+                    arena: &self.simulation.arena().name,
+                };
+                ui_menu::draw_menu(
+                    context,
+                    &mut canvas,
+                    &self.theme,
+                    setup,
+                    slots,
+                    self.selected_player,
+                    self.name_entry.as_deref(),
+                )?;
+            }
+            GameState::Leaderboard => {
+                crate::ui::leaderboard::draw_leaderboard(context, &mut canvas, &self.theme, &self.profiles.leaderboard())?;
+            }
+            GameState::Achievements => {
+                crate::ui::achievements::draw_achievements(context, &mut canvas, &self.theme, &self.achievements)?;
+            }
+            GameState::HighScores => {
+                crate::ui::high_scores::draw_high_scores(context, &mut canvas, &self.theme, &self.high_scores)?;
+            }
+            GameState::Campaign => {
+                crate::ui::campaign::draw_campaign(context, &mut canvas, &self.theme, &self.campaign, self.campaign_selected)?;
+            }
+            GameState::TournamentSetup => {
+                ui_tournament::draw_tournament_setup(context, &mut canvas, &self.theme, &self.tournament_setup, self.winning_score)?;
+            }
+            GameState::Tournament => {
+                if let Some(tournament) = &self.tournament {
+                    ui_tournament::draw_tournament(context, &mut canvas, &self.theme, tournament)?;
+                }
+            }
+            GameState::Settings => {
+                crate::ui::settings::draw_settings(context, &mut canvas, &self.theme, &self.settings_lines(), self.settings_selected)?;
+            }
+            GameState::Editor => {
+                if let Some(editor) = &self.editor {
+                    canvas.draw(&self.middle_line_mesh, DrawParam::default().color(Color { a: 0.3, ..self.theme.net }));
+                    ui_editor::draw_editor(context, &mut canvas, &self.theme, editor)?;
+                }
             }
             GameState::Playing => {
-                self.draw_playing(&mut canvas);
+                self.draw_playing(context, &mut canvas, origin)?;
+                // Resuming from pause counts down on top of whatever the serve is doing
+                if let Some(countdown) = self.countdown.as_ref().or(self.simulation.serve_countdown()) {
+                    crate::ui::countdown::draw_countdown(context, &mut canvas, &self.theme, countdown)?;
+                } else if let Some(server) = self.simulation.held_serve() {
+                    crate::ui::countdown::draw_serve_prompt(context, &mut canvas, &self.theme, server)?;
+                }
             }
             GameState::Paused => {
-                self.draw_playing(&mut canvas);
-                crate::ui::pause_screen::draw_pause_screen(context, &mut canvas)?;
+                self.draw_playing(context, &mut canvas, origin)?;
+                crate::ui::pause_screen::draw_pause_screen(context, &mut canvas, &self.theme)?;
             }
             GameState::GameOver { winner } => {
-                self.draw_playing(&mut canvas);
-                crate::ui::game_over::draw_game_over(context, &mut canvas, *winner)?;
+                self.draw_playing(context, &mut canvas, origin)?;
+                let mode = self.simulation.mode();
+                // Tournament results name the entrant who won, and cannot be replayed
+                let (title, hint) = match (self.tournament_match, &self.tournament) {
+                    (Some(index), Some(tournament)) => {
+                        let winner = tournament.bracket.seat(Slot::Winner(index));
+                        (format!("{} Wins!", tournament.seat_name(winner)), "SPACE/ENTER: Back to the bracket")
+                    }
+                    _ => (mode.winner_title(*winner), "R: Restart  |   Esc: Menu"),
+                };
+                crate::ui::game_over::draw_game_over(context, &mut canvas, &self.theme, mode, &title, hint, &self.stats)?;
+            }
+            GameState::ChallengeOver { result, place } => {
+                self.draw_playing(context, &mut canvas, origin)?;
+                ui_challenge::draw_challenge_over(context, &mut canvas, &self.theme, result, *place, &self.stats)?;
             }
         }
 
-        crate::ui::hud::draw_hud(context, &mut canvas, &self.debug)?;
-        canvas.finish(context)?;
+        canvas.set_screen_coordinates(view(context, Vec2::ZERO));
+        if let Some(transition) = &self.transition {
+            transition.draw_veil(context, &mut canvas, self.theme.background)?;
+        }
+
+        crate::ui::hud::draw_hud(context, &mut canvas, &self.theme, &self.debug)?;
+        self.toasts.draw(context, &mut canvas, &self.theme)?;
+        self.post_process.finish(context, canvas, self.settings.crt, &self.theme.crt)
+    }
+
+    fn mouse_wheel_event(&mut self, _ctx: &mut Context, _x: f32, y: f32) -> GameResult {
+        if self.state == GameState::Editor
+            && let Some(editor) = &mut self.editor
+            && y != 0.0
+        {
+            editor.resize_selected(y.signum());
+        }
+        Ok(())
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) -> GameResult {
+        let entry = if self.state == GameState::TournamentSetup {
+            Some(&mut self.tournament_setup.name)
+        } else {
+            self.name_entry.as_mut()
+        };
+        if let Some(name) = entry
+            && !character.is_control()
+            && name.chars().count() < MAX_NAME_LENGTH
+        {
+            name.push(character);
+        }
         Ok(())
     }
 
     fn quit_event(&mut self, _ctx: &mut Context) -> GameResult<bool> {
+        // Esc while typing a profile name only cancels the name entry.
+        if self.name_entry.take().is_some() {
+            return Ok(true);
+        }
+
         // If we're on the menu, allow the quit to proceed (return Ok(false)).
         // Otherwise, return to the menu and cancel the quit (return Ok(true)).
+        // Test plays go back to the editor, and the editor to the menu once unsaved changes are confirmed. Campaign
+        // matches go back to the ladder and tournament matches to the bracket, leaving the match unplayed.
         match &self.state {
             GameState::Menu => Ok(false),
+            GameState::Editor => {
+                self.leave_editor();
+                Ok(true)
+            }
+            GameState::Campaign => {
+                self.leave_campaign()?;
+                Ok(true)
+            }
+            GameState::TournamentSetup | GameState::Tournament => {
+                self.return_to_menu()?;
+                Ok(true)
+            }
+            _ if self.editor.is_some() || self.campaign_rung.is_some() || self.tournament_match.is_some() => {
+                self.leave_match();
+                Ok(true)
+            }
             _ => {
-                self.state = GameState::Menu;
+                self.change_state(GameState::Menu);
                 Ok(true)
             }
         }
@@ -269,8 +1415,44 @@ impl event::EventHandler for MainState {
 }
 
 impl MainState {
+    // Where a match leads back to: the editor after a test play, the ladder after a campaign match, the bracket after
+    // a tournament match, or the menu.
+    fn screen_after_match(&self) -> GameState {
+        if self.editor.is_some() {
+            GameState::Editor
+        } else if self.campaign_rung.is_some() {
+            GameState::Campaign
+        } else if self.tournament_match.is_some() {
+            GameState::Tournament
+        } else {
+            GameState::Menu
+        }
+    }
+
+    fn leave_match(&mut self) {
+        let screen = self.screen_after_match();
+        self.tournament_match = None;
+        self.tournament_abandon.dismiss();
+        self.change_state(screen);
+    }
+
     fn update_playing(&mut self, context: &mut Context, delta_time: f32) -> GameResult {
-        self.debug.set_ball_info(context, self.ball.position, self.ball.velocity, self.ball.speed)?;
+        // Debug info follows the served ball
+        let ball = &self.simulation.balls[0];
+        self.debug.set_ball_info(context, ball.position, ball.velocity, ball.speed)?;
+
+        // The resume countdown freezes play until "GO"
+        if let Some(countdown) = &mut self.countdown {
+            countdown.update(delta_time);
+            let blocking = countdown.is_blocking();
+            if countdown.is_finished() {
+                self.countdown = None;
+            }
+            if blocking {
+                self.effects.update(delta_time);
+                return Ok(());
+            }
+        }
 
         // Move rackets (player 1: W/S, player 2: Up/Down)
         let mut pressed = HashSet::new();
@@ -278,68 +1460,137 @@ impl MainState {
             pressed.insert(*k);
         }
 
-        let input_left = ControllerInput {
-            ball_position: self.ball.position,
-            ball_velocity: self.ball.velocity,
-            racket_position: self.player_left.position_y,
-            racket_x: self.player_left.position_x,
-            screen_height: context.gfx.drawable_size().1,
-            pressed_keys: pressed.clone(),
-        };
+        let (screen_width, screen_height) = context.gfx.drawable_size();
+        self.simulation.resize(screen_width, screen_height);
+        let events = self.simulation.step(delta_time, &pressed);
+        events::dispatch(&events, &mut [&mut self.stats, &mut self.audio, &mut self.achievements, &mut self.effects]);
+        self.effects.set_arena(screen_width, screen_height);
+        self.effects.update(delta_time);
+        self.effects.record_balls(self.simulation.balls.iter().map(|ball| ball.position));
+        self.audio.play_pending(context);
+        self.announce_achievements(&events);
 
-        let input_right = ControllerInput {
-            ball_position: self.ball.position,
-            ball_velocity: self.ball.velocity,
-            racket_position: self.player_right.position_y,
-            racket_x: self.player_right.position_x,
-            screen_height: context.gfx.drawable_size().1,
-            pressed_keys: pressed,
-        };
+        if let Some(result) = self.simulation.challenge_result() {
+            let place = self.record_high_score(&result);
+            self.change_state(GameState::ChallengeOver { result, place });
+        } else if let Some(winner) = self.simulation.winner() {
+            self.change_state(GameState::GameOver { winner });
+            self.record_career_match();
+            if let Some(index) = self.campaign_rung
+                && winner == Player::Left
+            {
+                self.record_campaign_win(index);
+            }
+            if let Some(index) = self.tournament_match {
+                self.record_tournament_result(index, winner);
+            }
+        }
 
-        self.player_left.update(&input_left, delta_time);
-        self.player_right.update(&input_right, delta_time);
+        Ok(())
+    }
 
-        if bounce_borders(&mut self.ball, context.gfx.drawable_size().1) {
-            let _ = play_embedded_sound(context, "wall_bounce.wav");
-        }
+    // Draw the match being played, shifted by `origin` (screen transitions) and the screen shake.
+    fn draw_playing(&self, context: &mut Context, canvas: &mut Canvas, origin: Vec2) -> GameResult {
+        // Screen shake moves the whole field, but not the screens drawn over it
+        canvas.set_screen_coordinates(view(context, origin + self.effects.shake_offset()));
 
-        if racket_collision(&mut self.ball, &self.player_left) || racket_collision(&mut self.ball, &self.player_right) {
-            let _ = play_embedded_sound(context, "racket_hit.wav");
+        self.simulation
+            .score
+            .draw_on_canvas(context, canvas, self.theme.score, self.simulation.mode())?;
+        if !self.simulation.mode().guards_every_wall() {
+            canvas.draw(&self.middle_line_mesh, DrawParam::default());
         }
-
-        if let Some(scored) = check_score(&self.ball, context.gfx.drawable_size().0) {
-            let _ = play_embedded_sound(context, "score.wav");
-            match scored {
-                Player::Left => {
-                    self.score.increment_p1(context)?;
-                    // Check if player 1 won
-                    if self.score.get_p1_score() >= WINNING_SCORE {
-                        self.state = GameState::GameOver { winner: Player::Left };
-                        return Ok(());
-                    }
-                }
-                Player::Right => {
-                    self.score.increment_p2(context)?;
-                    // Check if player 2 won
-                    if self.score.get_p2_score() >= WINNING_SCORE {
-                        self.state = GameState::GameOver { winner: Player::Right };
-                        return Ok(());
-                    }
+        ui_arena::draw_obstacles(context, canvas, &self.theme, &self.simulation.obstacles())?;
+        ui_arena::draw_bricks(context, canvas, &self.theme, self.simulation.bricks())?;
+        ui_effects::draw_particles(canvas, &self.particle_mesh, &self.theme, self.effects.particles());
+        ui_powerups::draw_field_power_ups(context, canvas, &self.theme, self.simulation.power_ups())?;
+        for (player, racket) in self.simulation.rackets() {
+            racket.draw_on_canvas(canvas, &self.racket_mesh, self.effects.racket_scale(player));
+        }
+        // An invisible ball hides its trail too
+        if self.simulation.balls_visible() {
+            if self.settings.crt.trail {
+                for trail in self.effects.trails() {
+                    ui_effects::draw_trail(canvas, &self.ball_mesh, trail, self.theme.crt.persistence, self.effects.intensity());
                 }
             }
-            self.ball.reset(context.gfx.drawable_size().0 / 2.0, context.gfx.drawable_size().1 / 2.0);
+            for ball in &self.simulation.balls {
+                ball.draw_on_canvas(canvas, &self.ball_mesh);
+            }
         }
 
-        self.ball.move_ball(delta_time);
-
+        canvas.set_screen_coordinates(view(context, origin));
+        ui_powerups::draw_active_power_ups(context, canvas, &self.theme, self.simulation.power_ups())?;
+        if let Some(run) = self.simulation.challenge() {
+            ui_challenge::draw_challenge_status(context, canvas, &self.theme, run)?;
+        }
         Ok(())
     }
 
-    fn draw_playing(&self, canvas: &mut Canvas) {
-        self.score.draw_on_canvas(canvas);
+    // The attract mode match, dimmed so the menu stays readable.
+    fn draw_attract(&self, context: &mut Context, canvas: &mut Canvas) -> GameResult {
+        let attract = &self.attract;
+        attract.score.draw_on_canvas(context, canvas, self.theme.score, attract.mode())?;
         canvas.draw(&self.middle_line_mesh, DrawParam::default());
-        self.player_left.draw_on_canvas(canvas);
-        self.player_right.draw_on_canvas(canvas);
-        self.ball.draw_on_canvas(canvas);
+        for (_, racket) in attract.rackets() {
+            racket.draw_on_canvas(canvas, &self.racket_mesh, Vec2::ONE);
+        }
+        for ball in &attract.balls {
+            ball.draw_on_canvas(canvas, &self.ball_mesh);
+        }
+
+        let (screen_width, screen_height) = context.gfx.drawable_size();
+        let rect = Rect::new(0.0, 0.0, screen_width, screen_height);
+        let dim = Mesh::new_rectangle(context, DrawMode::fill(), rect, self.theme.overlay)?;
+        canvas.draw(&dim, DrawParam::default());
+        Ok(())
     }
 }
+
+// Which transition leads from one screen to another, if any. Pausing and resuming are instant: the countdown
+// already marks the way back into play.
+fn transition_for(from: &GameState, to: &GameState) -> Option<TransitionKind> {
+    match (from, to) {
+        (
+            GameState::Menu,
+            GameState::Leaderboard
+            | GameState::Achievements
+            | GameState::HighScores
+            | GameState::Campaign
+            | GameState::TournamentSetup
+            | GameState::Tournament
+            | GameState::Settings
+            | GameState::Editor,
+        ) => Some(TransitionKind::SlideLeft),
+        (
+            GameState::Leaderboard
+            | GameState::Achievements
+            | GameState::HighScores
+            | GameState::Campaign
+            | GameState::TournamentSetup
+            | GameState::Tournament
+            | GameState::Settings
+            | GameState::Editor,
+            GameState::Menu,
+        ) => Some(TransitionKind::SlideRight),
+        (GameState::Playing, GameState::Paused) | (GameState::Paused, GameState::Playing) => None,
+        (from, to) if from == to => None,
+        _ => Some(TransitionKind::Fade),
+    }
+}
+
+// Screen coordinates that draw everything shifted by `offset`.
+fn view(context: &Context, offset: Vec2) -> Rect {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    Rect::new(-offset.x, -offset.y, screen_width, screen_height)
+}
+
+fn on_off(on: bool) -> String {
+    (if on { "On" } else { "Off" }).to_string()
+}
+
+fn build_middle_line_mesh(context: &mut Context, color: Color) -> GameResult<Mesh> {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let rectangle = Rect::new(screen_width / 2.0 - MIDDLE_LINE_WIDTH / 2.0, 0.0, MIDDLE_LINE_WIDTH, screen_height);
+    Mesh::new_rectangle(context, DrawMode::fill(), rectangle, color)
+}
@@ -0,0 +1,120 @@
+// The `pack.json` manifest at the root of an asset pack:
+//
+// {
+//   "name": "Retro",
+//   "sounds": { "racket_hit": "sounds/hit.wav", "wall_bounce": "sounds/wall.wav", "score": "sounds/point.wav" },
+//   "font": "fonts/retro.ttf",
+//   "colors": { "background": "#101020", "ball": "#f0f0f0", "racket": "#f0f0f0", "net": "#404060", "score": "#202040" }
+// }
+//
+// Every entry but the name is optional; anything left out falls back to the embedded assets. Colors override the
+// current theme and use the keys of `Theme::KEYS`.
+
+use crate::audio::manager::SOUND_EFFECTS;
+use crate::packs::PackError;
+use crate::ui::theme;
+use ggez::graphics::Color;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+pub const MANIFEST_FILE: &str = "pack.json";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawManifest {
+    name: String,
+    #[serde(default)]
+    sounds: BTreeMap<String, String>,
+    font: Option<String>,
+    #[serde(default)]
+    colors: BTreeMap<String, String>,
+}
+
+// A parsed and checked manifest. Files are not read yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifest {
+    pub name: String,
+    // Sound effect name (as in `SOUND_EFFECTS`) and the file replacing it
+    pub sounds: Vec<(&'static str, String)>,
+    pub font: Option<String>,
+    pub colors: Vec<(String, Color)>,
+}
+
+// Key used for a sound effect in manifests: its file name without the extension.
+pub fn sound_key(sound: &str) -> &str {
+    sound.strip_suffix(".wav").unwrap_or(sound)
+}
+
+pub fn parse(json: &[u8]) -> Result<Manifest, PackError> {
+    let raw: RawManifest = serde_json::from_slice(json).map_err(|error| PackError::Invalid(format!("{}: {}", MANIFEST_FILE, error)))?;
+    if raw.name.trim().is_empty() {
+        return Err(PackError::Invalid(format!("{}: the pack needs a name", MANIFEST_FILE)));
+    }
+
+    let mut sounds = Vec::new();
+    for (key, file) in raw.sounds {
+        let Some(sound) = SOUND_EFFECTS.iter().find(|sound| sound_key(sound) == key) else {
+            let known: Vec<&str> = SOUND_EFFECTS.iter().map(|sound| sound_key(sound)).collect();
+            return Err(PackError::Invalid(format!("unknown sound '{}' (expected one of: {})", key, known.join(", "))));
+        };
+        check_path(&file)?;
+        sounds.push((*sound, file));
+    }
+
+    if let Some(font) = &raw.font {
+        check_path(font)?;
+    }
+
+    let colors = theme::parse_colors(&raw.colors).map_err(PackError::Invalid)?;
+
+    Ok(Manifest {
+        name: raw.name.trim().to_string(),
+        sounds,
+        font: raw.font,
+        colors,
+    })
+}
+
+// Files must stay inside the pack.
+fn check_path(file: &str) -> Result<(), PackError> {
+    let inside = !file.is_empty() && Path::new(file).components().all(|component| matches!(component, Component::Normal(_)));
+    if inside {
+        Ok(())
+    } else {
+        Err(PackError::Invalid(format!("'{}' must be a relative path inside the pack", file)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_manifest() {
+        let manifest = parse(
+            br##"{
+                "name": " Retro ",
+                "sounds": { "racket_hit": "sounds/hit.wav" },
+                "font": "fonts/retro.ttf",
+                "colors": { "ball": "#ff0000" }
+            }"##,
+        )
+        .unwrap();
+        assert_eq!(manifest.name, "Retro");
+        assert_eq!(manifest.sounds, vec![("racket_hit.wav", "sounds/hit.wav".to_string())]);
+        assert_eq!(manifest.font.as_deref(), Some("fonts/retro.ttf"));
+        assert_eq!(manifest.colors, vec![("ball".to_string(), Color::from_rgb(255, 0, 0))]);
+    }
+
+    #[test]
+    fn errors_name_the_problem() {
+        let error = |json: &str| parse(json.as_bytes()).unwrap_err().to_string();
+        assert!(error(r#"{ "name": "A", "sounds": { "boing": "a.wav" } }"#).contains("unknown sound 'boing'"));
+        assert!(error(r#"{ "name": "A", "colors": { "ball": "red" } }"#).contains("color 'ball'"));
+        assert!(error(r##"{ "name": "A", "colors": { "sky": "#000000" } }"##).contains("unknown color 'sky'"));
+        assert!(error(r#"{ "name": "A", "font": "../evil.ttf" }"#).contains("inside the pack"));
+        assert!(error(r#"{ "name": "A", "music": {} }"#).contains("unknown field"));
+        assert!(error(r#"{ "name": "" }"#).contains("needs a name"));
+    }
+}
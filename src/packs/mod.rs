@@ -0,0 +1,217 @@
+// Asset packs: a directory or a zip file in the `packs` folder of the user data directory, with a `pack.json`
+// manifest at its root (see `manifest`). A pack overrides some of the embedded sounds, the font and the playing field
+// theme colors; whatever it leaves out keeps using the embedded assets.
+//
+// Packs are fully read and validated when loaded, so a broken pack is reported once and never half-applied.
+
+pub mod manifest;
+
+use crate::packs::manifest::MANIFEST_FILE;
+use ggez::audio::SoundData;
+use ggez::graphics::{Color, FontData};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+use zip::result::ZipError;
+
+pub const PACKS_DIR: &str = "packs";
+
+#[derive(Debug)]
+pub enum PackError {
+    Io(io::Error),
+    Zip(ZipError),
+    Invalid(String),
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::Io(error) => write!(f, "I/O error: {}", error),
+            PackError::Zip(error) => write!(f, "unreadable zip file: {}", error),
+            PackError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<io::Error> for PackError {
+    fn from(error: io::Error) -> Self {
+        PackError::Io(error)
+    }
+}
+
+impl From<ZipError> for PackError {
+    fn from(error: ZipError) -> Self {
+        PackError::Zip(error)
+    }
+}
+
+#[derive(Debug)]
+pub struct AssetPack {
+    pub name: String,
+    pub sounds: HashMap<&'static str, SoundData>,
+    // Checked TrueType/OpenType data
+    pub font: Option<Vec<u8>>,
+    // Theme colors to override, keyed by `Theme::KEYS`
+    pub colors: Vec<(String, Color)>,
+}
+
+enum PackFiles {
+    Directory(PathBuf),
+    Zip(ZipArchive<File>),
+}
+
+impl PackFiles {
+    fn read(&mut self, file: &str) -> Result<Vec<u8>, PackError> {
+        let missing = || PackError::Invalid(format!("{} is missing from the pack", file));
+        match self {
+            PackFiles::Directory(root) => fs::read(root.join(file)).map_err(|error| match error.kind() {
+                io::ErrorKind::NotFound => missing(),
+                _ => PackError::Io(error),
+            }),
+            PackFiles::Zip(archive) => {
+                let mut entry = archive.by_name(file).map_err(|error| match error {
+                    ZipError::FileNotFound => missing(),
+                    error => PackError::Zip(error),
+                })?;
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes)?;
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+// Packs found in `directory`: sub-directories with a manifest and zip files, sorted by id.
+pub fn discover(directory: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = entries
+        .flatten()
+        .filter(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                path.join(MANIFEST_FILE).is_file()
+            } else {
+                path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("zip"))
+            }
+        })
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    ids.sort();
+    ids
+}
+
+// Read and validate the pack `id` in `directory`.
+pub fn load(directory: &Path, id: &str) -> Result<AssetPack, PackError> {
+    let path = directory.join(id);
+    let mut files = if path.is_dir() {
+        PackFiles::Directory(path)
+    } else if path.is_file() {
+        PackFiles::Zip(ZipArchive::new(File::open(&path)?)?)
+    } else {
+        return Err(PackError::Invalid(format!("no pack named '{}' in {}", id, directory.display())));
+    };
+
+    let manifest = manifest::parse(&files.read(MANIFEST_FILE)?)?;
+
+    let mut sounds = HashMap::new();
+    for (sound, file) in &manifest.sounds {
+        let data = SoundData::from(files.read(file)?);
+        if !data.can_play() {
+            return Err(PackError::Invalid(format!("{} is not a playable sound file", file)));
+        }
+        sounds.insert(*sound, data);
+    }
+
+    let font = match &manifest.font {
+        Some(file) => {
+            let bytes = files.read(file)?;
+            if FontData::from_vec(bytes.clone()).is_err() {
+                return Err(PackError::Invalid(format!("{} is not a TrueType or OpenType font", file)));
+            }
+            Some(bytes)
+        }
+        None => None,
+    };
+
+    Ok(AssetPack {
+        name: manifest.name,
+        sounds,
+        font,
+        colors: manifest.colors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::Asset;
+    use crate::audio::manager::RACKET_HIT;
+    use std::io::Write;
+    use zip::write::FileOptions;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust-pong-packs-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const MANIFEST: &str = r##"{ "name": "Test", "sounds": { "racket_hit": "hit.wav" }, "colors": { "ball": "#ff0000" } }"##;
+
+    fn hit_sound() -> Vec<u8> {
+        Asset::get(RACKET_HIT).unwrap().data.to_vec()
+    }
+
+    #[test]
+    fn directory_pack() {
+        let dir = temp_dir("directory");
+        fs::create_dir(dir.join("test")).unwrap();
+        fs::write(dir.join("test").join(MANIFEST_FILE), MANIFEST).unwrap();
+        fs::write(dir.join("test").join("hit.wav"), hit_sound()).unwrap();
+        fs::create_dir(dir.join("not-a-pack")).unwrap();
+
+        assert_eq!(discover(&dir), vec!["test".to_string()]);
+        let pack = load(&dir, "test").unwrap();
+        assert_eq!(pack.name, "Test");
+        assert!(pack.sounds.contains_key(RACKET_HIT));
+        assert_eq!(pack.colors, vec![("ball".to_string(), Color::from_rgb(255, 0, 0))]);
+        assert!(pack.font.is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn zip_pack() {
+        let dir = temp_dir("zip");
+        let mut zip = zip::ZipWriter::new(File::create(dir.join("test.zip")).unwrap());
+        zip.start_file(MANIFEST_FILE, FileOptions::default()).unwrap();
+        zip.write_all(MANIFEST.as_bytes()).unwrap();
+        zip.start_file("hit.wav", FileOptions::default()).unwrap();
+        zip.write_all(&hit_sound()).unwrap();
+        zip.finish().unwrap();
+
+        assert_eq!(discover(&dir), vec!["test.zip".to_string()]);
+        let pack = load(&dir, "test.zip").unwrap();
+        assert!(pack.sounds.contains_key(RACKET_HIT));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn broken_packs_are_rejected() {
+        let dir = temp_dir("broken");
+        fs::create_dir(dir.join("missing")).unwrap();
+        fs::write(dir.join("missing").join(MANIFEST_FILE), MANIFEST).unwrap();
+        fs::create_dir(dir.join("garbage")).unwrap();
+        fs::write(dir.join("garbage").join(MANIFEST_FILE), MANIFEST).unwrap();
+        fs::write(dir.join("garbage").join("hit.wav"), b"not a sound").unwrap();
+
+        assert_eq!(load(&dir, "missing").unwrap_err().to_string(), "hit.wav is missing from the pack");
+        assert_eq!(load(&dir, "garbage").unwrap_err().to_string(), "hit.wav is not a playable sound file");
+        assert!(load(&dir, "nowhere").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
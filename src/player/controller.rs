@@ -1,8 +1,15 @@
-use crate::game::racket::RACKET_HEIGHT_HALF;
 use ggez::{glam::Vec2, input::keyboard::KeyCode};
 use std::collections::HashSet;
 
 const AI_RACKET_PERCEPTION: f32 = 0.75;
+// The defensive AI takes every ball on the middle of its racket, the aggressive one this far out towards an edge
+const DEFENSIVE_PERCEPTION: f32 = 0.25;
+const AGGRESSIVE_PERCEPTION: f32 = 0.15;
+const AGGRESSIVE_EDGE: f32 = 0.6;
+// The trickster waits on the wrong side until the ball is this many seconds away, then returns it off a different
+// part of its racket each time
+const TRICKSTER_FEINT_TIME: f32 = 1.2;
+const TRICKSTER_OFFSETS: [f32; 5] = [0.6, -0.3, 0.0, -0.6, 0.3];
 
 pub trait Controller {
     fn get_action(&mut self, input: &ControllerInput) -> RacketAction;
@@ -14,15 +21,44 @@ pub enum RacketAction {
     Stay,
 }
 
+// A ball as a controller sees it, in the racket's frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BallView {
+    pub position: Vec2,
+    pub velocity: Vec2,
+}
+
+// What a controller sees, in its racket's frame: the racket moves along y and guards the wall at x = `racket_x`.
+// For the top and bottom rackets the axes are swapped, so the same controllers play on any wall.
 pub struct ControllerInput {
-    pub ball_position: Vec2,
-    pub ball_velocity: Vec2,
+    // Every ball in play: one, unless multi-ball is on
+    pub balls: Vec<BallView>,
     pub racket_position: f32,
     pub racket_x: f32,
+    // Half the racket's current length, which power-ups change
+    pub racket_half_length: f32,
+    // Length of the racket's wall
     pub screen_height: f32,
     pub pressed_keys: HashSet<KeyCode>,
 }
 
+impl ControllerInput {
+    // Seconds until `ball` reaches the racket's line, or `None` if it is not heading there.
+    pub fn time_to_arrival(&self, ball: &BallView) -> Option<f32> {
+        let approaching = (ball.velocity.x > 0.0 && self.racket_x > ball.position.x) || (ball.velocity.x < 0.0 && self.racket_x < ball.position.x);
+        approaching.then(|| (self.racket_x - ball.position.x) / ball.velocity.x)
+    }
+
+    // The ball to defend against: of those coming at the racket, the one arriving first.
+    pub fn most_threatening_ball(&self) -> Option<&BallView> {
+        self.balls
+            .iter()
+            .filter_map(|ball| self.time_to_arrival(ball).map(|time| (ball, time)))
+            .min_by(|(_, first), (_, second)| first.total_cmp(second))
+            .map(|(ball, _)| ball)
+    }
+}
+
 pub struct HumanController {
     pub up_key: KeyCode,
     pub down_key: KeyCode,
@@ -47,8 +83,13 @@ impl Controller for HumanController {
 }
 
 trait AiBehavior {
-    // Choose a vertical target (y) for the racket based on the controller input.
-    fn choose_target(&mut self, input: &ControllerInput) -> f32;
+    // Choose a vertical target (y) for the racket to meet `ball`, one of the balls in the controller input.
+    fn choose_target(&mut self, input: &ControllerInput, ball: &BallView) -> f32;
+
+    // How far from its middle, as a fraction of its half length, the racket may meet the target.
+    fn perception(&self) -> f32 {
+        AI_RACKET_PERCEPTION
+    }
 }
 
 struct ReactiveBehavior {}
@@ -60,8 +101,8 @@ impl ReactiveBehavior {
 }
 
 impl AiBehavior for ReactiveBehavior {
-    fn choose_target(&mut self, input: &ControllerInput) -> f32 {
-        input.ball_position.y
+    fn choose_target(&mut self, _input: &ControllerInput, ball: &BallView) -> f32 {
+        ball.position.y
     }
 }
 
@@ -73,16 +114,16 @@ impl PredictiveBehavior {
     }
 
     // Predict where the ball will be vertically when it reaches racket_x.
-    pub fn predict_ball_y(&self, input: &ControllerInput) -> f32 {
+    pub fn predict_ball_y(&self, input: &ControllerInput, ball: &BallView) -> f32 {
         // time until ball reaches racket x
-        let delta_x = input.racket_x - input.ball_position.x;
-        if input.ball_velocity.x == 0.0 {
-            return input.ball_position.y;
+        let delta_x = input.racket_x - ball.position.x;
+        if ball.velocity.x == 0.0 {
+            return ball.position.y;
         }
-        let time_to_reach = delta_x / input.ball_velocity.x;
+        let time_to_reach = delta_x / ball.velocity.x;
 
         // projected vertical position at that time (may be outside bounds)
-        let projected_y = input.ball_position.y + input.ball_velocity.y * time_to_reach;
+        let projected_y = ball.position.y + ball.velocity.y * time_to_reach;
 
         // reflect across top/bottom using mirror modulus to account for bounces
         let screen_height = input.screen_height;
@@ -99,8 +140,8 @@ impl PredictiveBehavior {
 }
 
 impl AiBehavior for PredictiveBehavior {
-    fn choose_target(&mut self, input: &ControllerInput) -> f32 {
-        self.predict_ball_y(input)
+    fn choose_target(&mut self, input: &ControllerInput, ball: &BallView) -> f32 {
+        self.predict_ball_y(input, ball)
     }
 }
 
@@ -117,14 +158,90 @@ impl BalancedBehavior {
 }
 
 impl AiBehavior for BalancedBehavior {
-    fn choose_target(&mut self, input: &ControllerInput) -> f32 {
+    fn choose_target(&mut self, input: &ControllerInput, ball: &BallView) -> f32 {
         // Logic to average between Reactive and Predictive behavior
-        let reactive_target = ReactiveBehavior::new().choose_target(input);
-        let predictive_target = PredictiveBehavior::new().choose_target(input);
+        let reactive_target = ReactiveBehavior::new().choose_target(input, ball);
+        let predictive_target = PredictiveBehavior::new().choose_target(input, ball);
         (reactive_target + predictive_target) / 2.0
     }
 }
 
+// Meets every ball with the middle of its racket: few misses, but flat and readable returns.
+pub struct DefensiveBehavior {}
+
+impl DefensiveBehavior {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl AiBehavior for DefensiveBehavior {
+    fn choose_target(&mut self, input: &ControllerInput, ball: &BallView) -> f32 {
+        PredictiveBehavior::new().predict_ball_y(input, ball)
+    }
+
+    fn perception(&self) -> f32 {
+        DEFENSIVE_PERCEPTION
+    }
+}
+
+// Takes the ball near an edge of its racket to send it steeply towards the far side of the field.
+pub struct AggressiveBehavior {}
+
+impl AggressiveBehavior {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl AiBehavior for AggressiveBehavior {
+    fn choose_target(&mut self, input: &ControllerInput, ball: &BallView) -> f32 {
+        let predicted = PredictiveBehavior::new().predict_ball_y(input, ball);
+        // A ball met below the racket's middle leaves downwards
+        let away = if predicted < input.screen_height / 2.0 { 1.0 } else { -1.0 };
+        predicted - away * AGGRESSIVE_EDGE * input.racket_half_length
+    }
+
+    fn perception(&self) -> f32 {
+        AGGRESSIVE_PERCEPTION
+    }
+}
+
+// Waits on the wrong side of the field while the ball is far, then rushes to it and returns it off a different part
+// of its racket each time.
+pub struct TricksterBehavior {
+    // Index in `TRICKSTER_OFFSETS` of this return
+    trick: usize,
+    // Seconds until the tracked ball arrived, on the last frame
+    last_arrival: f32,
+}
+
+impl TricksterBehavior {
+    pub fn new() -> Self {
+        Self { trick: 0, last_arrival: 0.0 }
+    }
+}
+
+impl AiBehavior for TricksterBehavior {
+    fn choose_target(&mut self, input: &ControllerInput, ball: &BallView) -> f32 {
+        let arrival = input.time_to_arrival(ball).unwrap_or_default();
+        // The ball only gets closer until it is returned, so a longer wait means a new one is coming
+        if arrival > self.last_arrival {
+            self.trick = (self.trick + 1) % TRICKSTER_OFFSETS.len();
+        }
+        self.last_arrival = arrival;
+        let predicted = PredictiveBehavior::new().predict_ball_y(input, ball);
+        if arrival > TRICKSTER_FEINT_TIME {
+            return input.screen_height - predicted;
+        }
+        predicted - TRICKSTER_OFFSETS[self.trick] * input.racket_half_length
+    }
+
+    fn perception(&self) -> f32 {
+        AGGRESSIVE_PERCEPTION
+    }
+}
+
 pub struct AIController {
     strategy: Box<dyn AiBehavior + Send>,
 }
@@ -147,21 +264,36 @@ impl AIController {
             strategy: Box::new(PredictiveBehavior::new()),
         }
     }
+
+    pub fn defensive() -> Self {
+        Self {
+            strategy: Box::new(DefensiveBehavior::new()),
+        }
+    }
+
+    pub fn aggressive() -> Self {
+        Self {
+            strategy: Box::new(AggressiveBehavior::new()),
+        }
+    }
+
+    pub fn trickster() -> Self {
+        Self {
+            strategy: Box::new(TricksterBehavior::new()),
+        }
+    }
 }
 
 impl Controller for AIController {
     fn get_action(&mut self, input: &ControllerInput) -> RacketAction {
-        let perceived_half_height = RACKET_HEIGHT_HALF * AI_RACKET_PERCEPTION;
+        let perceived_half_height = input.racket_half_length * self.strategy.perception();
 
         let racket_top = input.racket_position - perceived_half_height;
         let racket_bottom = input.racket_position + perceived_half_height;
 
-        // Decide whether the ball is approaching this racket (works for either side)
-        let ball_approaching =
-            (input.ball_velocity.x > 0.0 && input.racket_x > input.ball_position.x) || (input.ball_velocity.x < 0.0 && input.racket_x < input.ball_position.x);
-
-        if ball_approaching {
-            let target_y = self.strategy.choose_target(input);
+        // Track the incoming ball that arrives first (works for either side); with none coming, wait in the middle
+        if let Some(ball) = input.most_threatening_ball() {
+            let target_y = self.strategy.choose_target(input, ball);
             if target_y < racket_top {
                 RacketAction::MoveUp
             } else if target_y > racket_bottom {
@@ -185,16 +317,23 @@ impl Controller for AIController {
 
 #[cfg(test)]
 mod tests {
-    use super::{ControllerInput, PredictiveBehavior};
+    use super::*;
+    use crate::game::events::GameEvent;
+    use crate::game::physics::Player;
+    use crate::game::simulation::Simulation;
+    use crate::player::player_type::PlayerType;
     use ggez::glam::Vec2;
     use std::collections::HashSet;
 
     fn base_input() -> ControllerInput {
         ControllerInput {
-            ball_position: Vec2::new(100.0, 100.0),
-            ball_velocity: Vec2::new(200.0, 50.0),
+            balls: vec![BallView {
+                position: Vec2::new(100.0, 100.0),
+                velocity: Vec2::new(200.0, 50.0),
+            }],
             racket_position: 200.0,
             racket_x: 600.0,
+            racket_half_length: 75.0,
             screen_height: 400.0,
             pressed_keys: HashSet::new(),
         }
@@ -204,7 +343,7 @@ mod tests {
     fn predictive_handles_simple_projection() {
         let predictive_behavior = PredictiveBehavior::new();
         let input = base_input();
-        let y = predictive_behavior.predict_ball_y(&input);
+        let y = predictive_behavior.predict_ball_y(&input, &input.balls[0]);
         // sanity: should be within bounds
         assert!(y >= 0.0 && y <= input.screen_height);
     }
@@ -213,8 +352,82 @@ mod tests {
     fn predictive_handles_vertical_wrap() {
         let predictive_behavior = PredictiveBehavior::new();
         let mut input = base_input();
-        input.ball_velocity = Vec2::new(50.0, 500.0);
-        let y = predictive_behavior.predict_ball_y(&input);
+        input.balls[0].velocity = Vec2::new(50.0, 500.0);
+        let y = predictive_behavior.predict_ball_y(&input, &input.balls[0]);
         assert!(y >= 0.0 && y <= input.screen_height);
     }
+
+    #[test]
+    fn tracks_the_ball_that_arrives_first() {
+        let mut input = base_input();
+        // Closer, but slow: 400 px at 100 px/s
+        input.balls[0] = BallView {
+            position: Vec2::new(200.0, 100.0),
+            velocity: Vec2::new(100.0, 0.0),
+        };
+        // Further away, but there in one second
+        let fast = BallView {
+            position: Vec2::new(0.0, 300.0),
+            velocity: Vec2::new(600.0, 0.0),
+        };
+        // Leaving the racket behind
+        let leaving = BallView {
+            position: Vec2::new(590.0, 200.0),
+            velocity: Vec2::new(-900.0, 0.0),
+        };
+        input.balls.extend([fast, leaving]);
+        assert_eq!(input.time_to_arrival(&leaving), None);
+        assert_eq!(input.most_threatening_ball(), Some(&fast));
+
+        input.balls = vec![leaving];
+        assert_eq!(input.most_threatening_ball(), None);
+    }
+
+    #[test]
+    fn aggressive_meets_the_ball_off_center_towards_the_open_field() {
+        // A ball met in the top half is sent down, one met in the bottom half up
+        for (height, downwards) in [(0.3, true), (0.7, false)] {
+            let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::Human, PlayerType::AIAggressive, 5, Some(2));
+            simulation.reset();
+            let keys = HashSet::new();
+            while !simulation.step(1.0 / 60.0, &keys).iter().any(|event| matches!(event, GameEvent::Serve { .. })) {}
+            let ball = &mut simulation.balls[0];
+            ball.position = Vec2::new(400.0, 720.0 * height);
+            ball.velocity = Vec2::new(ball.velocity.length(), 0.0);
+
+            let offset = (0..600)
+                .flat_map(|_| simulation.step(1.0 / 60.0, &keys))
+                .find_map(|event| match event {
+                    GameEvent::BallHitRacket {
+                        player: Player::Right, offset, ..
+                    } => Some(offset),
+                    _ => None,
+                })
+                .expect("the aggressive AI returns the ball");
+            // Off the racket's edge rather than its middle, leaving towards the other half
+            assert!(offset.abs() > 0.3, "offset {}", offset);
+            assert_eq!(simulation.balls[0].velocity.y > 0.0, downwards);
+        }
+        let input = base_input();
+        let predicted = PredictiveBehavior::new().predict_ball_y(&input, &input.balls[0]);
+        assert_eq!(DefensiveBehavior::new().choose_target(&input, &input.balls[0]), predicted);
+    }
+
+    #[test]
+    fn trickster_feints_then_changes_its_return() {
+        let mut trickster = TricksterBehavior::new();
+        let mut input = base_input();
+        // 2.5 seconds away: waits on the mirrored side
+        let predicted = PredictiveBehavior::new().predict_ball_y(&input, &input.balls[0]);
+        assert_eq!(trickster.choose_target(&input, &input.balls[0]), input.screen_height - predicted);
+
+        // Close: goes for the ball, off a part of the racket that changes with every new ball
+        input.balls[0].position.x = 550.0;
+        let first = trickster.choose_target(&input, &input.balls[0]);
+        input.balls[0].position.x = 100.0;
+        trickster.choose_target(&input, &input.balls[0]);
+        input.balls[0].position.x = 550.0;
+        let second = trickster.choose_target(&input, &input.balls[0]);
+        assert_ne!(first, second);
+    }
 }
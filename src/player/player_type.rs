@@ -8,24 +8,49 @@ pub enum PlayerType {
     AIEasy,
     AIMedium,
     AIHard,
+    // AI personalities, unlocked in the campaign
+    AIDefensive,
+    AIAggressive,
+    AITrickster,
 }
 
 impl PlayerType {
+    pub const ALL: [PlayerType; 7] = [
+        PlayerType::Human,
+        PlayerType::AIEasy,
+        PlayerType::AIMedium,
+        PlayerType::AIHard,
+        PlayerType::AIDefensive,
+        PlayerType::AIAggressive,
+        PlayerType::AITrickster,
+    ];
+
     pub fn next(&self) -> Self {
         match self {
             PlayerType::Human => PlayerType::AIEasy,
             PlayerType::AIEasy => PlayerType::AIMedium,
             PlayerType::AIMedium => PlayerType::AIHard,
-            PlayerType::AIHard => PlayerType::Human,
+            PlayerType::AIHard => PlayerType::AIDefensive,
+            PlayerType::AIDefensive => PlayerType::AIAggressive,
+            PlayerType::AIAggressive => PlayerType::AITrickster,
+            PlayerType::AITrickster => PlayerType::Human,
         }
     }
 
+    // Whether this is one of the campaign's AI personalities.
+    pub fn is_personality(&self) -> bool {
+        matches!(self, PlayerType::AIDefensive | PlayerType::AIAggressive | PlayerType::AITrickster)
+    }
+
     pub fn previous(&self) -> Self {
         match self {
-            PlayerType::Human => PlayerType::AIHard,
+            PlayerType::Human => PlayerType::AITrickster,
             PlayerType::AIEasy => PlayerType::Human,
             PlayerType::AIMedium => PlayerType::AIEasy,
             PlayerType::AIHard => PlayerType::AIMedium,
+            PlayerType::AIDefensive => PlayerType::AIHard,
+            PlayerType::AIAggressive => PlayerType::AIDefensive,
+            PlayerType::AITrickster => PlayerType::AIAggressive,
         }
     }
 
@@ -35,25 +60,59 @@ impl PlayerType {
             PlayerType::AIEasy => "AI - Easy",
             PlayerType::AIMedium => "AI - Medium",
             PlayerType::AIHard => "AI - Hard",
+            PlayerType::AIDefensive => "AI - Defensive",
+            PlayerType::AIAggressive => "AI - Aggressive",
+            PlayerType::AITrickster => "AI - Trickster",
+        }
+    }
+
+    // Name used on the command line (e.g. `--left ai-hard`).
+    pub fn cli_name(&self) -> &'static str {
+        match self {
+            PlayerType::Human => "human",
+            PlayerType::AIEasy => "ai-easy",
+            PlayerType::AIMedium => "ai-medium",
+            PlayerType::AIHard => "ai-hard",
+            PlayerType::AIDefensive => "ai-defensive",
+            PlayerType::AIAggressive => "ai-aggressive",
+            PlayerType::AITrickster => "ai-trickster",
         }
     }
 
+    pub fn from_cli_name(name: &str) -> Option<Self> {
+        PlayerType::ALL
+            .into_iter()
+            .find(|player_type| player_type.cli_name() == name.to_ascii_lowercase())
+    }
+
     pub fn create_controller_for_player(&self, player: Player) -> Box<dyn Controller> {
         match self {
             PlayerType::Human => {
-                if player == Player::Left {
-                    Box::new(HumanController::new(KeyCode::W, KeyCode::S))
-                } else {
-                    Box::new(HumanController::new(KeyCode::Up, KeyCode::Down))
-                }
+                let (up_key, down_key) = movement_keys(player);
+                Box::new(HumanController::new(up_key, down_key))
             }
             PlayerType::AIEasy => Box::new(AIController::easy()),
             PlayerType::AIMedium => Box::new(AIController::medium()),
             PlayerType::AIHard => Box::new(AIController::hard()),
+            PlayerType::AIDefensive => Box::new(AIController::defensive()),
+            PlayerType::AIAggressive => Box::new(AIController::aggressive()),
+            PlayerType::AITrickster => Box::new(AIController::trickster()),
         }
     }
 }
 
+// Movement keys of a human player: towards the start of their wall (up, or left), then towards its end.
+pub fn movement_keys(player: Player) -> (KeyCode, KeyCode) {
+    match player {
+        Player::Left => (KeyCode::W, KeyCode::S),
+        Player::Right => (KeyCode::Up, KeyCode::Down),
+        Player::Top => (KeyCode::J, KeyCode::L),
+        Player::Bottom => (KeyCode::Numpad4, KeyCode::Numpad6),
+        Player::LeftFront => (KeyCode::T, KeyCode::G),
+        Player::RightFront => (KeyCode::Numpad8, KeyCode::Numpad5),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::PlayerType;
@@ -63,14 +122,25 @@ mod tests {
         assert_eq!(PlayerType::Human.next(), PlayerType::AIEasy);
         assert_eq!(PlayerType::AIEasy.next(), PlayerType::AIMedium);
         assert_eq!(PlayerType::AIMedium.next(), PlayerType::AIHard);
-        assert_eq!(PlayerType::AIHard.next(), PlayerType::Human);
+        assert_eq!(PlayerType::AIHard.next(), PlayerType::AIDefensive);
+        assert_eq!(PlayerType::AITrickster.next(), PlayerType::Human);
     }
 
     #[test]
     fn previous_cycles_in_order() {
-        assert_eq!(PlayerType::Human.previous(), PlayerType::AIHard);
+        assert_eq!(PlayerType::Human.previous(), PlayerType::AITrickster);
+        assert_eq!(PlayerType::AIDefensive.previous(), PlayerType::AIHard);
         assert_eq!(PlayerType::AIEasy.previous(), PlayerType::Human);
         assert_eq!(PlayerType::AIMedium.previous(), PlayerType::AIEasy);
         assert_eq!(PlayerType::AIHard.previous(), PlayerType::AIMedium);
     }
+
+    #[test]
+    fn cli_names_round_trip() {
+        for player_type in PlayerType::ALL {
+            assert_eq!(PlayerType::from_cli_name(player_type.cli_name()), Some(player_type));
+        }
+        assert_eq!(PlayerType::from_cli_name("AI-Hard"), Some(PlayerType::AIHard));
+        assert_eq!(PlayerType::from_cli_name("robot"), None);
+    }
 }
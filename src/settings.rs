@@ -0,0 +1,308 @@
+// Player settings, changed on the settings screen and saved between sessions.
+// Command-line options override them for the current session.
+
+use crate::audio::SoundPack;
+use crate::audio::mixer::{Channel, Mixer};
+use crate::cli::CliOptions;
+use crate::effects::DEFAULT_INTENSITY;
+use crate::game::arena::DEFAULT_ARENA;
+use crate::game::multiball::MultiBall;
+use crate::game::powerup::PowerUpRules;
+use crate::game::serve::ServeRule;
+use crate::game::simulation::Simulation;
+use crate::storage::{self, Migration, StorageError};
+use crate::ui::crt::CrtEffects;
+use crate::ui::theme::DEFAULT_THEME;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const SETTINGS_FILE: &str = "settings.json";
+
+// Schema history of the settings file (see `storage`); the first format is v0.
+const MIGRATIONS: &[Migration] = &[];
+
+// Rows of the settings screen, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsItem {
+    Theme,
+    Scanlines,
+    Bloom,
+    Curvature,
+    Aberration,
+    BallTrail,
+    EffectsIntensity,
+    ServeRule,
+    HumanServe,
+    Balls,
+    BallCollisions,
+    BallSpawns,
+    PowerUps,
+    PowerUpRate,
+    SoundPack,
+    AssetPack,
+    MasterVolume,
+    SfxVolume,
+    MusicVolume,
+    Mute,
+}
+
+impl SettingsItem {
+    pub const ALL: [SettingsItem; 20] = [
+        SettingsItem::Theme,
+        SettingsItem::Scanlines,
+        SettingsItem::Bloom,
+        SettingsItem::Curvature,
+        SettingsItem::Aberration,
+        SettingsItem::BallTrail,
+        SettingsItem::EffectsIntensity,
+        SettingsItem::ServeRule,
+        SettingsItem::HumanServe,
+        SettingsItem::Balls,
+        SettingsItem::BallCollisions,
+        SettingsItem::BallSpawns,
+        SettingsItem::PowerUps,
+        SettingsItem::PowerUpRate,
+        SettingsItem::SoundPack,
+        SettingsItem::AssetPack,
+        SettingsItem::MasterVolume,
+        SettingsItem::SfxVolume,
+        SettingsItem::MusicVolume,
+        SettingsItem::Mute,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SettingsItem::Theme => "Theme",
+            SettingsItem::Scanlines => "CRT scanlines",
+            SettingsItem::Bloom => "Phosphor bloom",
+            SettingsItem::Curvature => "Screen curvature",
+            SettingsItem::Aberration => "Chromatic aberration",
+            SettingsItem::BallTrail => "Ball trail",
+            SettingsItem::EffectsIntensity => "Effects intensity",
+            SettingsItem::ServeRule => "Serve",
+            SettingsItem::HumanServe => "Humans serve themselves",
+            SettingsItem::Balls => "Balls per serve",
+            SettingsItem::BallCollisions => "Balls collide",
+            SettingsItem::BallSpawns => "Extra balls mid-rally",
+            SettingsItem::PowerUps => "Power-ups",
+            SettingsItem::PowerUpRate => "Power-up spawn rate",
+            SettingsItem::SoundPack => "Sound effects",
+            SettingsItem::AssetPack => "Asset pack",
+            SettingsItem::MasterVolume => "Master volume",
+            SettingsItem::SfxVolume => "Effects volume",
+            SettingsItem::MusicVolume => "Music volume",
+            SettingsItem::Mute => "Mute",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct SettingsDocument {
+    theme: String,
+    crt: CrtEffects,
+    effects_intensity: f32,
+    serve_rule: ServeRule,
+    human_serve: bool,
+    multi_ball: MultiBall,
+    power_ups: PowerUpRules,
+    arena: String,
+    sound_pack: SoundPack,
+    asset_pack: Option<String>,
+    master_volume: f32,
+    sfx_volume: f32,
+    music_volume: f32,
+    muted: bool,
+}
+
+impl Default for SettingsDocument {
+    fn default() -> Self {
+        Settings::default().document()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    // `None` when the settings could not be loaded: changes then only last for the session
+    path: Option<PathBuf>,
+    // Id of a built-in theme or name of a theme file
+    pub theme: String,
+    pub crt: CrtEffects,
+    // Particles, screen shake and racket squash, from 0 (off) to 1
+    pub effects_intensity: f32,
+    pub serve_rule: ServeRule,
+    pub human_serve: bool,
+    pub multi_ball: MultiBall,
+    pub power_ups: PowerUpRules,
+    // Id of a built-in arena or name of a level file, picked in the menu
+    pub arena: String,
+    pub sound_pack: SoundPack,
+    // Id of the asset pack in use, if any
+    pub asset_pack: Option<String>,
+    pub mixer: Mixer,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            path: None,
+            theme: DEFAULT_THEME.to_string(),
+            crt: CrtEffects::default(),
+            effects_intensity: DEFAULT_INTENSITY,
+            serve_rule: ServeRule::default(),
+            human_serve: false,
+            multi_ball: MultiBall::default(),
+            power_ups: PowerUpRules::default(),
+            arena: DEFAULT_ARENA.to_string(),
+            sound_pack: SoundPack::Samples,
+            asset_pack: None,
+            mixer: Mixer::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(path: &Path) -> Result<Self, StorageError> {
+        let document: SettingsDocument = storage::load_document(path, MIGRATIONS)?.unwrap_or_default();
+        let mut mixer = Mixer::new();
+        mixer.set_level(Channel::Master, document.master_volume);
+        mixer.set_level(Channel::Sfx, document.sfx_volume);
+        mixer.set_level(Channel::Music, document.music_volume);
+        mixer.set_muted(document.muted);
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            theme: document.theme,
+            crt: document.crt,
+            effects_intensity: document.effects_intensity.clamp(0.0, 1.0),
+            serve_rule: document.serve_rule,
+            human_serve: document.human_serve,
+            multi_ball: document.multi_ball,
+            power_ups: document.power_ups,
+            arena: document.arena,
+            sound_pack: document.sound_pack,
+            asset_pack: document.asset_pack,
+            mixer,
+        })
+    }
+
+    // Settings that are never written to disk.
+    pub fn detached() -> Self {
+        Self::default()
+    }
+
+    pub fn save(&self) -> Result<(), StorageError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        storage::save_document(path, MIGRATIONS, &self.document())
+    }
+
+    pub fn apply_cli(&mut self, options: &CliOptions) {
+        for (channel, level) in [
+            (Channel::Master, options.master_volume),
+            (Channel::Sfx, options.sfx_volume),
+            (Channel::Music, options.music_volume),
+        ] {
+            if let Some(level) = level {
+                self.mixer.set_level(channel, level);
+            }
+        }
+        if options.mute {
+            self.mixer.set_muted(true);
+        }
+        if let Some(pack) = options.sound_pack {
+            self.sound_pack = pack;
+        }
+        if let Some(rule) = options.serve_rule {
+            self.serve_rule = rule;
+        }
+        if options.human_serve {
+            self.human_serve = true;
+        }
+        options.apply_multi_ball(&mut self.multi_ball);
+        options.apply_power_ups(&mut self.power_ups);
+        if let Some(arena) = &options.arena {
+            self.arena = arena.clone();
+        }
+    }
+
+    // Give `simulation` the match rules picked here.
+    pub fn apply_rules(&self, simulation: &mut Simulation) {
+        simulation.serve_rule = self.serve_rule;
+        simulation.human_serve = self.human_serve;
+        simulation.multi_ball = self.multi_ball;
+        simulation.power_up_rules = self.power_ups;
+    }
+
+    fn document(&self) -> SettingsDocument {
+        SettingsDocument {
+            theme: self.theme.clone(),
+            crt: self.crt,
+            effects_intensity: self.effects_intensity,
+            serve_rule: self.serve_rule,
+            human_serve: self.human_serve,
+            multi_ball: self.multi_ball,
+            power_ups: self.power_ups,
+            arena: self.arena.clone(),
+            sound_pack: self.sound_pack,
+            asset_pack: self.asset_pack.clone(),
+            master_volume: self.mixer.level(Channel::Master),
+            sfx_volume: self.mixer.level(Channel::Sfx),
+            music_volume: self.mixer.level(Channel::Music),
+            muted: self.mixer.is_muted(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::powerup::SpawnRate;
+
+    fn temp_path(test: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("rust-pong-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        directory.join(SETTINGS_FILE)
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        let path = temp_path("settings");
+        let mut settings = Settings::load(&path).unwrap();
+        assert_eq!(settings.mixer, Mixer::new());
+
+        settings.theme = "neon".to_string();
+        settings.crt.bloom = true;
+        settings.effects_intensity = 0.5;
+        settings.serve_rule = ServeRule::Alternate;
+        settings.multi_ball.balls = 3;
+        settings.multi_ball.spawns = true;
+        settings.power_ups.enabled = true;
+        settings.power_ups.rate = SpawnRate::Rare;
+        settings.arena = "bumpers".to_string();
+        settings.sound_pack = SoundPack::Synth;
+        settings.asset_pack = Some("retro.zip".to_string());
+        settings.mixer.set_level(Channel::Music, 0.2);
+        settings.mixer.set_muted(true);
+        settings.save().unwrap();
+
+        assert_eq!(Settings::load(&path).unwrap(), settings);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn command_line_overrides_saved_settings() {
+        let mut settings = Settings::detached();
+        let options = CliOptions {
+            sfx_volume: Some(0.5),
+            mute: true,
+            sound_pack: Some(SoundPack::Synth),
+            ..CliOptions::default()
+        };
+        settings.apply_cli(&options);
+        assert_eq!(settings.mixer.level(Channel::Sfx), 0.5);
+        assert_eq!(settings.mixer.level(Channel::Master), 1.0);
+        assert!(settings.mixer.is_muted());
+        assert_eq!(settings.sound_pack, SoundPack::Synth);
+    }
+}
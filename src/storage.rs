@@ -0,0 +1,148 @@
+// Versioned JSON documents saved in the user data directory.
+//
+// Every document carries a top-level "version" field. When a document written by an older build is loaded,
+// the migrations registered for it upgrade the raw JSON one version at a time before it is deserialized.
+// Documents written by a newer build are rejected rather than silently downgraded.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+// Upgrades a raw document from version N to version N + 1 (N being its index in the migration list).
+pub type Migration = fn(Value) -> Result<Value, StorageError>;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    UnsupportedVersion { found: u64, supported: u64 },
+    Invalid(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io(error) => write!(f, "I/O error: {}", error),
+            StorageError::Json(error) => write!(f, "malformed data: {}", error),
+            StorageError::UnsupportedVersion { found, supported } => {
+                write!(
+                    f,
+                    "saved with a newer version of the game (format v{}, this build supports up to v{})",
+                    found, supported
+                )
+            }
+            StorageError::Invalid(message) => write!(f, "invalid data: {}", message),
+        }
+    }
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(error: std::io::Error) -> Self {
+        StorageError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(error: serde_json::Error) -> Self {
+        StorageError::Json(error)
+    }
+}
+
+// Version of a raw document; documents without a "version" field predate versioning and count as v0.
+fn document_version(document: &Value) -> u64 {
+    document.get("version").and_then(Value::as_u64).unwrap_or(0)
+}
+
+// Bring a raw document up to the latest version (`migrations.len()`).
+pub fn migrate(mut document: Value, migrations: &[Migration]) -> Result<Value, StorageError> {
+    let supported = migrations.len() as u64;
+    let found = document_version(&document);
+    if found > supported {
+        return Err(StorageError::UnsupportedVersion { found, supported });
+    }
+
+    for migration in &migrations[found as usize..] {
+        document = migration(document)?;
+    }
+    Ok(document)
+}
+
+// Load a document, or `None` if the file does not exist yet.
+pub fn load_document<T: DeserializeOwned>(path: &Path, migrations: &[Migration]) -> Result<Option<T>, StorageError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    let document = migrate(serde_json::from_str(&contents)?, migrations)?;
+    Ok(Some(serde_json::from_value(document)?))
+}
+
+// Load what is saved at `path` with `load`. If that fails, say why and go on with `fallback`, `label` naming what
+// was lost in the message.
+pub fn load_or<T>(path: &Path, label: &str, load: impl FnOnce(&Path) -> Result<T, StorageError>, fallback: impl FnOnce() -> T) -> T {
+    load(path).unwrap_or_else(|error| {
+        eprintln!("Could not load {} from {}: {}. Starting without it this session.", label, path.display(), error);
+        fallback()
+    })
+}
+
+// Save a document tagged with the latest version, writing to a temporary file first so a crash cannot truncate it.
+pub fn save_document<T: Serialize>(path: &Path, migrations: &[Migration], data: &T) -> Result<(), StorageError> {
+    let mut document = serde_json::to_value(data)?;
+    match document.as_object_mut() {
+        Some(object) => object.insert("version".to_string(), Value::from(migrations.len() as u64)),
+        None => return Err(StorageError::Invalid("only objects can be saved as documents".to_string())),
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temporary_path = path.with_extension("tmp");
+    fs::write(&temporary_path, serde_json::to_string_pretty(&document)?)?;
+    fs::rename(&temporary_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn add_field(mut document: Value) -> Result<Value, StorageError> {
+        document["added"] = json!(true);
+        document["version"] = json!(1);
+        Ok(document)
+    }
+
+    fn rename_field(mut document: Value) -> Result<Value, StorageError> {
+        let added = document["added"].take();
+        document["renamed"] = added;
+        document["version"] = json!(2);
+        Ok(document)
+    }
+
+    #[test]
+    fn migrations_run_from_the_stored_version() {
+        let migrations: [Migration; 2] = [add_field, rename_field];
+        assert_eq!(migrate(json!({}), &migrations).unwrap()["renamed"], json!(true));
+        assert_eq!(migrate(json!({"version": 1, "added": false}), &migrations).unwrap()["renamed"], json!(false));
+        assert_eq!(migrate(json!({"version": 2, "renamed": 3}), &migrations).unwrap()["renamed"], json!(3));
+    }
+
+    #[test]
+    fn newer_documents_are_rejected() {
+        let migrations: [Migration; 1] = [add_field];
+        let error = migrate(json!({"version": 5}), &migrations).unwrap_err();
+        assert!(matches!(error, StorageError::UnsupportedVersion { found: 5, supported: 1 }));
+    }
+    #[test]
+    fn failed_loads_fall_back() {
+        let path = Path::new("unused.json");
+        assert_eq!(load_or(path, "numbers", |_| Ok(1), || 0), 1);
+        assert_eq!(load_or(path, "numbers", |_| Err(StorageError::Invalid("bad".to_string())), || 0), 0);
+    }
+}
@@ -0,0 +1,326 @@
+// Single- and double-elimination brackets.
+//
+// A bracket is a flat list of matches in the order they are played. Each side of a match is an entrant, a bye, or
+// the winner or loser of an earlier match, so results flow through the bracket without anyone being moved. Entrants
+// are seeded in the order they were entered, and when the field is not a power of two the top seeds get the byes.
+// In double elimination the losers of the winners' bracket drop into a losers' bracket, whose last survivor meets
+// the winners' champion in the grand final; if the survivor wins it, a reset match decides the title.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Format {
+    Single,
+    Double,
+}
+
+impl Format {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Single => "Single elimination",
+            Format::Double => "Double elimination",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Format::Single => Format::Double,
+            Format::Double => Format::Single,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stage {
+    Winners,
+    Losers,
+    GrandFinal,
+    // Only played when the losers' bracket survivor wins the grand final
+    Reset,
+}
+
+// Where one side of a match comes from; `Winner` and `Loser` hold the index of an earlier match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Slot {
+    Entrant(usize),
+    Bye,
+    Winner(usize),
+    Loser(usize),
+}
+
+// Who fills a side of a match once it is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seat {
+    Entrant(usize),
+    Bye,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BracketMatch {
+    pub stage: Stage,
+    // From 1, within the stage
+    pub round: usize,
+    pub slots: [Slot; 2],
+    // Side (0 or 1) that won, once the match is decided
+    pub winner: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bracket {
+    matches: Vec<BracketMatch>,
+}
+
+impl Bracket {
+    // A bracket for `entrants` players, at least two.
+    pub fn new(format: Format, entrants: usize) -> Self {
+        let size = entrants.next_power_of_two().max(2);
+        let rounds = size.trailing_zeros() as usize;
+        let mut bracket = Self { matches: Vec::new() };
+
+        let seat = |seed: usize| if seed < entrants { Slot::Entrant(seed) } else { Slot::Bye };
+        let seeds = seeding(size);
+        let mut previous: Vec<usize> = seeds
+            .chunks(2)
+            .map(|pair| bracket.push(Stage::Winners, 1, [seat(pair[0]), seat(pair[1])]))
+            .collect();
+        let double = format == Format::Double;
+        let mut lower = Vec::new();
+        let mut lower_round = 1;
+        if double {
+            lower = previous
+                .chunks(2)
+                .map(|pair| bracket.push(Stage::Losers, 1, [Slot::Loser(pair[0]), Slot::Loser(pair[1])]))
+                .collect();
+        }
+        for round in 2..=rounds {
+            let current: Vec<usize> = previous
+                .chunks(2)
+                .map(|pair| bracket.push(Stage::Winners, round, [Slot::Winner(pair[0]), Slot::Winner(pair[1])]))
+                .collect();
+            if double {
+                // The losers of this round drop in against the survivors, in reverse order to put off rematches
+                lower_round += 1;
+                lower = lower
+                    .iter()
+                    .zip(current.iter().rev())
+                    .map(|(&survivor, &dropped)| bracket.push(Stage::Losers, lower_round, [Slot::Winner(survivor), Slot::Loser(dropped)]))
+                    .collect();
+                if lower.len() > 1 {
+                    lower_round += 1;
+                    lower = lower
+                        .chunks(2)
+                        .map(|pair| bracket.push(Stage::Losers, lower_round, [Slot::Winner(pair[0]), Slot::Winner(pair[1])]))
+                        .collect();
+                }
+            }
+            previous = current;
+        }
+        if double {
+            let grand_final = bracket.push(Stage::GrandFinal, 1, [Slot::Winner(previous[0]), Slot::Winner(lower[0])]);
+            bracket.push(Stage::Reset, 1, [Slot::Winner(grand_final), Slot::Loser(grand_final)]);
+        }
+        bracket.settle();
+        bracket
+    }
+
+    // A saved bracket, checked against the one a new tournament of `entrants` players starts with: only the winners
+    // can differ.
+    pub fn from_matches(format: Format, matches: Vec<BracketMatch>, entrants: usize) -> Result<Self, String> {
+        let fresh = Self::new(format, entrants);
+        if matches.len() != fresh.matches.len() {
+            return Err(format!("the bracket has {} matches instead of {}", matches.len(), fresh.matches.len()));
+        }
+        for (index, (saved, expected)) in matches.iter().zip(&fresh.matches).enumerate() {
+            if saved.stage != expected.stage || saved.round != expected.round || saved.slots != expected.slots {
+                return Err(format!("match {} is not the one the bracket has there", index + 1));
+            }
+            if saved.winner.is_some_and(|side| side > 1) {
+                return Err(format!("match {} has an unknown winner", index + 1));
+            }
+        }
+        Ok(Self { matches })
+    }
+
+    fn push(&mut self, stage: Stage, round: usize, slots: [Slot; 2]) -> usize {
+        self.matches.push(BracketMatch {
+            stage,
+            round,
+            slots,
+            winner: None,
+        });
+        self.matches.len() - 1
+    }
+
+    pub fn matches(&self) -> &[BracketMatch] {
+        &self.matches
+    }
+
+    // Who fills `slot`, if it is known yet.
+    pub fn seat(&self, slot: Slot) -> Option<Seat> {
+        match slot {
+            Slot::Entrant(entrant) => Some(Seat::Entrant(entrant)),
+            Slot::Bye => Some(Seat::Bye),
+            Slot::Winner(index) | Slot::Loser(index) => {
+                let earlier = &self.matches[index];
+                let side = earlier.winner?;
+                let side = if matches!(slot, Slot::Winner(_)) { side } else { 1 - side };
+                self.seat(earlier.slots[side])
+            }
+        }
+    }
+
+    pub fn seats(&self, index: usize) -> [Option<Seat>; 2] {
+        self.matches[index].slots.map(|slot| self.seat(slot))
+    }
+
+    // The next match to play: the first undecided one between two entrants.
+    pub fn next_match(&self) -> Option<usize> {
+        (0..self.matches.len())
+            .find(|&index| self.matches[index].winner.is_none() && self.seats(index).iter().all(|seat| matches!(seat, Some(Seat::Entrant(_)))))
+    }
+
+    // Side `winner` (0 or 1) won the match at `index`.
+    pub fn record(&mut self, index: usize, winner: usize) {
+        self.matches[index].winner = Some(winner);
+        self.settle();
+    }
+
+    // The entrant who won the last match, once the bracket is over.
+    pub fn champion(&self) -> Option<usize> {
+        let last = self.matches.len() - 1;
+        match self.seat(Slot::Winner(last)) {
+            Some(Seat::Entrant(entrant)) => Some(entrant),
+            _ => None,
+        }
+    }
+
+    // Whether the match at `index` is worth showing: not a meeting of two byes, nor a reset that is not needed.
+    pub fn is_shown(&self, index: usize) -> bool {
+        let bracket_match = &self.matches[index];
+        if bracket_match.stage == Stage::Reset {
+            return self.matches[index - 1].winner == Some(1);
+        }
+        self.seats(index) != [Some(Seat::Bye), Some(Seat::Bye)]
+    }
+
+    // Decide the matches that need no playing: byes advance whoever they meet, and the reset is skipped when the
+    // winners' champion takes the grand final.
+    fn settle(&mut self) {
+        loop {
+            let decided = (0..self.matches.len()).find_map(|index| {
+                let bracket_match = &self.matches[index];
+                if bracket_match.winner.is_some() {
+                    return None;
+                }
+                if bracket_match.stage == Stage::Reset {
+                    return (self.matches[index - 1].winner == Some(0)).then_some((index, 0));
+                }
+                match self.seats(index) {
+                    [Some(Seat::Bye), Some(_)] => Some((index, 1)),
+                    [Some(_), Some(Seat::Bye)] => Some((index, 0)),
+                    _ => None,
+                }
+            });
+            let Some((index, winner)) = decided else {
+                return;
+            };
+            self.matches[index].winner = Some(winner);
+        }
+    }
+}
+
+// Seeds (from 0) in bracket order for a bracket of `size`, a power of two: the top seeds are as far apart as can
+// be, and each first-round match adds up to `size - 1`.
+fn seeding(size: usize) -> Vec<usize> {
+    let mut seeds = vec![0];
+    while seeds.len() < size {
+        let next = seeds.len() * 2;
+        seeds = seeds.iter().flat_map(|&seed| [seed, next - 1 - seed]).collect();
+    }
+    seeds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Play the bracket through, the lower entrant number winning every match; returns the matches played.
+    fn play_favorites(bracket: &mut Bracket) -> usize {
+        let mut played = 0;
+        while let Some(index) = bracket.next_match() {
+            let [Some(Seat::Entrant(first)), Some(Seat::Entrant(second))] = bracket.seats(index) else {
+                unreachable!();
+            };
+            bracket.record(index, if first < second { 0 } else { 1 });
+            played += 1;
+        }
+        played
+    }
+
+    #[test]
+    fn top_seeds_get_the_byes() {
+        assert_eq!(seeding(8), [0, 7, 3, 4, 1, 6, 2, 5]);
+        let bracket = Bracket::new(Format::Single, 5);
+        // Seeds 1 to 3 skip the first round; seeds 4 and 5 play it
+        assert_eq!(bracket.next_match(), Some(1));
+        assert_eq!(bracket.seats(1), [Some(Seat::Entrant(3)), Some(Seat::Entrant(4))]);
+        assert_eq!(bracket.seat(Slot::Winner(0)), Some(Seat::Entrant(0)));
+    }
+
+    #[test]
+    fn single_elimination_plays_one_match_less_than_the_entrants() {
+        for entrants in 3..=16 {
+            let mut bracket = Bracket::new(Format::Single, entrants);
+            assert_eq!(play_favorites(&mut bracket), entrants - 1);
+            assert_eq!(bracket.champion(), Some(0));
+        }
+    }
+
+    #[test]
+    fn double_elimination_gives_everyone_two_losses() {
+        for entrants in 3..=16 {
+            let mut bracket = Bracket::new(Format::Double, entrants);
+            // Everyone but the champion loses twice, and the reset is not needed
+            assert_eq!(play_favorites(&mut bracket), 2 * entrants - 2, "{} entrants", entrants);
+            assert_eq!(bracket.champion(), Some(0));
+            assert!(!bracket.is_shown(bracket.matches().len() - 1));
+        }
+    }
+
+    #[test]
+    fn losers_bracket_survivor_can_force_a_reset() {
+        let mut bracket = Bracket::new(Format::Double, 4);
+        let grand_final = bracket.matches().len() - 2;
+        while let Some(index) = bracket.next_match() {
+            // Seed 1 loses its first match, then wins everything; otherwise the better seed wins
+            let [Some(Seat::Entrant(first)), Some(Seat::Entrant(second))] = bracket.seats(index) else {
+                unreachable!();
+            };
+            let upset = index == 0 || second == 0 || (first != 0 && second < first);
+            bracket.record(index, upset as usize);
+            if index == grand_final {
+                assert!(bracket.is_shown(grand_final + 1));
+            }
+        }
+        assert_eq!(bracket.champion(), Some(0));
+        assert_eq!(bracket.matches()[grand_final].winner, Some(1));
+    }
+
+    #[test]
+    fn saved_brackets_are_checked() {
+        let mut bracket = Bracket::new(Format::Double, 6);
+        play_favorites(&mut bracket);
+        assert_eq!(Bracket::from_matches(Format::Double, bracket.matches().to_vec(), 6), Ok(bracket.clone()));
+        assert!(Bracket::from_matches(Format::Double, bracket.matches().to_vec(), 3).is_err());
+        assert!(Bracket::from_matches(Format::Single, bracket.matches().to_vec(), 6).is_err());
+        assert!(Bracket::from_matches(Format::Double, Vec::new(), 6).is_err());
+
+        // A reset that does not follow the grand final
+        let mut matches = bracket.matches().to_vec();
+        matches[0].stage = Stage::Reset;
+        assert!(Bracket::from_matches(Format::Double, matches, 6).is_err());
+        let mut matches = bracket.matches().to_vec();
+        matches[1].slots[0] = Slot::Entrant(5);
+        assert!(Bracket::from_matches(Format::Double, matches, 6).is_err());
+    }
+}
@@ -0,0 +1,367 @@
+// Local tournaments for parties: 3 to 16 entrants, people by name or AIs, play through a single- or
+// double-elimination bracket one match at a time, Player 1's seat going to the higher seed. The tournament in
+// progress is saved after every match so it can be resumed after quitting, until its champion is crowned.
+
+pub mod bracket;
+
+use crate::career::store::MAX_NAME_LENGTH;
+use crate::game::mode::GameMode;
+use crate::game::physics::Player;
+use crate::game::simulation::Simulation;
+use crate::player::player_type::PlayerType;
+use crate::storage::{self, Migration, StorageError};
+use bracket::{Bracket, BracketMatch, Format, Seat};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+pub const TOURNAMENT_FILE: &str = "tournament.json";
+pub const MIN_ENTRANTS: usize = 3;
+pub const MAX_ENTRANTS: usize = 16;
+
+// Schema history of the tournament file (see `storage`); the first format is v0.
+const MIGRATIONS: &[Migration] = &[];
+
+// Frames after which a played-out AI match is given to whoever leads, at 60 frames a second.
+const PLAY_OUT_MAX_FRAMES: u32 = 60 * 60 * 30;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entrant {
+    pub name: String,
+    pub player_type: PlayerType,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EntrantRecord {
+    name: String,
+    // The player type's `cli_name`
+    player_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TournamentDocument {
+    format: Format,
+    winning_score: u8,
+    entrants: Vec<EntrantRecord>,
+    bracket: Vec<BracketMatch>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tournament {
+    pub format: Format,
+    pub winning_score: u8,
+    // In seeding order
+    pub entrants: Vec<Entrant>,
+    pub bracket: Bracket,
+}
+
+impl Tournament {
+    // A new tournament, or what is wrong with its entrants.
+    pub fn new(entrants: Vec<Entrant>, format: Format, winning_score: u8) -> Result<Self, String> {
+        if !(MIN_ENTRANTS..=MAX_ENTRANTS).contains(&entrants.len()) {
+            return Err(format!("A tournament takes {} to {} entrants", MIN_ENTRANTS, MAX_ENTRANTS));
+        }
+        let mut names = HashSet::new();
+        for entrant in &entrants {
+            check_name(&entrant.name)?;
+            if !names.insert(entrant.name.to_lowercase()) {
+                return Err(format!("{} is entered twice", entrant.name));
+            }
+        }
+        Ok(Self {
+            format,
+            winning_score,
+            bracket: Bracket::new(format, entrants.len()),
+            entrants,
+        })
+    }
+
+    // The saved tournament, or `None` if there is none.
+    pub fn load(path: &Path) -> Result<Option<Self>, StorageError> {
+        let Some(document): Option<TournamentDocument> = storage::load_document(path, MIGRATIONS)? else {
+            return Ok(None);
+        };
+        let entrants = document
+            .entrants
+            .into_iter()
+            .map(|record| match PlayerType::from_cli_name(&record.player_type) {
+                Some(player_type) => Ok(Entrant {
+                    name: record.name,
+                    player_type,
+                }),
+                None => Err(StorageError::Invalid(format!("unknown player type '{}'", record.player_type))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut tournament = Self::new(entrants, document.format, document.winning_score).map_err(StorageError::Invalid)?;
+        tournament.bracket = Bracket::from_matches(document.format, document.bracket, tournament.entrants.len()).map_err(StorageError::Invalid)?;
+        Ok(Some(tournament))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+        let document = TournamentDocument {
+            format: self.format,
+            winning_score: self.winning_score,
+            entrants: self
+                .entrants
+                .iter()
+                .map(|entrant| EntrantRecord {
+                    name: entrant.name.clone(),
+                    player_type: entrant.player_type.cli_name().to_string(),
+                })
+                .collect(),
+            bracket: self.bracket.matches().to_vec(),
+        };
+        storage::save_document(path, MIGRATIONS, &document)
+    }
+
+    // Forget the saved tournament, if there is one.
+    pub fn remove(path: &Path) -> Result<(), StorageError> {
+        match fs::remove_file(path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+
+    // Who fills a seat of the bracket, as shown to players.
+    pub fn seat_name(&self, seat: Option<Seat>) -> &str {
+        match seat {
+            Some(Seat::Entrant(entrant)) => &self.entrants[entrant].name,
+            Some(Seat::Bye) => "(bye)",
+            None => "...",
+        }
+    }
+
+    // The next match to play and its two entrants, Player 1's first.
+    pub fn next_match(&self) -> Option<(usize, [&Entrant; 2])> {
+        let index = self.bracket.next_match()?;
+        let [Some(Seat::Entrant(left)), Some(Seat::Entrant(right))] = self.bracket.seats(index) else {
+            return None;
+        };
+        Some((index, [&self.entrants[left], &self.entrants[right]]))
+    }
+
+    // `winner` won the match at `index`, Player 1 being its first side.
+    pub fn record(&mut self, index: usize, winner: Player) {
+        self.bracket.record(index, if winner == Player::Left { 0 } else { 1 });
+    }
+
+    pub fn champion(&self) -> Option<&Entrant> {
+        self.bracket.champion().map(|entrant| &self.entrants[entrant])
+    }
+
+    // Set up `simulation` for the match at `index`. It still needs a reset.
+    pub fn prepare(&self, index: usize, simulation: &mut Simulation) {
+        let [left, right] = self.bracket.seats(index).map(|seat| match seat {
+            Some(Seat::Entrant(entrant)) => self.entrants[entrant].player_type,
+            _ => PlayerType::AIMedium,
+        });
+        simulation.set_player_types(left, right);
+        simulation.set_mode(GameMode::Classic, PlayerType::AIMedium, PlayerType::AIMedium);
+        simulation.winning_score = self.winning_score;
+    }
+}
+
+// A name fit for an entrant.
+fn check_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!("Names must be 1 to {} characters", MAX_NAME_LENGTH));
+    }
+    Ok(())
+}
+
+// Play a prepared match between two AIs to the end, without a window. Returns the winner.
+pub fn play_out(simulation: &mut Simulation) -> Player {
+    simulation.reset();
+    let pressed_keys = HashSet::new();
+    for _ in 0..PLAY_OUT_MAX_FRAMES {
+        simulation.step(1.0 / 60.0, &pressed_keys);
+        if let Some(winner) = simulation.winner() {
+            return winner;
+        }
+    }
+    if simulation.score.get(Player::Right) > simulation.score.get(Player::Left) {
+        Player::Right
+    } else {
+        Player::Left
+    }
+}
+
+// Abandoning a tournament takes two presses of X in a row: the first only asks.
+#[derive(Debug, Default)]
+pub struct AbandonPrompt {
+    asked: bool,
+}
+
+impl AbandonPrompt {
+    // Whether this press abandons the tournament.
+    pub fn confirm(&mut self) -> bool {
+        let confirmed = self.asked;
+        self.asked = true;
+        confirmed
+    }
+
+    // Anything else the player does in between asks again.
+    pub fn dismiss(&mut self) {
+        self.asked = false;
+    }
+}
+
+// The entrants being put together before a tournament starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentSetup {
+    pub entrants: Vec<Entrant>,
+    pub format: Format,
+    // Name being typed for the next entrant
+    pub name: String,
+    pub player_type: PlayerType,
+}
+
+impl Default for TournamentSetup {
+    fn default() -> Self {
+        Self {
+            entrants: Vec::new(),
+            format: Format::Single,
+            name: String::new(),
+            player_type: PlayerType::Human,
+        }
+    }
+}
+
+impl TournamentSetup {
+    // Enter the next entrant. AIs left unnamed are called after their type, numbered when there are several.
+    pub fn add(&mut self) -> Result<(), String> {
+        if self.entrants.len() >= MAX_ENTRANTS {
+            return Err(format!("A tournament takes at most {} entrants", MAX_ENTRANTS));
+        }
+        let mut name = self.name.trim().to_string();
+        if name.is_empty() && self.player_type != PlayerType::Human {
+            let base = self.player_type.display_name().trim_start_matches("AI - ").to_string() + " AI";
+            name = base.clone();
+            let mut number = 1;
+            while self.is_taken(&name) {
+                number += 1;
+                name = format!("{} {}", base, number);
+            }
+        }
+        check_name(&name)?;
+        if self.is_taken(&name) {
+            return Err(format!("{} is already entered", name));
+        }
+        self.entrants.push(Entrant {
+            name,
+            player_type: self.player_type,
+        });
+        self.name.clear();
+        Ok(())
+    }
+
+    fn is_taken(&self, name: &str) -> bool {
+        self.entrants.iter().any(|entrant| entrant.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn start(&self, winning_score: u8) -> Result<Tournament, String> {
+        Tournament::new(self.entrants.clone(), self.format, winning_score)
+    }
+
+    // The entrants and format of `tournament`, ready to play it again.
+    pub fn rematch(tournament: &Tournament) -> Self {
+        Self {
+            entrants: tournament.entrants.clone(),
+            format: tournament.format,
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(player_types: &[PlayerType]) -> TournamentSetup {
+        let mut setup = TournamentSetup::default();
+        for (number, &player_type) in player_types.iter().enumerate() {
+            setup.player_type = player_type;
+            if player_type == PlayerType::Human {
+                setup.name = format!("Player {}", number + 1);
+            }
+            setup.add().unwrap();
+        }
+        setup
+    }
+
+    #[test]
+    fn setup_names_ais_and_rejects_bad_fields() {
+        let mut setup = setup(&[PlayerType::AIHard, PlayerType::AIHard]);
+        assert_eq!(setup.entrants[1].name, "Hard AI 2");
+        setup.player_type = PlayerType::Human;
+        assert!(setup.add().is_err());
+        setup.name = "  Ada ".to_string();
+        setup.add().unwrap();
+        setup.name = "ADA".to_string();
+        assert!(setup.add().is_err());
+        assert_eq!(setup.entrants[2].name, "Ada");
+
+        assert!(setup.start(5).is_ok());
+        setup.entrants.pop();
+        assert!(setup.start(5).is_err());
+        while setup.entrants.len() < MAX_ENTRANTS {
+            setup.player_type = PlayerType::AIEasy;
+            setup.add().unwrap();
+        }
+        assert!(setup.add().is_err());
+    }
+
+    #[test]
+    fn ai_matches_play_out_to_a_champion() {
+        let setup = setup(&[PlayerType::AIEasy, PlayerType::AIMedium, PlayerType::AIHard]);
+        let mut tournament = setup.start(1).unwrap();
+        while let Some((index, _)) = tournament.next_match() {
+            let mut simulation = Simulation::new(1280.0, 720.0, PlayerType::Human, PlayerType::Human, 5, Some(index as u64));
+            tournament.prepare(index, &mut simulation);
+            assert_eq!(simulation.winning_score, 1);
+            let winner = play_out(&mut simulation);
+            tournament.record(index, winner);
+        }
+        assert!(tournament.champion().is_some());
+    }
+
+    #[test]
+    fn abandoning_takes_two_presses_in_a_row() {
+        let mut prompt = AbandonPrompt::default();
+        assert!(!prompt.confirm());
+        assert!(prompt.confirm());
+
+        prompt.dismiss();
+        assert!(!prompt.confirm());
+        prompt.dismiss();
+        assert!(!prompt.confirm());
+        assert!(prompt.confirm());
+    }
+
+    #[test]
+    fn tournaments_resume_after_a_save_and_load() {
+        let directory = std::env::temp_dir().join(format!("rust-pong-tournament-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let path = directory.join(TOURNAMENT_FILE);
+        assert_eq!(Tournament::load(&path).unwrap(), None);
+
+        let mut setup = setup(&[PlayerType::Human, PlayerType::AITrickster, PlayerType::AIEasy, PlayerType::AIMedium]);
+        setup.format = Format::Double;
+        let mut tournament = setup.start(7).unwrap();
+        let (index, _) = tournament.next_match().unwrap();
+        tournament.record(index, Player::Right);
+        tournament.save(&path).unwrap();
+        assert_eq!(Tournament::load(&path).unwrap(), Some(tournament));
+
+        // An edited bracket is turned down rather than played
+        let mut document: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        document["bracket"][0]["stage"] = "Reset".into();
+        fs::write(&path, document.to_string()).unwrap();
+        assert!(matches!(Tournament::load(&path), Err(StorageError::Invalid(_))));
+
+        Tournament::remove(&path).unwrap();
+        Tournament::remove(&path).unwrap();
+        assert_eq!(Tournament::load(&path).unwrap(), None);
+    }
+}
@@ -0,0 +1,58 @@
+use ggez::graphics::{Canvas, DrawParam, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::achievements::{ACHIEVEMENTS, AchievementTracker};
+use crate::ui::theme::Theme;
+
+pub fn draw_achievements(context: &mut Context, canvas: &mut Canvas, theme: &Theme, tracker: &AchievementTracker) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+
+    // Title
+    let mut title = Text::new(format!("ACHIEVEMENTS  {}/{}", tracker.unlocked_count(), ACHIEVEMENTS.len()));
+    title.set_scale(screen_height / 12.0);
+    let title_dimensions = title.measure(context)?;
+    let title_position = Vec2::new((screen_width - title_dimensions.x) / 2.0, screen_height * 0.08);
+    canvas.draw(&title, DrawParam::default().dest(title_position).color(theme.text));
+
+    let top = screen_height * 0.25;
+    let line_height = screen_height / 14.0;
+    for (index, achievement) in ACHIEVEMENTS.iter().enumerate() {
+        let progress = tracker.progress(achievement);
+        let unlocked = progress.unlocked_at.is_some();
+        let y = top + line_height * index as f32;
+
+        let (marker, name_color) = if unlocked { ("[x]", theme.accent) } else { ("[ ]", theme.text_muted) };
+        let mut name = Text::new(format!("{} {}", marker, achievement.name));
+        name.set_scale(screen_height / 28.0);
+        canvas.draw(&name, DrawParam::default().dest(Vec2::new(screen_width * 0.15, y)).color(name_color));
+
+        let mut description = Text::new(achievement.description);
+        description.set_scale(screen_height / 38.0);
+        canvas.draw(
+            &description,
+            DrawParam::default()
+                .dest(Vec2::new(screen_width * 0.15, y + screen_height / 26.0))
+                .color(theme.text_muted),
+        );
+
+        let status = if unlocked {
+            "Unlocked".to_string()
+        } else if achievement.goal > 1 {
+            format!("{}/{}", progress.progress, achievement.goal)
+        } else {
+            "Locked".to_string()
+        };
+        let mut status_text = Text::new(status);
+        status_text.set_scale(screen_height / 32.0);
+        canvas.draw(&status_text, DrawParam::default().dest(Vec2::new(screen_width * 0.72, y)).color(name_color));
+    }
+
+    // Back hint
+    let mut hint = Text::new("SPACE/ENTER/Esc: Back to menu");
+    hint.set_scale(screen_height / 30.0);
+    let hint_dimensions = hint.measure(context)?;
+    let hint_position = Vec2::new((screen_width - hint_dimensions.x) / 2.0, screen_height * 0.9);
+    canvas.draw(&hint, DrawParam::default().dest(hint_position).color(theme.text_secondary));
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Mesh, MeshBuilder, Rect};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::game::arena::Body;
+use crate::game::bricks::Brick;
+use crate::ui::theme::Theme;
+
+// Straight segments per radian of a curved wall
+const CURVE_SEGMENTS_PER_RADIAN: f32 = 12.0;
+const PORTAL_RING_WIDTH: f32 = 3.0;
+// Bricks are a little dimmer than the rackets in front of them
+const BRICK_ALPHA: f32 = 0.75;
+
+// The arena's obstacles: blocks and curved walls in the racket color, bumpers in the accent color, and portals as
+// an accent ring linked to a faint ring where balls come out.
+pub fn draw_obstacles(context: &mut Context, canvas: &mut Canvas, theme: &Theme, bodies: &[Body]) -> GameResult {
+    for body in bodies {
+        match *body {
+            Body::Block { center, half_size } => {
+                let rect = Rect::new(center.x - half_size.x, center.y - half_size.y, half_size.x * 2.0, half_size.y * 2.0);
+                let mesh = Mesh::new_rectangle(context, DrawMode::fill(), rect, theme.racket)?;
+                canvas.draw(&mesh, DrawParam::default());
+            }
+            Body::Bumper { center, radius } => {
+                let mesh = Mesh::new_circle(context, DrawMode::fill(), center, radius, 0.5, theme.accent)?;
+                canvas.draw(&mesh, DrawParam::default());
+            }
+            Body::Portal { center, radius, exit } => {
+                let entry = Mesh::new_circle(context, DrawMode::stroke(PORTAL_RING_WIDTH), center, radius, 0.5, theme.accent)?;
+                canvas.draw(&entry, DrawParam::default());
+                let exit = Mesh::new_circle(context, DrawMode::stroke(PORTAL_RING_WIDTH), exit, radius, 0.5, theme.text_muted)?;
+                canvas.draw(&exit, DrawParam::default());
+            }
+            Body::Curve {
+                center,
+                radius,
+                from,
+                to,
+                half_thickness,
+            } => {
+                let segments = ((to - from) * CURVE_SEGMENTS_PER_RADIAN).ceil().max(1.0) as usize;
+                let points: Vec<Vec2> = (0..=segments)
+                    .map(|step| center + Vec2::from_angle(from + (to - from) * step as f32 / segments as f32) * radius)
+                    .collect();
+                let mesh = Mesh::new_line(context, &points, half_thickness * 2.0, theme.racket)?;
+                canvas.draw(&mesh, DrawParam::default());
+            }
+        }
+    }
+    Ok(())
+}
+
+// Pong-Out's brick walls, in the racket color.
+pub fn draw_bricks(context: &mut Context, canvas: &mut Canvas, theme: &Theme, bricks: &[Brick]) -> GameResult {
+    if bricks.is_empty() {
+        return Ok(());
+    }
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let mut builder = MeshBuilder::new();
+    for brick in bricks {
+        let (center, half_size) = brick.rect(screen_width, screen_height);
+        let rect = Rect::new(center.x - half_size.x, center.y - half_size.y, half_size.x * 2.0, half_size.y * 2.0);
+        builder.rectangle(
+            DrawMode::fill(),
+            rect,
+            Color {
+                a: BRICK_ALPHA,
+                ..theme.racket
+            },
+        )?;
+    }
+    let mesh = Mesh::from_data(context, builder.build());
+    canvas.draw(&mesh, DrawParam::default());
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use ggez::graphics::{Canvas, DrawParam, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::campaign::{Campaign, LADDER};
+use crate::ui::theme::Theme;
+
+// The campaign ladder from the bottom: each opponent with its rules, and whether it is beaten, next or locked.
+pub fn draw_campaign(context: &mut Context, canvas: &mut Canvas, theme: &Theme, campaign: &Campaign, selected: usize) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+
+    // Title
+    let mut title = Text::new(format!("CAMPAIGN  {}/{}", campaign.cleared(), LADDER.len()));
+    title.set_scale(screen_height / 12.0);
+    let title_dimensions = title.measure(context)?;
+    let title_position = Vec2::new((screen_width - title_dimensions.x) / 2.0, screen_height * 0.08);
+    canvas.draw(&title, DrawParam::default().dest(title_position).color(theme.text));
+
+    let top = screen_height * 0.25;
+    let line_height = screen_height / 8.5;
+    for (index, rung) in LADDER.iter().enumerate() {
+        let open = campaign.is_open(index);
+        let y = top + line_height * index as f32;
+
+        let (status, color) = if index < campaign.cleared() {
+            ("Beaten", theme.accent)
+        } else if open {
+            ("Next", theme.text)
+        } else {
+            ("Locked", theme.text_muted)
+        };
+        let marker = if index == selected { ">" } else { " " };
+        let mut name = Text::new(format!("{} {}. {}  ({})", marker, index + 1, rung.name, rung.opponent.display_name()));
+        name.set_scale(screen_height / 28.0);
+        let name_color = if index == selected { theme.header } else { color };
+        canvas.draw(&name, DrawParam::default().dest(Vec2::new(screen_width * 0.12, y)).color(name_color));
+
+        // What awaits on a rung stays hidden until it is open
+        let details = if open {
+            format!("{}  {}", rung.rules(), rung.description)
+        } else {
+            "???".to_string()
+        };
+        let mut description = Text::new(details);
+        description.set_scale(screen_height / 38.0);
+        canvas.draw(
+            &description,
+            DrawParam::default()
+                .dest(Vec2::new(screen_width * 0.12, y + screen_height / 26.0))
+                .color(theme.text_muted),
+        );
+
+        let mut status_text = Text::new(status);
+        status_text.set_scale(screen_height / 32.0);
+        canvas.draw(&status_text, DrawParam::default().dest(Vec2::new(screen_width * 0.8, y)).color(color));
+    }
+
+    // Hints
+    let mut hint = Text::new("↑ ↓: Select  |  SPACE/ENTER: Play  |  Esc: Back to menu");
+    hint.set_scale(screen_height / 30.0);
+    let hint_dimensions = hint.measure(context)?;
+    let hint_position = Vec2::new((screen_width - hint_dimensions.x) / 2.0, screen_height * 0.9);
+    canvas.draw(&hint, DrawParam::default().dest(hint_position).color(theme.text_secondary));
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use ggez::graphics::{Canvas, DrawMode, DrawParam, Mesh, Rect, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::game::challenge::{self, ChallengeResult, ChallengeRun, WALL_PRACTICE_TARGET};
+use crate::game::mode::GameMode;
+use crate::game::stats::MatchStats;
+use crate::ui::theme::Theme;
+
+// How the run is going, at the top of the field: the time survived and the pace, the clock of a time attack, or
+// the returns towards the practice target.
+pub fn draw_challenge_status(context: &mut Context, canvas: &mut Canvas, theme: &Theme, run: &ChallengeRun) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let status = match run.mode() {
+        GameMode::Survival => format!("{}   (pace x{:.2})", challenge::format_score(run.mode(), run.score()), run.pace()),
+        GameMode::TimeAttack => {
+            let seconds = run.time_left().unwrap_or_default().ceil() as u32;
+            format!("{}:{:02} left", seconds / 60, seconds % 60)
+        }
+        _ => format!("{} / {} returns", run.returns(), WALL_PRACTICE_TARGET),
+    };
+    let mut text = Text::new(status);
+    text.set_scale(screen_height / 20.0);
+    let dimensions = text.measure(context)?;
+    canvas.draw(
+        &text,
+        DrawParam::default()
+            .dest(Vec2::new((screen_width - dimensions.x) / 2.0, screen_height * 0.04))
+            .color(theme.text),
+    );
+    Ok(())
+}
+
+// The end of a challenge: whether it was won, the score and, when it made the table, its place among the high scores.
+pub fn draw_challenge_over(
+    context: &mut Context,
+    canvas: &mut Canvas,
+    theme: &Theme,
+    result: &ChallengeResult,
+    place: Option<usize>,
+    stats: &MatchStats,
+) -> GameResult {
+    // Semi-transparent overlay
+    let overlay_rect = Rect::new(0.0, 0.0, context.gfx.drawable_size().0, context.gfx.drawable_size().1);
+    let overlay_mesh = Mesh::new_rectangle(context, DrawMode::fill(), overlay_rect, theme.overlay)?;
+    canvas.draw(&overlay_mesh, DrawParam::default());
+
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+
+    let title = if result.won { "Challenge Complete!" } else { "Game Over" };
+    super::common::draw_centered_title(context, canvas, title, theme.text)?;
+
+    let mut line = format!("Score: {}", challenge::format_score(result.mode, result.score));
+    if let Some(place) = place {
+        line.push_str(&format!("   -   New high score, #{}!", place + 1));
+    }
+    let mut score_text = Text::new(line);
+    score_text.set_scale(screen_height / 24.0);
+    let score_dimensions = score_text.measure(context)?;
+    let color = if place.is_some() { theme.accent } else { theme.text_secondary };
+    canvas.draw(
+        &score_text,
+        DrawParam::default()
+            .dest(Vec2::new((screen_width - score_dimensions.x) / 2.0, screen_height * 0.45))
+            .color(color),
+    );
+
+    super::stats_panel::draw_stats_panel(context, canvas, theme, stats, result.mode.players(), screen_height * 0.53)?;
+
+    let mut continue_text = Text::new("R: Retry  |  H: High Scores  |  Esc: Menu");
+    continue_text.set_scale(screen_height / 30.0);
+    let continue_dimensions = continue_text.measure(context)?;
+    let continue_position = Vec2::new((screen_width - continue_dimensions.x) / 2.0, screen_height * 0.9);
+    canvas.draw(&continue_text, DrawParam::default().dest(continue_position).color(theme.text_secondary));
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use ggez::graphics::{Canvas, Color, DrawParam, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::game::countdown::Countdown;
+use crate::game::physics::Player;
+use crate::game::serve;
+use crate::ui::theme::Theme;
+
+// The current countdown label, large in the middle of the field. Each label shrinks and fades as its step ends.
+pub fn draw_countdown(context: &mut Context, canvas: &mut Canvas, theme: &Theme, countdown: &Countdown) -> GameResult {
+    let Some(label) = countdown.label() else {
+        return Ok(());
+    };
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let progress = countdown.step_progress();
+
+    let mut text = Text::new(label);
+    text.set_scale(screen_height / 4.0 * (1.2 - 0.4 * progress));
+    let dimensions = text.measure(context)?;
+    let position = Vec2::new((screen_width - dimensions.x) / 2.0, (screen_height - dimensions.y) / 2.0);
+    let color = if label == "GO" { theme.accent } else { theme.text };
+    canvas.draw(
+        &text,
+        DrawParam::default().dest(position).color(Color {
+            a: color.a * (1.0 - 0.7 * progress),
+            ..color
+        }),
+    );
+    Ok(())
+}
+
+// Reminder for a human holding the ball, on the server's side of the field.
+pub fn draw_serve_prompt(context: &mut Context, canvas: &mut Canvas, theme: &Theme, server: Player) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let mut text = Text::new(serve::serve_prompt(server));
+    text.set_scale(screen_height / 30.0);
+    let dimensions = text.measure(context)?;
+    let (center_x, top) = match server {
+        Player::Left => (screen_width / 4.0, screen_height * 0.7),
+        Player::Right => (screen_width * 3.0 / 4.0, screen_height * 0.7),
+        Player::Top => (screen_width / 2.0, screen_height * 0.25),
+        Player::Bottom => (screen_width / 2.0, screen_height * 0.75),
+        Player::LeftFront => (screen_width / 4.0, screen_height * 0.25),
+        Player::RightFront => (screen_width * 3.0 / 4.0, screen_height * 0.25),
+    };
+    let position = Vec2::new(center_x - dimensions.x / 2.0, top);
+    canvas.draw(&text, DrawParam::default().dest(position).color(theme.accent));
+    Ok(())
+}
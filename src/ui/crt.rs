@@ -0,0 +1,253 @@
+// CRT post-processing.
+//
+// When a shader effect is on, the frame is drawn to an off-screen image first and copied to the window through
+// `crt.wgsl` (scanlines, phosphor bloom, screen curvature, chromatic aberration). The ball trail (see `effects`) is
+// drawn with the rest of the field. With every effect off, or if the shader cannot be built, frames go straight to the
+// window as before.
+//
+// How strong each effect is belongs to the theme (`CrtParams`); which effects are on is a setting (`CrtEffects`).
+
+use crevice::std140::AsStd140;
+use ggez::graphics::{Canvas, Color, DrawParam, Image, ScreenImage, Shader, ShaderBuilder, ShaderParams, ShaderParamsBuilder};
+use ggez::{Context, GameResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const SHADER: &str = include_str!("crt.wgsl");
+
+// Effect strengths, part of the theme. Zero turns an effect off whatever the settings say.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrtParams {
+    // How dark every other line gets, 0 to 1
+    pub scanlines: f32,
+    // Glow added around bright pixels, 0 to 2
+    pub bloom: f32,
+    // Barrel distortion of the screen, 0 to 0.5
+    pub curvature: f32,
+    // Red/blue fringe width in pixels, 0 to 8
+    pub aberration: f32,
+    // Brightness each trail ghost keeps from the previous one, 0 to 1
+    pub persistence: f32,
+}
+
+impl Default for CrtParams {
+    fn default() -> Self {
+        Self {
+            scanlines: 0.35,
+            bloom: 0.6,
+            curvature: 0.12,
+            aberration: 1.5,
+            persistence: 0.6,
+        }
+    }
+}
+
+impl CrtParams {
+    // Keys of a theme file's `crt` object, with the largest value each accepts.
+    pub const KEYS: [(&'static str, f32); 5] = [
+        ("scanlines", 1.0),
+        ("bloom", 2.0),
+        ("curvature", 0.5),
+        ("aberration", 8.0),
+        ("persistence", 1.0),
+    ];
+
+    pub fn value_mut(&mut self, key: &str) -> Option<&mut f32> {
+        let value = match key {
+            "scanlines" => &mut self.scanlines,
+            "bloom" => &mut self.bloom,
+            "curvature" => &mut self.curvature,
+            "aberration" => &mut self.aberration,
+            "persistence" => &mut self.persistence,
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+// Check the `crt` object of a theme file and apply it to `params`.
+pub fn parse_params(values: &BTreeMap<String, f32>, params: &mut CrtParams) -> Result<(), String> {
+    for (key, value) in values {
+        let Some(&(_, max)) = CrtParams::KEYS.iter().find(|(name, _)| name == key) else {
+            let keys: Vec<&str> = CrtParams::KEYS.iter().map(|(name, _)| *name).collect();
+            return Err(format!("unknown CRT parameter '{}' (expected one of: {})", key, keys.join(", ")));
+        };
+        if !(0.0..=max).contains(value) {
+            return Err(format!("CRT parameter '{}' must be between 0 and {}, got {}", key, max, value));
+        }
+        if let Some(slot) = params.value_mut(key) {
+            *slot = *value;
+        }
+    }
+    Ok(())
+}
+
+// Effects the player turned on. All are off by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrtEffects {
+    pub scanlines: bool,
+    pub bloom: bool,
+    pub curvature: bool,
+    pub aberration: bool,
+    pub trail: bool,
+}
+
+impl CrtEffects {
+    // Whether the frame has to go through the shader (the trail does not need it).
+    pub fn needs_shader(&self) -> bool {
+        self.scanlines || self.bloom || self.curvature || self.aberration
+    }
+}
+
+// Mirrors `CrtUniforms` in crt.wgsl.
+#[derive(AsStd140)]
+pub struct CrtUniforms {
+    width: f32,
+    height: f32,
+    scanlines: f32,
+    bloom: f32,
+    curvature: f32,
+    aberration: f32,
+}
+
+// Shader inputs for a `width` x `height` frame, with the effects that are off zeroed.
+pub fn uniforms(params: &CrtParams, effects: CrtEffects, width: f32, height: f32) -> CrtUniforms {
+    let enabled = |on: bool, value: f32| if on { value } else { 0.0 };
+    CrtUniforms {
+        width,
+        height,
+        scanlines: enabled(effects.scanlines, params.scanlines),
+        bloom: enabled(effects.bloom, params.bloom),
+        curvature: enabled(effects.curvature, params.curvature),
+        aberration: enabled(effects.aberration, params.aberration),
+    }
+}
+
+// The shader and the off-screen image the frame is drawn to before going through it.
+struct ShaderPass {
+    shader: Shader,
+    params: ShaderParams<CrtUniforms>,
+    target: ScreenImage,
+}
+
+enum ShaderState {
+    // Built on first use, so the shader never touches the GPU while every effect is off
+    NotBuilt,
+    Ready(Box<ShaderPass>),
+    Failed,
+}
+
+pub struct PostProcess {
+    shader: ShaderState,
+}
+
+impl PostProcess {
+    pub fn new() -> Self {
+        Self { shader: ShaderState::NotBuilt }
+    }
+
+    // Build the shader if needed. Returns an error message the first time building it fails.
+    pub fn prepare(&mut self, context: &mut Context, effects: CrtEffects) -> Option<String> {
+        if !effects.needs_shader() || !matches!(self.shader, ShaderState::NotBuilt) {
+            return None;
+        }
+        match ShaderBuilder::new().fragment_code(SHADER).build(&context.gfx) {
+            Ok(shader) => {
+                let (width, height) = context.gfx.drawable_size();
+                let params = ShaderParamsBuilder::new(&uniforms(&CrtParams::default(), effects, width, height)).build(context);
+                let target = ScreenImage::new(context, None, 1.0, 1.0, 1);
+                self.shader = ShaderState::Ready(Box::new(ShaderPass { shader, params, target }));
+                None
+            }
+            Err(error) => {
+                self.shader = ShaderState::Failed;
+                Some(error.to_string())
+            }
+        }
+    }
+
+    // Canvas the frame is drawn on: the off-screen image when the shader is in use, the window otherwise.
+    pub fn begin(&mut self, context: &mut Context, effects: CrtEffects, clear: Color) -> Canvas {
+        match &mut self.shader {
+            ShaderState::Ready(pass) if effects.needs_shader() => Canvas::from_screen_image(context, &mut pass.target, clear),
+            _ => Canvas::from_frame(context, clear),
+        }
+    }
+
+    // Finish the frame drawn on the canvas returned by `begin`, copying it to the window through the shader if needed.
+    pub fn finish(&mut self, context: &mut Context, canvas: Canvas, effects: CrtEffects, crt: &CrtParams) -> GameResult {
+        canvas.finish(context)?;
+        let ShaderState::Ready(pass) = &mut self.shader else {
+            return Ok(());
+        };
+        if !effects.needs_shader() {
+            return Ok(());
+        }
+
+        let image: Image = pass.target.image(context);
+        let (width, height) = context.gfx.drawable_size();
+        pass.params.set_uniforms(context, &uniforms(crt, effects, width, height));
+        let mut screen = Canvas::from_frame(context, Color::BLACK);
+        screen.set_shader(&pass.shader);
+        screen.set_shader_params(&pass.params);
+        screen.draw(&image, DrawParam::default());
+        screen.finish(context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_effects_are_zeroed() {
+        let params = CrtParams::default();
+        let effects = CrtEffects {
+            scanlines: true,
+            aberration: true,
+            ..CrtEffects::default()
+        };
+        let shader_input = uniforms(&params, effects, 1280.0, 720.0);
+        assert_eq!(shader_input.scanlines, params.scanlines);
+        assert_eq!(shader_input.aberration, params.aberration);
+        assert_eq!(shader_input.bloom, 0.0);
+        assert_eq!(shader_input.curvature, 0.0);
+        assert!(effects.needs_shader());
+        assert!(
+            !CrtEffects {
+                trail: true,
+                ..CrtEffects::default()
+            }
+            .needs_shader()
+        );
+    }
+
+    #[test]
+    fn params_are_checked() {
+        let mut params = CrtParams::default();
+        let values = |pairs: &[(&str, f32)]| pairs.iter().map(|(key, value)| (key.to_string(), *value)).collect();
+        parse_params(&values(&[("bloom", 1.5), ("curvature", 0.0)]), &mut params).unwrap();
+        assert_eq!(params.bloom, 1.5);
+        assert_eq!(params.curvature, 0.0);
+
+        assert!(
+            parse_params(&values(&[("glow", 1.0)]), &mut params)
+                .unwrap_err()
+                .contains("unknown CRT parameter 'glow'")
+        );
+        assert!(
+            parse_params(&values(&[("scanlines", 2.0)]), &mut params)
+                .unwrap_err()
+                .contains("between 0 and 1")
+        );
+    }
+
+    #[test]
+    fn shader_is_valid_wgsl() {
+        let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+        naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::empty())
+            .validate(&module)
+            .unwrap();
+    }
+}
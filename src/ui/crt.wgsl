@@ -0,0 +1,63 @@
+// CRT post-processing pass, applied to the whole frame (see crt.rs).
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+struct CrtUniforms {
+    width: f32,
+    height: f32,
+    scanlines: f32,
+    bloom: f32,
+    curvature: f32,
+    aberration: f32,
+}
+
+@group(1) @binding(0)
+var t: texture_2d<f32>;
+
+@group(1) @binding(1)
+var s: sampler;
+
+@group(3) @binding(0)
+var<uniform> crt: CrtUniforms;
+
+// Barrel distortion: points move away from the center the further they are from it.
+fn curve(uv: vec2<f32>) -> vec2<f32> {
+    let centered = uv * 2.0 - 1.0;
+    let bent = centered * (1.0 + centered.yx * centered.yx * crt.curvature);
+    return bent * 0.5 + 0.5;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let uv = curve(in.uv);
+    let texel = vec2<f32>(1.0 / crt.width, 1.0 / crt.height);
+
+    // Chromatic aberration: red and blue are sampled a little to each side
+    let shift = vec2<f32>(crt.aberration * texel.x, 0.0);
+    var color = vec3<f32>(
+        textureSample(t, s, uv + shift).r,
+        textureSample(t, s, uv).g,
+        textureSample(t, s, uv - shift).b,
+    );
+
+    // Phosphor bloom: average of two rings of samples around the pixel
+    var glow = vec3<f32>(0.0);
+    for (var i = 0; i < 8; i = i + 1) {
+        let angle = f32(i) * 0.7853982;
+        let offset = vec2<f32>(cos(angle), sin(angle)) * texel * 3.0;
+        glow = glow + textureSample(t, s, uv + offset).rgb + textureSample(t, s, uv + offset * 2.0).rgb;
+    }
+    color = color + glow / 16.0 * crt.bloom;
+
+    // Scanlines: every other row of the (curved) screen is darker
+    let row = u32(max(uv.y, 0.0) * crt.height) % 2u;
+    color = color * (1.0 - crt.scanlines * f32(row));
+
+    // Black outside the curved screen
+    let inside = step(0.0, uv.x) * step(uv.x, 1.0) * step(0.0, uv.y) * step(uv.y, 1.0);
+    return vec4<f32>(color * inside, 1.0) * in.color;
+}
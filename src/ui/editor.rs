@@ -0,0 +1,137 @@
+use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Mesh, Rect, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::editor::{Editor, GRID_STEP, Tool};
+use crate::game::arena::Body;
+use crate::ui::arena as ui_arena;
+use crate::ui::theme::Theme;
+
+const HANDLE_SIZE: f32 = 10.0;
+const SELECTION_MARGIN: f32 = 6.0;
+
+// The arena being edited on an empty field: the snapping grid, the obstacles where they start with the paths of
+// the moving ones, the selection with its size handle, and the tools and controls.
+pub fn draw_editor(context: &mut Context, canvas: &mut Canvas, theme: &Theme, editor: &Editor) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+
+    if editor.snap {
+        let color = Color { a: 0.15, ..theme.net };
+        let lines = (1.0 / GRID_STEP).round() as usize;
+        for step in 1..lines {
+            let fraction = step as f32 * GRID_STEP;
+            for points in [
+                [Vec2::new(fraction * screen_width, 0.0), Vec2::new(fraction * screen_width, screen_height)],
+                [Vec2::new(0.0, fraction * screen_height), Vec2::new(screen_width, fraction * screen_height)],
+            ] {
+                let line = Mesh::new_line(context, &points, 1.0, color)?;
+                canvas.draw(&line, DrawParam::default());
+            }
+        }
+    }
+
+    let bodies = editor.bodies(screen_width, screen_height);
+    ui_arena::draw_obstacles(context, canvas, theme, &bodies)?;
+
+    // Moving obstacles show where their middle slides to
+    for obstacle in &editor.arena.obstacles {
+        if let Some(motion) = obstacle.motion {
+            let start = center(&obstacle.body(0.0, screen_width, screen_height));
+            let end = start + Vec2::new(motion.dx * screen_width, motion.dy * screen_height);
+            let path = Mesh::new_line(context, &[start, end], 2.0, theme.text_muted)?;
+            canvas.draw(&path, DrawParam::default());
+        }
+    }
+
+    if let Some(index) = editor.selected() {
+        let rect = bounds(&bodies[index]);
+        let outline = Rect::new(
+            rect.x - SELECTION_MARGIN,
+            rect.y - SELECTION_MARGIN,
+            rect.w + SELECTION_MARGIN * 2.0,
+            rect.h + SELECTION_MARGIN * 2.0,
+        );
+        let mesh = Mesh::new_rectangle(context, DrawMode::stroke(1.5), outline, theme.accent)?;
+        canvas.draw(&mesh, DrawParam::default());
+        if let Some(handle) = editor.handle(screen_width, screen_height) {
+            let square = Rect::new(handle.x - HANDLE_SIZE / 2.0, handle.y - HANDLE_SIZE / 2.0, HANDLE_SIZE, HANDLE_SIZE);
+            let mesh = Mesh::new_rectangle(context, DrawMode::fill(), square, theme.accent)?;
+            canvas.draw(&mesh, DrawParam::default());
+        }
+    }
+
+    let scale = screen_height / 36.0;
+    let unsaved = if editor.is_dirty() { " *" } else { "" };
+    let mut title = Text::new(format!("Editing {}  ({}{})", editor.arena.name, editor.file, unsaved));
+    title.set_scale(scale * 1.2);
+    canvas.draw(&title, DrawParam::default().dest(Vec2::new(16.0, 12.0)).color(theme.header));
+
+    let tools: Vec<String> = Tool::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, tool)| {
+            let name = if *tool == editor.tool {
+                format!("[{}]", tool.name())
+            } else {
+                tool.name().to_string()
+            };
+            format!("{}: {}", index + 1, name)
+        })
+        .collect();
+    let grid = if editor.snap { "On" } else { "Off" };
+    let mut toolbar = Text::new(format!("{}   |   G: Grid {}", tools.join("  "), grid));
+    toolbar.set_scale(scale);
+    canvas.draw(
+        &toolbar,
+        DrawParam::default().dest(Vec2::new(16.0, 16.0 + scale * 1.4)).color(theme.text_secondary),
+    );
+
+    if let Some(problem) = editor.arena.problem() {
+        let mut warning = Text::new(format!("Cannot be played: {}", problem));
+        warning.set_scale(scale);
+        canvas.draw(&warning, DrawParam::default().dest(Vec2::new(16.0, 20.0 + scale * 2.6)).color(theme.accent));
+    }
+
+    let hints = [
+        "Click: Place  |  Drag: Move  |  Drag handle or wheel: Resize  |  Right-click or Del: Delete  |  O: Motion",
+        "Ctrl+Z / Ctrl+Y: Undo / Redo  |  Ctrl+S: Save  |  T: Test Play  |  N: New Arena  |  Esc: Menu",
+    ];
+    for (row, hint) in hints.iter().enumerate() {
+        let mut text = Text::new(*hint);
+        text.set_scale(scale * 0.85);
+        let dimensions = text.measure(context)?;
+        let y = screen_height - (hints.len() - row) as f32 * (dimensions.y + 6.0) - 8.0;
+        canvas.draw(
+            &text,
+            DrawParam::default()
+                .dest(Vec2::new((screen_width - dimensions.x) / 2.0, y))
+                .color(theme.text_muted),
+        );
+    }
+    Ok(())
+}
+
+fn center(body: &Body) -> Vec2 {
+    match *body {
+        Body::Block { center, .. } | Body::Bumper { center, .. } | Body::Portal { center, .. } | Body::Curve { center, .. } => center,
+    }
+}
+
+// The rectangle around an obstacle, or around a portal's entry.
+fn bounds(body: &Body) -> Rect {
+    let (low, high) = match *body {
+        Body::Block { center, half_size } => (center - half_size, center + half_size),
+        Body::Bumper { center, radius } | Body::Portal { center, radius, .. } => (center - radius, center + radius),
+        Body::Curve {
+            center,
+            radius,
+            from,
+            to,
+            half_thickness,
+        } => {
+            let points = (0..=16).map(|step| center + Vec2::from_angle(from + (to - from) * step as f32 / 16.0) * radius);
+            let (low, high) = points.fold((Vec2::MAX, Vec2::MIN), |(low, high), point| (low.min(point), high.max(point)));
+            (low - half_thickness, high + half_thickness)
+        }
+    };
+    Rect::new(low.x, low.y, high.x - low.x, high.y - low.y)
+}
@@ -0,0 +1,41 @@
+use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Mesh, Rect};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::effects::juice::Trail;
+use crate::effects::particles::{ParticleKind, ParticleSystem};
+use crate::ui::theme::Theme;
+
+// A 1x1 white square centered on the origin, scaled and tinted for each particle.
+pub fn build_particle_mesh(context: &mut Context) -> GameResult<Mesh> {
+    Mesh::new_rectangle(context, DrawMode::fill(), Rect::new(-0.5, -0.5, 1.0, 1.0), Color::WHITE)
+}
+
+pub fn draw_particles(canvas: &mut Canvas, mesh: &Mesh, theme: &Theme, particles: &ParticleSystem) {
+    for particle in particles.particles() {
+        let remaining = particle.remaining();
+        let color = match particle.kind {
+            ParticleKind::Spark => theme.ball,
+            ParticleKind::Dust => theme.net,
+            ParticleKind::Explosion => theme.accent,
+        };
+        // Particles shrink and fade as they die
+        let size = particle.size * (0.5 + 0.5 * remaining);
+        canvas.draw(
+            mesh,
+            DrawParam::default().dest(particle.position).scale(Vec2::splat(size)).color(Color {
+                a: color.a * remaining,
+                ..color
+            }),
+        );
+    }
+}
+
+// Fading copies of the ball along its recent positions.
+pub fn draw_trail(canvas: &mut Canvas, ball_mesh: &Mesh, trail: &Trail, persistence: f32, intensity: f32) {
+    for (position, alpha) in trail.ghosts(persistence) {
+        canvas.draw(
+            ball_mesh,
+            DrawParam::default().dest(position).color(Color::new(1.0, 1.0, 1.0, alpha * intensity)),
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use ggez::graphics::{Canvas, Color, DrawParam, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::game::challenge;
+use crate::game::mode::GameMode;
+use crate::highscores::HighScores;
+use crate::ui::theme::Theme;
+
+// Left edge of each challenge's table, and of the name and score columns within it, as ratios of the screen width
+const TABLE_LEFT: [f32; 3] = [0.05, 0.37, 0.69];
+const NAME_OFFSET: f32 = 0.03;
+const SCORE_OFFSET: f32 = 0.18;
+
+// The high-score table of every challenge, side by side.
+pub fn draw_high_scores(context: &mut Context, canvas: &mut Canvas, theme: &Theme, high_scores: &HighScores) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+
+    // Title
+    let mut title = Text::new("HIGH SCORES");
+    title.set_scale(screen_height / 10.0);
+    let title_dimensions = title.measure(context)?;
+    let title_position = Vec2::new((screen_width - title_dimensions.x) / 2.0, screen_height * 0.08);
+    canvas.draw(&title, DrawParam::default().dest(title_position).color(theme.text));
+
+    let top = screen_height * 0.25;
+    let line_height = screen_height / 20.0;
+    let text_scale = screen_height / 32.0;
+    let mut draw_cell = |text: &str, x_ratio: f32, row: usize, color: Color| {
+        let mut cell = Text::new(text);
+        cell.set_scale(text_scale);
+        let position = Vec2::new(screen_width * x_ratio, top + line_height * row as f32);
+        canvas.draw(&cell, DrawParam::default().dest(position).color(color));
+    };
+
+    for (mode, left) in GameMode::CHALLENGES.into_iter().zip(TABLE_LEFT) {
+        draw_cell(mode.name(), left, 0, theme.header);
+        let table = high_scores.table(mode);
+        if table.is_empty() {
+            draw_cell("No scores yet", left, 1, theme.text_muted);
+        }
+        for (index, entry) in table.iter().enumerate() {
+            let color = if index == 0 { theme.accent } else { theme.text };
+            draw_cell(&(index + 1).to_string(), left, index + 1, color);
+            draw_cell(&entry.name, left + NAME_OFFSET, index + 1, color);
+            draw_cell(&challenge::format_score(mode, entry.score), left + SCORE_OFFSET, index + 1, color);
+        }
+    }
+
+    // Back hint
+    let mut hint = Text::new("SPACE/ENTER/Esc: Back to menu");
+    hint.set_scale(screen_height / 30.0);
+    let hint_dimensions = hint.measure(context)?;
+    let hint_position = Vec2::new((screen_width - hint_dimensions.x) / 2.0, screen_height * 0.9);
+    canvas.draw(&hint, DrawParam::default().dest(hint_position).color(theme.text_secondary));
+
+    Ok(())
+}
@@ -2,9 +2,10 @@ use ggez::graphics::Canvas;
 use ggez::{Context, GameResult};
 
 use crate::debug::DebugInfo;
+use crate::ui::theme::Theme;
 
-pub fn draw_hud(_context: &mut Context, canvas: &mut Canvas, debug: &DebugInfo) -> GameResult {
+pub fn draw_hud(_context: &mut Context, canvas: &mut Canvas, theme: &Theme, debug: &DebugInfo) -> GameResult {
     // Delegate debug drawing to the DebugInfo helper
-    debug.draw(canvas);
+    debug.draw(canvas, theme.debug);
     Ok(())
 }
@@ -0,0 +1,81 @@
+use ggez::graphics::{Canvas, Color, DrawParam, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::career::profile::Profile;
+use crate::player::player_type::PlayerType;
+use crate::ui::theme::Theme;
+
+// Only the top of the leaderboard fits on screen
+const MAX_ROWS: usize = 10;
+
+// Column positions, as ratios of the screen width
+const COLUMNS: [(&str, f32); 8] = [
+    ("#", 0.08),
+    ("Name", 0.13),
+    ("Rating", 0.33),
+    ("W-L", 0.43),
+    ("vs Easy", 0.52),
+    ("vs Medium", 0.61),
+    ("vs Hard", 0.72),
+    ("Best rally / ball", 0.81),
+];
+
+pub fn draw_leaderboard(context: &mut Context, canvas: &mut Canvas, theme: &Theme, ranked: &[&Profile]) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+
+    // Title
+    let mut title = Text::new("LEADERBOARD");
+    title.set_scale(screen_height / 10.0);
+    let title_dimensions = title.measure(context)?;
+    let title_position = Vec2::new((screen_width - title_dimensions.x) / 2.0, screen_height * 0.08);
+    canvas.draw(&title, DrawParam::default().dest(title_position).color(theme.text));
+
+    let top = screen_height * 0.25;
+    let line_height = screen_height / 20.0;
+    let text_scale = screen_height / 32.0;
+    let mut draw_cell = |text: &str, x_ratio: f32, row: usize, color: Color| {
+        let mut cell = Text::new(text);
+        cell.set_scale(text_scale);
+        let position = Vec2::new(screen_width * x_ratio, top + line_height * row as f32);
+        canvas.draw(&cell, DrawParam::default().dest(position).color(color));
+    };
+
+    for (header, x_ratio) in COLUMNS {
+        draw_cell(header, x_ratio, 0, theme.header);
+    }
+
+    if ranked.is_empty() {
+        draw_cell("No profiles yet: press N in the menu to create one", COLUMNS[1].1, 2, theme.text_muted);
+    }
+
+    for (index, profile) in ranked.iter().take(MAX_ROWS).enumerate() {
+        let total = profile.total();
+        let against = |player_type: PlayerType| {
+            let record = profile.record_against(player_type.cli_name());
+            format!("{}-{}", record.wins, record.losses)
+        };
+        let cells = [
+            (index + 1).to_string(),
+            profile.name.clone(),
+            format!("{:.0}", profile.rating),
+            format!("{}-{}", total.wins, total.losses),
+            against(PlayerType::AIEasy),
+            against(PlayerType::AIMedium),
+            against(PlayerType::AIHard),
+            format!("{} / {:.0}", profile.bests.longest_rally, profile.bests.fastest_ball),
+        ];
+        let color = if index == 0 { theme.accent } else { theme.text };
+        for (cell, (_, x_ratio)) in cells.iter().zip(COLUMNS) {
+            draw_cell(cell, x_ratio, index + 1, color);
+        }
+    }
+
+    // Back hint
+    let mut hint = Text::new("SPACE/ENTER/Esc: Back to menu");
+    hint.set_scale(screen_height / 30.0);
+    let hint_dimensions = hint.measure(context)?;
+    let hint_position = Vec2::new((screen_width - hint_dimensions.x) / 2.0, screen_height * 0.9);
+    canvas.draw(&hint, DrawParam::default().dest(hint_position).color(theme.text_secondary));
+
+    Ok(())
+}
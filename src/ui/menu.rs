@@ -1,7 +1,9 @@
 use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Mesh, Rect, Text};
 use ggez::{Context, GameResult, glam::Vec2};
 
+use crate::game::mode::GameMode;
 use crate::player::player_type::PlayerType;
+use crate::ui::theme::Theme;
 
 // Layout ratios for the menu UI (tweak here to adjust spacing/size)
 const BOX_WIDTH_RATIO: f32 = 0.35;
@@ -9,40 +11,66 @@ const BOX_HEIGHT_RATIO: f32 = 0.25;
 const BOX_Y_RATIO: f32 = 0.4;
 const LEFT_BOX_X_RATIO: f32 = 0.1;
 const RIGHT_BOX_X_RATIO: f32 = 0.55;
+// Four-player modes show four narrower boxes in a row
+const FOUR_BOX_WIDTH_RATIO: f32 = 0.2;
+const FOUR_BOX_SPACING_RATIO: f32 = 0.24;
+const FOUR_BOX_X_RATIO: f32 = 0.04;
+const MODE_Y_RATIO: f32 = 0.32;
 
 // Public API for the menu UI module
 // - MenuLayout: geometry for player boxes
+// - PlayerSlot: what a player box displays
+// - MatchSetup: the game mode and arena shown above the boxes
 // - draw_menu: renders the complete menu screen
-// - hit_test_player: returns 1 or 2 if mouse is inside a player box
+// - hit_test_player: returns the player number (from 1) if mouse is inside a player box
 
 #[derive(Debug, Clone, Copy)]
+pub struct PlayerSlot<'a> {
+    pub player_type: PlayerType,
+    // Career profile shown for human players (`None` plays as a guest)
+    pub profile: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MatchSetup<'a> {
+    pub mode: GameMode,
+    // Name of the selected arena
+    pub arena: &'a str,
+}
+
+#[derive(Debug, Clone)]
 pub struct MenuLayout {
-    pub left_box: Rect,
-    pub right_box: Rect,
+    // One box per player, in player order
+    pub boxes: Vec<Rect>,
 }
 
 impl MenuLayout {
-    pub fn from_screen(screen_width: f32, screen_height: f32) -> Self {
-        let box_width = screen_width * BOX_WIDTH_RATIO;
+    pub fn from_screen(screen_width: f32, screen_height: f32, players: usize) -> Self {
         let box_height = screen_height * BOX_HEIGHT_RATIO;
         let box_y = screen_height * BOX_Y_RATIO;
-        let left_box_x = screen_width * LEFT_BOX_X_RATIO;
-        let right_box_x = screen_width * RIGHT_BOX_X_RATIO;
-
-        Self {
-            left_box: Rect {
-                x: left_box_x,
-                y: box_y,
-                w: box_width,
-                h: box_height,
-            },
-            right_box: Rect {
-                x: right_box_x,
-                y: box_y,
-                w: box_width,
-                h: box_height,
-            },
-        }
+        let boxes = if players > 2 {
+            (0..players)
+                .map(|index| Rect {
+                    x: screen_width * (FOUR_BOX_X_RATIO + FOUR_BOX_SPACING_RATIO * index as f32),
+                    y: box_y,
+                    w: screen_width * FOUR_BOX_WIDTH_RATIO,
+                    h: box_height,
+                })
+                .collect()
+        } else {
+            // Wall practice only has the left box
+            [LEFT_BOX_X_RATIO, RIGHT_BOX_X_RATIO]
+                .into_iter()
+                .take(players)
+                .map(|x_ratio| Rect {
+                    x: screen_width * x_ratio,
+                    y: box_y,
+                    w: screen_width * BOX_WIDTH_RATIO,
+                    h: box_height,
+                })
+                .collect()
+        };
+        Self { boxes }
     }
 
     fn contains(rect: Rect, p: Vec2) -> bool {
@@ -51,91 +79,142 @@ impl MenuLayout {
 }
 
 pub fn hit_test_player_with_layout(layout: &MenuLayout, mouse_position: Vec2) -> Option<u8> {
-    if MenuLayout::contains(layout.left_box, mouse_position) {
-        Some(1)
-    } else if MenuLayout::contains(layout.right_box, mouse_position) {
-        Some(2)
-    } else {
-        None
-    }
+    layout
+        .boxes
+        .iter()
+        .position(|&rect| MenuLayout::contains(rect, mouse_position))
+        .map(|index| index as u8 + 1)
 }
 
-pub fn hit_test_player(context: &Context, mouse_position: Vec2) -> Option<u8> {
+pub fn hit_test_player(context: &Context, mouse_position: Vec2, players: usize) -> Option<u8> {
     let (screen_width, screen_height) = context.gfx.drawable_size();
-    let layout = MenuLayout::from_screen(screen_width, screen_height);
+    let layout = MenuLayout::from_screen(screen_width, screen_height, players);
     hit_test_player_with_layout(&layout, mouse_position)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn hit_test_left_and_right_boxes() {
-        let layout = MenuLayout::from_screen(800.0, 600.0);
-
-        let left_center = Vec2::new(layout.left_box.x + layout.left_box.w / 2.0, layout.left_box.y + layout.left_box.h / 2.0);
-        let right_center = Vec2::new(layout.right_box.x + layout.right_box.w / 2.0, layout.right_box.y + layout.right_box.h / 2.0);
-
-        assert_eq!(hit_test_player_with_layout(&layout, left_center), Some(1));
-        assert_eq!(hit_test_player_with_layout(&layout, right_center), Some(2));
-        assert_eq!(hit_test_player_with_layout(&layout, Vec2::new(0.0, 0.0)), None);
-    }
-}
-
-pub fn draw_menu(context: &mut Context, canvas: &mut Canvas, left_type: PlayerType, right_type: PlayerType, selected_player: u8) -> GameResult {
+pub fn draw_menu(
+    context: &mut Context,
+    canvas: &mut Canvas,
+    theme: &Theme,
+    setup: MatchSetup,
+    slots: &[PlayerSlot],
+    selected_player: u8,
+    name_entry: Option<&str>,
+) -> GameResult {
     let (screen_width, screen_height) = context.gfx.drawable_size();
-    let layout = MenuLayout::from_screen(screen_width, screen_height);
+    let layout = MenuLayout::from_screen(screen_width, screen_height, slots.len());
 
     // Title
     let mut title = Text::new("PONG");
     title.set_scale(screen_height / 6.0);
     let title_dimensions = title.measure(context)?;
     let title_position = Vec2::new((screen_width - title_dimensions.x) / 2.0, screen_height * 0.15);
-    canvas.draw(&title, DrawParam::default().dest(title_position).color(Color::WHITE));
+    canvas.draw(&title, DrawParam::default().dest(title_position).color(theme.text));
 
-    // Player 1 & Player 2 boxes
-    draw_player_box(context, canvas, layout.left_box, "Player 1", left_type, selected_player == 1)?;
-    draw_player_box(context, canvas, layout.right_box, "Player 2", right_type, selected_player == 2)?;
+    // Game mode and arena
+    let mut mode_text = Text::new(format!(
+        "Mode: {}  (G to change)   |   Arena: {}  (R to change)",
+        setup.mode.name(),
+        setup.arena
+    ));
+    mode_text.set_scale(screen_height / 30.0);
+    let mode_dimensions = mode_text.measure(context)?;
+    let mode_position = Vec2::new((screen_width - mode_dimensions.x) / 2.0, screen_height * MODE_Y_RATIO);
+    canvas.draw(&mode_text, DrawParam::default().dest(mode_position).color(theme.text_secondary));
+
+    // One box per player
+    for (index, (slot, rect)) in slots.iter().zip(&layout.boxes).enumerate() {
+        let number = index as u8 + 1;
+        draw_player_box(context, canvas, theme, *rect, &format!("Player {}", number), *slot, selected_player == number)?;
+    }
 
     // Keyboard instructions
     let mut keyboard_instructions = Text::new("← → or TAB: Select Player  |  ↑ ↓: Change Type  |  1-4: Direct Select");
     keyboard_instructions.set_scale(screen_height / 35.0);
     let keyboard_dimensions = keyboard_instructions.measure(context)?;
-    let keyboard_position = Vec2::new((screen_width - keyboard_dimensions.x) / 2.0, screen_height * 0.72);
-    canvas.draw(
-        &keyboard_instructions,
-        DrawParam::default().dest(keyboard_position).color(Color::from_rgb(150, 150, 150)),
-    );
+    let keyboard_position = Vec2::new((screen_width - keyboard_dimensions.x) / 2.0, screen_height * 0.70);
+    canvas.draw(&keyboard_instructions, DrawParam::default().dest(keyboard_position).color(theme.text_muted));
+
+    // Profile instructions
+    let mut profile_instructions =
+        Text::new("[ ]: Profile  |  N: New Profile  |  L: Leaderboard  |  A: Achievements  |  H: High Scores  |  S: Settings  |  E: Editor");
+    profile_instructions.set_scale(screen_height / 35.0);
+    let profile_dimensions = profile_instructions.measure(context)?;
+    let profile_position = Vec2::new((screen_width - profile_dimensions.x) / 2.0, screen_height * 0.75);
+    canvas.draw(&profile_instructions, DrawParam::default().dest(profile_position).color(theme.text_muted));
 
     // Mouse instructions
-    let mut instructions = Text::new("Click on players to change type");
+    let mut instructions = Text::new("Click on players to change type  |  M: Mute  |  -/=: Volume  |  F2: Sound Pack");
     instructions.set_scale(screen_height / 35.0);
     let instructions_dimensions = instructions.measure(context)?;
-    let instructions_position = Vec2::new((screen_width - instructions_dimensions.x) / 2.0, screen_height * 0.78);
-    canvas.draw(
-        &instructions,
-        DrawParam::default().dest(instructions_position).color(Color::from_rgb(150, 150, 150)),
-    );
+    let instructions_position = Vec2::new((screen_width - instructions_dimensions.x) / 2.0, screen_height * 0.80);
+    canvas.draw(&instructions, DrawParam::default().dest(instructions_position).color(theme.text_muted));
 
     // Start/quit instruction
-    let mut start_text = Text::new("SPACE/ENTER: Start   |   Esc: Quit");
+    let mut start_text = Text::new("SPACE/ENTER: Start   |   C: Campaign   |   T: Tournament   |   Esc: Quit");
     start_text.set_scale(screen_height / 28.0);
     let start_dimensions = start_text.measure(context)?;
-    let start_position = Vec2::new((screen_width - start_dimensions.x) / 2.0, screen_height * 0.85);
-    canvas.draw(&start_text, DrawParam::default().dest(start_position).color(Color::from_rgb(200, 200, 200)));
+    let start_position = Vec2::new((screen_width - start_dimensions.x) / 2.0, screen_height * 0.86);
+    canvas.draw(&start_text, DrawParam::default().dest(start_position).color(theme.text_secondary));
+
+    if let Some(name) = name_entry {
+        draw_name_entry(context, canvas, theme, name)?;
+    }
 
     Ok(())
 }
 
-fn draw_player_box(context: &mut Context, canvas: &mut Canvas, rect: Rect, player_name: &str, player_type: PlayerType, is_selected: bool) -> GameResult {
+fn draw_name_entry(context: &mut Context, canvas: &mut Canvas, theme: &Theme, name: &str) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let rect = Rect::new(screen_width * 0.25, screen_height * 0.4, screen_width * 0.5, screen_height * 0.25);
+
+    let box_mesh = Mesh::new_rectangle(context, DrawMode::fill(), rect, ui_color_box_bg(theme, true))?;
+    canvas.draw(&box_mesh, DrawParam::default());
+    let border_mesh = Mesh::new_rectangle(context, DrawMode::stroke(5.0), rect, ui_color_box_border(theme, true))?;
+    canvas.draw(&border_mesh, DrawParam::default());
+
+    let mut prompt = Text::new("New profile name");
+    prompt.set_scale(screen_height / 25.0);
+    let prompt_dimensions = prompt.measure(context)?;
+    let prompt_position = Vec2::new(rect.x + (rect.w - prompt_dimensions.x) / 2.0, rect.y + rect.h * 0.15);
+    canvas.draw(&prompt, DrawParam::default().dest(prompt_position).color(theme.text));
+
+    let mut name_text = Text::new(format!("{}_", name));
+    name_text.set_scale(screen_height / 20.0);
+    let name_dimensions = name_text.measure(context)?;
+    let name_position = Vec2::new(rect.x + (rect.w - name_dimensions.x) / 2.0, rect.y + rect.h * 0.4);
+    canvas.draw(
+        &name_text,
+        DrawParam::default().dest(name_position).color(ui_color_player_type(theme, PlayerType::Human)),
+    );
+
+    let mut hint = Text::new("ENTER: Confirm   |   Esc: Cancel");
+    hint.set_scale(screen_height / 35.0);
+    let hint_dimensions = hint.measure(context)?;
+    let hint_position = Vec2::new(rect.x + (rect.w - hint_dimensions.x) / 2.0, rect.y + rect.h * 0.75);
+    canvas.draw(&hint, DrawParam::default().dest(hint_position).color(theme.text_muted));
+
+    Ok(())
+}
+
+fn draw_player_box(
+    context: &mut Context,
+    canvas: &mut Canvas,
+    theme: &Theme,
+    rect: Rect,
+    player_name: &str,
+    slot: PlayerSlot,
+    is_selected: bool,
+) -> GameResult {
+    let player_type = slot.player_type;
+
     // Background
-    let box_mesh = Mesh::new_rectangle(context, DrawMode::fill(), rect, ui_color_box_bg(is_selected))?;
+    let box_mesh = Mesh::new_rectangle(context, DrawMode::fill(), rect, ui_color_box_bg(theme, is_selected))?;
     canvas.draw(&box_mesh, DrawParam::default());
 
     // Border
     let border_width = if is_selected { 5.0 } else { 3.0 };
-    let border_mesh = Mesh::new_rectangle(context, DrawMode::stroke(border_width), rect, ui_color_box_border(is_selected))?;
+    let border_mesh = Mesh::new_rectangle(context, DrawMode::stroke(border_width), rect, ui_color_box_border(theme, is_selected))?;
     canvas.draw(&border_mesh, DrawParam::default());
 
     let (_, screen_height) = context.gfx.drawable_size();
@@ -144,41 +223,77 @@ fn draw_player_box(context: &mut Context, canvas: &mut Canvas, rect: Rect, playe
     let mut name_text = Text::new(player_name);
     name_text.set_scale(screen_height / 25.0);
     let name_dimensions = name_text.measure(context)?;
-    let name_position = Vec2::new(rect.x + (rect.w - name_dimensions.x) / 2.0, rect.y + rect.h * 0.2);
-    canvas.draw(&name_text, DrawParam::default().dest(name_position).color(Color::WHITE));
+    let name_position = Vec2::new(rect.x + (rect.w - name_dimensions.x) / 2.0, rect.y + rect.h * 0.15);
+    canvas.draw(&name_text, DrawParam::default().dest(name_position).color(theme.text));
 
     // Type
     let mut type_text = Text::new(player_type.display_name());
     type_text.set_scale(screen_height / 20.0);
     let type_dimensions = type_text.measure(context)?;
-    let type_position = Vec2::new(rect.x + (rect.w - type_dimensions.x) / 2.0, rect.y + rect.h * 0.55);
-    canvas.draw(&type_text, DrawParam::default().dest(type_position).color(ui_color_player_type(player_type)));
+    let type_position = Vec2::new(rect.x + (rect.w - type_dimensions.x) / 2.0, rect.y + rect.h * 0.45);
+    canvas.draw(
+        &type_text,
+        DrawParam::default().dest(type_position).color(ui_color_player_type(theme, player_type)),
+    );
+
+    // Profile (humans only)
+    if player_type == PlayerType::Human {
+        let mut profile_text = Text::new(format!("Profile: {}", slot.profile.unwrap_or("Guest")));
+        profile_text.set_scale(screen_height / 35.0);
+        let profile_dimensions = profile_text.measure(context)?;
+        let profile_position = Vec2::new(rect.x + (rect.w - profile_dimensions.x) / 2.0, rect.y + rect.h * 0.8);
+        canvas.draw(&profile_text, DrawParam::default().dest(profile_position).color(theme.text_secondary));
+    }
 
     Ok(())
 }
 
 // Colors helpers for consistent styling
-fn ui_color_box_bg(is_selected: bool) -> Color {
-    if is_selected {
-        Color::from_rgba(60, 60, 90, 255)
-    } else {
-        Color::from_rgba(40, 40, 60, 255)
-    }
+fn ui_color_box_bg(theme: &Theme, is_selected: bool) -> Color {
+    if is_selected { theme.panel_selected } else { theme.panel }
 }
 
-fn ui_color_box_border(is_selected: bool) -> Color {
-    if is_selected {
-        Color::from_rgb(150, 200, 255)
-    } else {
-        Color::from_rgb(100, 100, 150)
-    }
+fn ui_color_box_border(theme: &Theme, is_selected: bool) -> Color {
+    if is_selected { theme.header } else { theme.panel_border }
 }
 
-fn ui_color_player_type(player_type: PlayerType) -> Color {
+fn ui_color_player_type(theme: &Theme, player_type: PlayerType) -> Color {
     match player_type {
-        PlayerType::Human => Color::from_rgb(100, 200, 100),
-        PlayerType::AIEasy => Color::from_rgb(100, 150, 255),
-        PlayerType::AIMedium => Color::from_rgb(255, 200, 100),
-        PlayerType::AIHard => Color::from_rgb(255, 100, 100),
+        PlayerType::Human => theme.human,
+        PlayerType::AIEasy => theme.ai_easy,
+        PlayerType::AIMedium => theme.ai_medium,
+        PlayerType::AIHard => theme.ai_hard,
+        PlayerType::AIDefensive | PlayerType::AIAggressive | PlayerType::AITrickster => theme.accent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hit_test_left_and_right_boxes() {
+        let layout = MenuLayout::from_screen(800.0, 600.0, 2);
+
+        let left_center = Vec2::new(layout.boxes[0].x + layout.boxes[0].w / 2.0, layout.boxes[0].y + layout.boxes[0].h / 2.0);
+        let right_center = Vec2::new(layout.boxes[1].x + layout.boxes[1].w / 2.0, layout.boxes[1].y + layout.boxes[1].h / 2.0);
+
+        assert_eq!(hit_test_player_with_layout(&layout, left_center), Some(1));
+        assert_eq!(hit_test_player_with_layout(&layout, right_center), Some(2));
+        assert_eq!(hit_test_player_with_layout(&layout, Vec2::new(0.0, 0.0)), None);
+
+        let solo = MenuLayout::from_screen(800.0, 600.0, 1);
+        assert_eq!(hit_test_player_with_layout(&solo, right_center), None);
+    }
+
+    #[test]
+    fn four_player_boxes_fit_the_screen() {
+        let layout = MenuLayout::from_screen(800.0, 600.0, 4);
+        assert_eq!(layout.boxes.len(), 4);
+        assert!(layout.boxes.windows(2).all(|pair| pair[0].right() < pair[1].left()));
+        assert!(layout.boxes[3].right() <= 800.0);
+
+        let last_center = Vec2::new(layout.boxes[3].x + 1.0, layout.boxes[3].y + 1.0);
+        assert_eq!(hit_test_player_with_layout(&layout, last_center), Some(4));
     }
 }
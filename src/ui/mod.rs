@@ -1,5 +1,22 @@
+pub mod achievements;
+pub mod arena;
+pub mod campaign;
+pub mod challenge;
 mod common;
+pub mod countdown;
+pub mod crt;
+pub mod editor;
+pub mod effects;
 pub mod game_over;
+pub mod high_scores;
 pub mod hud;
+pub mod leaderboard;
 pub mod menu;
 pub mod pause_screen;
+pub mod powerups;
+pub mod settings;
+mod stats_panel;
+pub mod theme;
+pub mod toast;
+pub mod tournament;
+pub mod transition;
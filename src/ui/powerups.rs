@@ -0,0 +1,59 @@
+use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Mesh, Rect, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::game::mode;
+use crate::game::powerup::{POWER_UP_SIZE, PowerUps};
+use crate::ui::theme::Theme;
+
+// Seconds before a waiting power-up disappears when it starts blinking
+const BLINK_WARNING: f32 = 3.0;
+const BAR_WIDTH: f32 = 160.0;
+const BAR_HEIGHT: f32 = 6.0;
+
+// Power-ups waiting in the field, drawn as rings around their symbol. They blink when about to disappear.
+pub fn draw_field_power_ups(context: &mut Context, canvas: &mut Canvas, theme: &Theme, power_ups: &PowerUps) -> GameResult {
+    for power_up in power_ups.field() {
+        if power_up.remaining < BLINK_WARNING && (power_up.remaining * 4.0).fract() < 0.5 {
+            continue;
+        }
+        let ring = Mesh::new_circle(context, DrawMode::stroke(3.0), power_up.position, POWER_UP_SIZE / 2.0, 0.5, theme.accent)?;
+        canvas.draw(&ring, DrawParam::default());
+        let mut symbol = Text::new(power_up.kind.spec().symbol);
+        symbol.set_scale(POWER_UP_SIZE * 0.6);
+        let dimensions = symbol.measure(context)?;
+        canvas.draw(
+            &symbol,
+            DrawParam::default()
+                .dest(power_up.position - Vec2::new(dimensions.x, dimensions.y) / 2.0)
+                .color(theme.accent),
+        );
+    }
+    Ok(())
+}
+
+// Timed power-ups in effect, each with the time it has left, under the score of its owner's side.
+pub fn draw_active_power_ups(context: &mut Context, canvas: &mut Canvas, theme: &Theme, power_ups: &PowerUps) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let mut rows = [0.0f32; 2];
+    for power_up in power_ups.active() {
+        let side = mode::team(power_up.owner);
+        let x = if side == 0 { screen_width / 4.0 } else { screen_width * 3.0 / 4.0 } - BAR_WIDTH / 2.0;
+        let y = screen_height * 0.16 + rows[side];
+
+        let mut label = Text::new(format!("{}  {}", power_up.owner.name(), power_up.kind.name()));
+        label.set_scale(screen_height / 40.0);
+        let dimensions = label.measure(context)?;
+        canvas.draw(&label, DrawParam::default().dest(Vec2::new(x, y)).color(theme.text_secondary));
+
+        let bar_y = y + dimensions.y + 4.0;
+        let track = Mesh::new_rectangle(context, DrawMode::fill(), Rect::new(x, bar_y, BAR_WIDTH, BAR_HEIGHT), theme.panel)?;
+        canvas.draw(&track, DrawParam::default());
+        let filled = Rect::new(x, bar_y, BAR_WIDTH * power_up.progress(), BAR_HEIGHT);
+        if filled.w > 0.0 {
+            let bar = Mesh::new_rectangle(context, DrawMode::fill(), filled, Color { a: 0.9, ..theme.accent })?;
+            canvas.draw(&bar, DrawParam::default());
+        }
+        rows[side] += dimensions.y + BAR_HEIGHT + 12.0;
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use ggez::graphics::{Canvas, DrawParam, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::ui::theme::Theme;
+
+// One row of the settings screen.
+pub struct SettingsLine<'a> {
+    pub label: &'a str,
+    pub value: String,
+}
+
+pub fn draw_settings(context: &mut Context, canvas: &mut Canvas, theme: &Theme, lines: &[SettingsLine], selected: usize) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+
+    // Title
+    let mut title = Text::new("SETTINGS");
+    title.set_scale(screen_height / 10.0);
+    let title_dimensions = title.measure(context)?;
+    let title_position = Vec2::new((screen_width - title_dimensions.x) / 2.0, screen_height * 0.08);
+    canvas.draw(&title, DrawParam::default().dest(title_position).color(theme.text));
+
+    // Rows shrink to fit between the title and the hints
+    let top = screen_height * 0.26;
+    let line_height = (screen_height * 0.6 / lines.len().max(1) as f32).min(screen_height / 13.0);
+    let text_scale = (line_height * 0.7).min(screen_height / 26.0);
+    for (index, line) in lines.iter().enumerate() {
+        let y = top + line_height * index as f32;
+        let color = if index == selected { theme.accent } else { theme.text_secondary };
+
+        let mut label = Text::new(line.label);
+        label.set_scale(text_scale);
+        canvas.draw(&label, DrawParam::default().dest(Vec2::new(screen_width * 0.25, y)).color(color));
+
+        let value = if index == selected {
+            format!("< {} >", line.value)
+        } else {
+            line.value.clone()
+        };
+        let mut value_text = Text::new(value);
+        value_text.set_scale(text_scale);
+        canvas.draw(&value_text, DrawParam::default().dest(Vec2::new(screen_width * 0.55, y)).color(color));
+    }
+
+    // Hints
+    let mut hint = Text::new("↑ ↓: Select  |  ← →: Change  |  SPACE/ENTER/Esc: Back to menu");
+    hint.set_scale(screen_height / 30.0);
+    let hint_dimensions = hint.measure(context)?;
+    let hint_position = Vec2::new((screen_width - hint_dimensions.x) / 2.0, screen_height * 0.9);
+    canvas.draw(&hint, DrawParam::default().dest(hint_position).color(theme.text_secondary));
+
+    Ok(())
+}
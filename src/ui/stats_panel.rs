@@ -0,0 +1,79 @@
+use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Mesh, Rect, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::game::physics::Player;
+use crate::game::stats::MatchStats;
+use crate::ui::theme::Theme;
+
+// Column positions, as ratios of the screen width
+const LABEL_X_RATIO: f32 = 0.3;
+const PLAYER_1_X_RATIO: f32 = 0.55;
+// Space between player columns: wide for two players, narrower for four
+const PLAYER_COLUMN_RATIO: f32 = 0.12;
+const FOUR_PLAYER_COLUMN_RATIO: f32 = 0.09;
+const PANEL_MARGIN_RATIO: f32 = 0.02;
+
+// Draw the end-of-match statistics table for `players`, starting at `top` (in pixels).
+pub fn draw_stats_panel(context: &mut Context, canvas: &mut Canvas, theme: &Theme, stats: &MatchStats, players: &[Player], top: f32) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let line_height = screen_height / 24.0;
+    let text_scale = screen_height / 32.0;
+
+    let per_player_row = |label, value: &dyn Fn(Player) -> String| (label, players.iter().map(|&player| value(player)).collect::<Vec<_>>());
+    let column_ratio = if players.len() > 2 { FOUR_PLAYER_COLUMN_RATIO } else { PLAYER_COLUMN_RATIO };
+    let column_x = |column: usize| PLAYER_1_X_RATIO + column_ratio * column as f32;
+    let last_column_x = column_x(players.len().saturating_sub(1));
+    let per_player_rows = [
+        per_player_row("Points", &|player| stats.points_won(player).to_string()),
+        per_player_row("Points on serve", &|player| stats.points_won_on_serve(player).to_string()),
+        per_player_row("Racket hits", &|player| stats.hits(player).to_string()),
+        per_player_row("Distance travelled", &|player| format!("{:.0} px", stats.distance_travelled(player))),
+    ];
+    let match_rows = [
+        ("Rallies", stats.rallies().len().to_string()),
+        ("Longest rally", format!("{} hits", stats.longest_rally())),
+        ("Average rally", format!("{:.1} hits", stats.average_rally_length())),
+        ("Fastest ball", format!("{:.0} px/s", stats.max_ball_speed())),
+    ];
+
+    // Panel background
+    let rows = 1 + per_player_rows.len() + match_rows.len();
+    let margin = screen_width * PANEL_MARGIN_RATIO;
+    let panel_rect = Rect::new(
+        screen_width * LABEL_X_RATIO - margin,
+        top - margin,
+        screen_width * (last_column_x - LABEL_X_RATIO + 0.08) + margin * 2.0,
+        line_height * rows as f32 + margin * 2.0,
+    );
+    let panel_mesh = Mesh::new_rectangle(context, DrawMode::fill(), panel_rect, Color { a: 0.8, ..theme.panel })?;
+    canvas.draw(&panel_mesh, DrawParam::default());
+
+    let mut draw_cell = |text: &str, x_ratio: f32, row: usize, color: Color| {
+        let mut cell = Text::new(text);
+        cell.set_scale(text_scale);
+        let position = Vec2::new(screen_width * x_ratio, top + line_height * row as f32);
+        canvas.draw(&cell, DrawParam::default().dest(position).color(color));
+    };
+
+    let header_color = theme.header;
+    let label_color = theme.text_muted;
+    for (column, player) in players.iter().enumerate() {
+        draw_cell(player.name(), column_x(column), 0, header_color);
+    }
+
+    for (index, (label, values)) in per_player_rows.iter().enumerate() {
+        let row = index + 1;
+        draw_cell(label, LABEL_X_RATIO, row, label_color);
+        for (column, value) in values.iter().enumerate() {
+            draw_cell(value, column_x(column), row, theme.text);
+        }
+    }
+
+    for (index, (label, value)) in match_rows.iter().enumerate() {
+        let row = 1 + per_player_rows.len() + index;
+        draw_cell(label, LABEL_X_RATIO, row, label_color);
+        draw_cell(value, PLAYER_1_X_RATIO, row, theme.text);
+    }
+
+    Ok(())
+}
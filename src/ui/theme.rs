@@ -0,0 +1,393 @@
+// Color themes for the playing field and the interface.
+//
+// Four themes are built in. More can be added as JSON files in the `themes` folder of the user data directory:
+//
+// { "name": "Ocean", "base": "classic", "colors": { "background": "#001830", "ball": "#ffd166" } }
+//
+// `base` is the built-in theme the file starts from (classic by default); `colors` overrides any of `Theme::KEYS` and
+// `crt` any of the CRT effect strengths (`CrtParams::KEYS`), e.g. "crt": { "bloom": 1.2 }.
+
+use crate::ui::crt::{self, CrtParams};
+use ggez::graphics::Color;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const THEMES_DIR: &str = "themes";
+pub const DEFAULT_THEME: &str = "classic";
+pub const BUILT_IN_THEMES: [&str; 4] = ["classic", "neon", "high-contrast", "colorblind"];
+
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(io::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io(error) => write!(f, "I/O error: {}", error),
+            ThemeError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<io::Error> for ThemeError {
+    fn from(error: io::Error) -> Self {
+        ThemeError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    // Playing field
+    pub background: Color,
+    pub racket: Color,
+    pub ball: Color,
+    pub net: Color,
+    pub score: Color,
+    // Interface
+    pub text: Color,
+    pub text_secondary: Color,
+    pub text_muted: Color,
+    pub accent: Color,
+    pub header: Color,
+    pub panel: Color,
+    pub panel_selected: Color,
+    pub panel_border: Color,
+    // Drawn over the field behind the pause and game-over screens
+    pub overlay: Color,
+    pub debug: Color,
+    // Player type names in the menu
+    pub human: Color,
+    pub ai_easy: Color,
+    pub ai_medium: Color,
+    pub ai_hard: Color,
+    // Strength of the CRT effects turned on in the settings
+    pub crt: CrtParams,
+}
+
+fn hex(value: &str) -> Color {
+    parse_hex_color(value).unwrap_or(Color::MAGENTA)
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl Theme {
+    // Names used for the colors in theme files and asset pack manifests.
+    pub const KEYS: [&'static str; 19] = [
+        "background",
+        "racket",
+        "ball",
+        "net",
+        "score",
+        "text",
+        "text_secondary",
+        "text_muted",
+        "accent",
+        "header",
+        "panel",
+        "panel_selected",
+        "panel_border",
+        "overlay",
+        "debug",
+        "human",
+        "ai_easy",
+        "ai_medium",
+        "ai_hard",
+    ];
+
+    pub fn classic() -> Self {
+        Self {
+            name: "Classic".to_string(),
+            background: Color::BLACK,
+            racket: Color::WHITE,
+            ball: Color::WHITE,
+            net: Color::from_rgb(127, 127, 127),
+            score: Color::from_rgb(50, 50, 50),
+            text: Color::WHITE,
+            text_secondary: Color::from_rgb(200, 200, 200),
+            text_muted: Color::from_rgb(150, 150, 150),
+            accent: Color::from_rgb(255, 200, 100),
+            header: Color::from_rgb(150, 200, 255),
+            panel: Color::from_rgb(40, 40, 60),
+            panel_selected: Color::from_rgb(60, 60, 90),
+            panel_border: Color::from_rgb(100, 100, 150),
+            overlay: Color::from_rgba(0, 0, 0, 170),
+            debug: Color::from_rgb(0, 255, 0),
+            human: Color::from_rgb(100, 200, 100),
+            ai_easy: Color::from_rgb(100, 150, 255),
+            ai_medium: Color::from_rgb(255, 200, 100),
+            ai_hard: Color::from_rgb(255, 100, 100),
+            crt: CrtParams::default(),
+        }
+    }
+
+    pub fn neon() -> Self {
+        Self {
+            name: "Neon".to_string(),
+            background: hex("#0b0220"),
+            racket: hex("#00f0ff"),
+            ball: hex("#ff2bd6"),
+            net: hex("#3a1f6e"),
+            score: hex("#2a1450"),
+            text: hex("#f5f5ff"),
+            text_secondary: hex("#b8a8ff"),
+            text_muted: hex("#7a6aa8"),
+            accent: hex("#ffe600"),
+            header: hex("#00f0ff"),
+            panel: hex("#1a0b3a"),
+            panel_selected: hex("#2d1466"),
+            panel_border: hex("#6a3cff"),
+            overlay: hex("#050014b4"),
+            debug: hex("#39ff14"),
+            human: hex("#39ff14"),
+            ai_easy: hex("#00f0ff"),
+            ai_medium: hex("#ffe600"),
+            ai_hard: hex("#ff2b6d"),
+            crt: CrtParams {
+                bloom: 1.2,
+                aberration: 2.5,
+                persistence: 0.75,
+                ..CrtParams::default()
+            },
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "High contrast".to_string(),
+            background: Color::BLACK,
+            racket: Color::WHITE,
+            ball: hex("#ffff00"),
+            net: Color::WHITE,
+            score: hex("#5a5a5a"),
+            text: Color::WHITE,
+            text_secondary: Color::WHITE,
+            text_muted: hex("#d0d0d0"),
+            accent: hex("#ffff00"),
+            header: hex("#00ffff"),
+            panel: Color::BLACK,
+            panel_selected: hex("#202020"),
+            panel_border: Color::WHITE,
+            overlay: hex("#000000dc"),
+            debug: hex("#00ff00"),
+            human: hex("#00ff00"),
+            ai_easy: hex("#00ffff"),
+            ai_medium: hex("#ffff00"),
+            ai_hard: hex("#ff5050"),
+            // Nothing that blurs or fringes the picture
+            crt: CrtParams {
+                scanlines: 0.2,
+                bloom: 0.0,
+                aberration: 0.0,
+                ..CrtParams::default()
+            },
+        }
+    }
+
+    // Okabe-Ito palette: the accent and player colors stay distinct with the common forms of color blindness.
+    pub fn colorblind() -> Self {
+        Self {
+            name: "Colorblind-safe".to_string(),
+            background: hex("#101010"),
+            racket: hex("#f0f0f0"),
+            ball: hex("#f0e442"),
+            net: hex("#606060"),
+            score: hex("#303030"),
+            text: Color::WHITE,
+            text_secondary: hex("#c8c8c8"),
+            text_muted: hex("#969696"),
+            accent: hex("#e69f00"),
+            header: hex("#56b4e9"),
+            panel: hex("#1e2a38"),
+            panel_selected: hex("#2c3e52"),
+            panel_border: hex("#0072b2"),
+            overlay: Color::from_rgba(0, 0, 0, 170),
+            debug: hex("#009e73"),
+            human: hex("#009e73"),
+            ai_easy: hex("#56b4e9"),
+            ai_medium: hex("#e69f00"),
+            ai_hard: hex("#d55e00"),
+            // Color fringes would blur the hues the theme keeps apart
+            crt: CrtParams {
+                aberration: 0.0,
+                ..CrtParams::default()
+            },
+        }
+    }
+
+    pub fn built_in(id: &str) -> Option<Self> {
+        match id {
+            "classic" => Some(Self::classic()),
+            "neon" => Some(Self::neon()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "colorblind" => Some(Self::colorblind()),
+            _ => None,
+        }
+    }
+
+    pub fn color_mut(&mut self, key: &str) -> Option<&mut Color> {
+        let color = match key {
+            "background" => &mut self.background,
+            "racket" => &mut self.racket,
+            "ball" => &mut self.ball,
+            "net" => &mut self.net,
+            "score" => &mut self.score,
+            "text" => &mut self.text,
+            "text_secondary" => &mut self.text_secondary,
+            "text_muted" => &mut self.text_muted,
+            "accent" => &mut self.accent,
+            "header" => &mut self.header,
+            "panel" => &mut self.panel,
+            "panel_selected" => &mut self.panel_selected,
+            "panel_border" => &mut self.panel_border,
+            "overlay" => &mut self.overlay,
+            "debug" => &mut self.debug,
+            "human" => &mut self.human,
+            "ai_easy" => &mut self.ai_easy,
+            "ai_medium" => &mut self.ai_medium,
+            "ai_hard" => &mut self.ai_hard,
+            _ => return None,
+        };
+        Some(color)
+    }
+
+    // Replace the colors named in `overrides` (keys not in `KEYS` are ignored).
+    pub fn apply(&mut self, overrides: &[(String, Color)]) {
+        for (key, color) in overrides {
+            if let Some(slot) = self.color_mut(key) {
+                *slot = *color;
+            }
+        }
+    }
+}
+
+// Parse `#RRGGBB` or `#RRGGBBAA`.
+pub fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::from_rgba(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+// Check color overrides read from a file, keyed by `Theme::KEYS`.
+pub fn parse_colors(colors: &BTreeMap<String, String>) -> Result<Vec<(String, Color)>, String> {
+    let mut parsed = Vec::new();
+    for (key, value) in colors {
+        if !Theme::KEYS.contains(&key.as_str()) {
+            return Err(format!("unknown color '{}' (expected one of: {})", key, Theme::KEYS.join(", ")));
+        }
+        let color = parse_hex_color(value).ok_or_else(|| format!("color '{}': expected #RRGGBB or #RRGGBBAA, got '{}'", key, value))?;
+        parsed.push((key.clone(), color));
+    }
+    Ok(parsed)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    name: String,
+    base: Option<String>,
+    #[serde(default)]
+    colors: BTreeMap<String, String>,
+    #[serde(default)]
+    crt: BTreeMap<String, f32>,
+}
+
+pub fn parse_theme_file(json: &[u8]) -> Result<Theme, ThemeError> {
+    let file: ThemeFile = serde_json::from_slice(json).map_err(|error| ThemeError::Invalid(error.to_string()))?;
+    let base = file.base.as_deref().unwrap_or(DEFAULT_THEME);
+    let mut theme =
+        Theme::built_in(base).ok_or_else(|| ThemeError::Invalid(format!("unknown base theme '{}' (expected one of: {})", base, BUILT_IN_THEMES.join(", "))))?;
+    theme.name = file.name;
+    theme.apply(&parse_colors(&file.colors).map_err(ThemeError::Invalid)?);
+    crt::parse_params(&file.crt, &mut theme.crt).map_err(ThemeError::Invalid)?;
+    Ok(theme)
+}
+
+// Built-in theme ids followed by the theme files found in `directory`.
+pub fn available_themes(directory: &Path) -> Vec<String> {
+    let mut files: Vec<String> = fs::read_dir(directory)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json")))
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    BUILT_IN_THEMES.iter().map(|id| id.to_string()).chain(files).collect()
+}
+
+// The built-in theme `id`, or the theme file `id` in `directory`.
+pub fn load_theme(directory: &Path, id: &str) -> Result<Theme, ThemeError> {
+    match Theme::built_in(id) {
+        Some(theme) => Ok(theme),
+        None => parse_theme_file(&fs::read(directory.join(id))?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(parse_hex_color("#ff8000"), Some(Color::from_rgb(255, 128, 0)));
+        assert_eq!(parse_hex_color("#FF800080"), Some(Color::from_rgba(255, 128, 0, 128)));
+        assert_eq!(parse_hex_color("ff8000"), None);
+        assert_eq!(parse_hex_color("#ff80"), None);
+        assert_eq!(parse_hex_color("#gg8000"), None);
+    }
+
+    #[test]
+    fn every_key_names_a_color() {
+        let mut theme = Theme::classic();
+        for key in Theme::KEYS {
+            assert!(theme.color_mut(key).is_some(), "{}", key);
+        }
+        assert!(theme.color_mut("sky").is_none());
+    }
+
+    #[test]
+    fn built_in_themes_exist() {
+        for id in BUILT_IN_THEMES {
+            let theme = Theme::built_in(id).unwrap();
+            // Built-in colors are written as valid hex literals
+            assert!(
+                !Theme::KEYS.iter().any(|key| *theme.clone().color_mut(key).unwrap() == Color::MAGENTA),
+                "{}",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn theme_files_start_from_a_base() {
+        let theme = parse_theme_file(br##"{ "name": "Ocean", "base": "neon", "colors": { "ball": "#ffd166" }, "crt": { "curvature": 0.3 } }"##).unwrap();
+        assert_eq!(theme.name, "Ocean");
+        assert_eq!(theme.ball, Color::from_rgb(255, 209, 102));
+        assert_eq!(theme.racket, Theme::neon().racket);
+        assert_eq!(theme.crt.curvature, 0.3);
+        assert_eq!(theme.crt.bloom, Theme::neon().crt.bloom);
+
+        let error = |json: &str| parse_theme_file(json.as_bytes()).unwrap_err().to_string();
+        assert!(error(r#"{ "name": "A", "base": "sepia" }"#).contains("unknown base theme 'sepia'"));
+        assert!(error(r##"{ "name": "A", "colors": { "sky": "#000000" } }"##).contains("unknown color 'sky'"));
+        assert!(error(r#"{ "name": "A", "colors": { "ball": "red" } }"#).contains("color 'ball'"));
+        assert!(error(r#"{ "name": "A", "crt": { "bloom": -1 } }"#).contains("CRT parameter 'bloom'"));
+    }
+}
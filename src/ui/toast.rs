@@ -0,0 +1,95 @@
+use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Mesh, Rect, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+use std::collections::VecDeque;
+
+use crate::ui::theme::Theme;
+
+const TOAST_DURATION: f32 = 3.5;
+const TOAST_FADE: f32 = 0.5;
+// Toasts beyond this wait for a free spot
+const MAX_VISIBLE: usize = 3;
+
+struct Toast {
+    title: String,
+    message: String,
+    remaining: f32,
+}
+
+// Short notifications shown in the top-right corner (e.g. achievement unlocks).
+#[derive(Default)]
+pub struct Toasts {
+    queue: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, title: &str, message: &str) {
+        self.queue.push_back(Toast {
+            title: title.to_string(),
+            message: message.to_string(),
+            remaining: TOAST_DURATION,
+        });
+    }
+
+    // Like `push`, but updates a toast with the same title that is still shown instead of queueing another one.
+    pub fn replace(&mut self, title: &str, message: &str) {
+        match self.queue.iter_mut().find(|toast| toast.title == title) {
+            Some(toast) => {
+                toast.message = message.to_string();
+                toast.remaining = TOAST_DURATION;
+            }
+            None => self.push(title, message),
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        for toast in self.queue.iter_mut().take(MAX_VISIBLE) {
+            toast.remaining -= delta_time;
+        }
+        self.queue.retain(|toast| toast.remaining > 0.0);
+    }
+
+    pub fn draw(&self, context: &mut Context, canvas: &mut Canvas, theme: &Theme) -> GameResult {
+        let (screen_width, screen_height) = context.gfx.drawable_size();
+        let width = screen_width * 0.28;
+        let height = screen_height * 0.09;
+        let margin = screen_height * 0.02;
+
+        for (index, toast) in self.queue.iter().take(MAX_VISIBLE).enumerate() {
+            let alpha = (toast.remaining / TOAST_FADE).min(1.0);
+            let rect = Rect::new(screen_width - width - margin, margin + (height + margin) * index as f32, width, height);
+
+            let background = Mesh::new_rectangle(context, DrawMode::fill(), rect, faded(theme.panel_selected, 0.9 * alpha))?;
+            canvas.draw(&background, DrawParam::default());
+            let border = Mesh::new_rectangle(context, DrawMode::stroke(3.0), rect, faded(theme.accent, alpha))?;
+            canvas.draw(&border, DrawParam::default());
+
+            let mut title = Text::new(toast.title.as_str());
+            title.set_scale(height * 0.3);
+            canvas.draw(
+                &title,
+                DrawParam::default()
+                    .dest(Vec2::new(rect.x + margin, rect.y + height * 0.12))
+                    .color(faded(theme.accent, alpha)),
+            );
+
+            let mut message = Text::new(toast.message.as_str());
+            message.set_scale(height * 0.26);
+            canvas.draw(
+                &message,
+                DrawParam::default()
+                    .dest(Vec2::new(rect.x + margin, rect.y + height * 0.55))
+                    .color(faded(theme.text, alpha)),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn faded(color: Color, alpha: f32) -> Color {
+    Color { a: color.a * alpha, ..color }
+}
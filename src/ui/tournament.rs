@@ -0,0 +1,197 @@
+use ggez::graphics::{Canvas, Color, DrawParam, Text};
+use ggez::{Context, GameResult, glam::Vec2};
+
+use crate::career::store::MAX_NAME_LENGTH;
+use crate::player::player_type::PlayerType;
+use crate::tournament::bracket::{Seat, Stage};
+use crate::tournament::{MAX_ENTRANTS, MIN_ENTRANTS, Tournament, TournamentSetup};
+use crate::ui::theme::Theme;
+
+// Entrants shown in each column of the setup screen
+const SETUP_COLUMN_ROWS: usize = 8;
+
+fn draw_title(context: &mut Context, canvas: &mut Canvas, theme: &Theme, title: &str, subtitle: &str) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let mut title = Text::new(title);
+    title.set_scale(screen_height / 14.0);
+    let title_dimensions = title.measure(context)?;
+    canvas.draw(
+        &title,
+        DrawParam::default()
+            .dest(Vec2::new((screen_width - title_dimensions.x) / 2.0, screen_height * 0.05))
+            .color(theme.text),
+    );
+
+    let mut subtitle = Text::new(subtitle);
+    subtitle.set_scale(screen_height / 32.0);
+    let subtitle_dimensions = subtitle.measure(context)?;
+    canvas.draw(
+        &subtitle,
+        DrawParam::default()
+            .dest(Vec2::new((screen_width - subtitle_dimensions.x) / 2.0, screen_height * 0.14))
+            .color(theme.text_secondary),
+    );
+    Ok(())
+}
+
+fn draw_hint(context: &mut Context, canvas: &mut Canvas, theme: &Theme, hint: &str) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let mut hint = Text::new(hint);
+    hint.set_scale(screen_height / 40.0);
+    let hint_dimensions = hint.measure(context)?;
+    canvas.draw(
+        &hint,
+        DrawParam::default()
+            .dest(Vec2::new((screen_width - hint_dimensions.x) / 2.0, screen_height * 0.92))
+            .color(theme.text_secondary),
+    );
+    Ok(())
+}
+
+// The entrants entered so far and the line where the next one is typed, with what ENTER will do.
+pub fn draw_tournament_setup(context: &mut Context, canvas: &mut Canvas, theme: &Theme, setup: &TournamentSetup, winning_score: u8) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let subtitle = format!(
+        "< {} >, first to {}  -  {} to {} entrants",
+        setup.format.name(),
+        winning_score,
+        MIN_ENTRANTS,
+        MAX_ENTRANTS
+    );
+    draw_title(context, canvas, theme, "TOURNAMENT SETUP", &subtitle)?;
+
+    // The next entrant
+    let mut entry = Text::new(format!("> {}_   [{}]", setup.name, setup.player_type.display_name()));
+    entry.set_scale(screen_height / 24.0);
+    canvas.draw(
+        &entry,
+        DrawParam::default()
+            .dest(Vec2::new(screen_width * 0.15, screen_height * 0.24))
+            .color(theme.header),
+    );
+
+    let top = screen_height * 0.34;
+    let line_height = screen_height / 20.0;
+    for (index, entrant) in setup.entrants.iter().enumerate() {
+        let x = screen_width * (0.15 + 0.38 * (index / SETUP_COLUMN_ROWS) as f32);
+        let y = top + line_height * (index % SETUP_COLUMN_ROWS) as f32;
+        let mut line = Text::new(format!(
+            "{:>2}. {:<width$} {}",
+            index + 1,
+            entrant.name,
+            entrant.player_type.display_name(),
+            width = MAX_NAME_LENGTH
+        ));
+        line.set_scale(screen_height / 32.0);
+        let color = if entrant.player_type == PlayerType::Human {
+            theme.text
+        } else {
+            theme.text_muted
+        };
+        canvas.draw(&line, DrawParam::default().dest(Vec2::new(x, y)).color(color));
+    }
+
+    let enter = if !setup.name.trim().is_empty() {
+        format!("ENTER: Add {}", setup.name.trim())
+    } else if setup.player_type != PlayerType::Human {
+        format!("ENTER: Add {}", setup.player_type.display_name())
+    } else {
+        format!("ENTER: Start with {} entrants", setup.entrants.len())
+    };
+    draw_hint(
+        context,
+        canvas,
+        theme,
+        &format!("Type a name  |  ↑ ↓: Player type  |  ← →: Format  |  {}  |  Delete: Remove last", enter),
+    )
+}
+
+// The bracket between matches: winners' rounds on top with the finals, the losers' bracket below, each match with
+// its winner highlighted and the next one to play marked.
+pub fn draw_tournament(context: &mut Context, canvas: &mut Canvas, theme: &Theme, tournament: &Tournament) -> GameResult {
+    let (screen_width, screen_height) = context.gfx.drawable_size();
+    let title = match tournament.champion() {
+        Some(champion) => format!("{} WINS THE TOURNAMENT!", champion.name.to_uppercase()),
+        None => "TOURNAMENT".to_string(),
+    };
+    let subtitle = format!(
+        "{}, first to {}, {} entrants",
+        tournament.format.name(),
+        tournament.winning_score,
+        tournament.entrants.len()
+    );
+    draw_title(context, canvas, theme, &title, &subtitle)?;
+
+    // Columns of matches: one per round, the finals after the winners' bracket
+    let matches = tournament.bracket.matches();
+    let mut upper: Vec<(String, Vec<usize>)> = Vec::new();
+    let mut lower: Vec<(String, Vec<usize>)> = Vec::new();
+    for (index, bracket_match) in matches.iter().enumerate() {
+        let (columns, header) = match bracket_match.stage {
+            Stage::Winners => (&mut upper, format!("Round {}", bracket_match.round)),
+            Stage::Losers => (&mut lower, format!("Losers {}", bracket_match.round)),
+            Stage::GrandFinal | Stage::Reset => (&mut upper, "Finals".to_string()),
+        };
+        match columns.last_mut() {
+            Some((last, column)) if *last == header => column.push(index),
+            _ => columns.push((header, vec![index])),
+        }
+    }
+
+    let next = tournament.bracket.next_match();
+    let top = screen_height * 0.21;
+    let bottom = screen_height * 0.88;
+    let split = if lower.is_empty() { bottom } else { top + (bottom - top) * 0.58 };
+    for (columns, section_top, section_bottom) in [(&upper, top, split), (&lower, split, bottom)] {
+        let Some(tallest) = columns.iter().map(|(_, column)| column.len()).max() else {
+            continue;
+        };
+        let column_width = screen_width * 0.94 / columns.len() as f32;
+        let header_height = screen_height / 30.0;
+        let slot_height = (section_bottom - section_top - header_height) / tallest as f32;
+        let scale = (slot_height / 2.6).min(column_width / (MAX_NAME_LENGTH as f32 * 0.7)).min(screen_height / 32.0);
+        for (column_index, (header, column)) in columns.iter().enumerate() {
+            let x = screen_width * 0.03 + column_width * column_index as f32;
+            let mut header_text = Text::new(header.as_str());
+            header_text.set_scale(screen_height / 40.0);
+            canvas.draw(&header_text, DrawParam::default().dest(Vec2::new(x, section_top)).color(theme.text_secondary));
+
+            // Spread the matches of a round over the whole section, as a bracket is drawn
+            let spacing = (section_bottom - section_top - header_height) / column.len() as f32;
+            for (row, &index) in column.iter().enumerate() {
+                if !tournament.bracket.is_shown(index) {
+                    continue;
+                }
+                let y = section_top + header_height + spacing * (row as f32 + 0.5) - scale;
+                let seats = tournament.bracket.seats(index);
+                for (side, &seat) in seats.iter().enumerate() {
+                    let color = side_color(theme, matches[index].winner, side, seat, next == Some(index));
+                    let marker = if next == Some(index) { ">" } else { " " };
+                    let mut name = Text::new(format!("{}{}", marker, tournament.seat_name(seat)));
+                    name.set_scale(scale);
+                    canvas.draw(&name, DrawParam::default().dest(Vec2::new(x, y + scale * 1.1 * side as f32)).color(color));
+                }
+            }
+        }
+    }
+
+    let hint = match tournament.next_match() {
+        None => "SPACE/ENTER: New tournament  |  Esc: Menu".to_string(),
+        Some((_, [left, right])) if left.player_type != PlayerType::Human && right.player_type != PlayerType::Human => {
+            format!("SPACE/ENTER: Watch {} vs {}  |  S: Skip  |  X: Abandon  |  Esc: Menu", left.name, right.name)
+        }
+        Some((_, [left, right])) => format!("SPACE/ENTER: Play {} vs {}  |  X: Abandon  |  Esc: Menu", left.name, right.name),
+    };
+    draw_hint(context, canvas, theme, &hint)
+}
+
+// Winners stand out and losers fade; the match about to be played is in the header color.
+fn side_color(theme: &Theme, winner: Option<usize>, side: usize, seat: Option<Seat>, is_next: bool) -> Color {
+    match (winner, seat) {
+        (_, Some(Seat::Bye)) | (_, None) => theme.text_muted,
+        _ if is_next => theme.header,
+        (Some(winner), _) if winner == side => theme.accent,
+        (Some(_), _) => theme.text_muted,
+        (None, _) => theme.text,
+    }
+}
@@ -0,0 +1,110 @@
+// Animated changes between screens: the new screen fades in from the background color or slides in from a side.
+// Transitions are purely visual; the new screen takes input as soon as it is shown.
+
+use ggez::graphics::{Canvas, Color, DrawMode, DrawParam, Mesh, Rect};
+use ggez::{Context, GameResult};
+
+const FADE_DURATION: f32 = 0.35;
+const SLIDE_DURATION: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionKind {
+    Fade,
+    // The new screen comes in from the right, pushing the old one out to the left
+    SlideLeft,
+    // The new screen comes in from the left
+    SlideRight,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    kind: TransitionKind,
+    elapsed: f32,
+}
+
+impl Transition {
+    pub fn new(kind: TransitionKind) -> Self {
+        Self { kind, elapsed: 0.0 }
+    }
+
+    fn duration(&self) -> f32 {
+        match self.kind {
+            TransitionKind::Fade => FADE_DURATION,
+            TransitionKind::SlideLeft | TransitionKind::SlideRight => SLIDE_DURATION,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.elapsed += delta_time;
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration()
+    }
+
+    // From 0 to 1, fast at first and settling at the end (cubic ease-out).
+    pub fn progress(&self) -> f32 {
+        let linear = (self.elapsed / self.duration()).clamp(0.0, 1.0);
+        1.0 - (1.0 - linear).powi(3)
+    }
+
+    // Horizontal offset of the new screen, for a screen `width` wide.
+    pub fn offset(&self, width: f32) -> f32 {
+        let remaining = 1.0 - self.progress();
+        match self.kind {
+            TransitionKind::Fade => 0.0,
+            TransitionKind::SlideLeft => width * remaining,
+            TransitionKind::SlideRight => -width * remaining,
+        }
+    }
+
+    // Opacity of the background-colored veil drawn over the new screen.
+    pub fn veil(&self) -> f32 {
+        match self.kind {
+            TransitionKind::Fade => 1.0 - self.progress(),
+            TransitionKind::SlideLeft | TransitionKind::SlideRight => 0.0,
+        }
+    }
+
+    pub fn draw_veil(&self, context: &mut Context, canvas: &mut Canvas, color: Color) -> GameResult {
+        let alpha = self.veil();
+        if alpha <= 0.0 {
+            return Ok(());
+        }
+        let (screen_width, screen_height) = context.gfx.drawable_size();
+        let rect = Rect::new(0.0, 0.0, screen_width, screen_height);
+        let veil = Mesh::new_rectangle(context, DrawMode::fill(), rect, Color { a: alpha, ..color })?;
+        canvas.draw(&veil, DrawParam::default());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fade_clears_the_veil() {
+        let mut transition = Transition::new(TransitionKind::Fade);
+        assert_eq!(transition.veil(), 1.0);
+        assert_eq!(transition.offset(800.0), 0.0);
+        transition.update(FADE_DURATION / 2.0);
+        assert!(transition.veil() > 0.0 && transition.veil() < 0.5);
+        transition.update(FADE_DURATION);
+        assert!(transition.is_done());
+        assert_eq!(transition.veil(), 0.0);
+    }
+
+    #[test]
+    fn slides_come_in_from_their_side() {
+        let mut left = Transition::new(TransitionKind::SlideLeft);
+        let mut right = Transition::new(TransitionKind::SlideRight);
+        assert_eq!(left.offset(800.0), 800.0);
+        assert_eq!(right.offset(800.0), -800.0);
+        left.update(SLIDE_DURATION);
+        right.update(SLIDE_DURATION);
+        assert_eq!(left.offset(800.0), 0.0);
+        assert_eq!(right.offset(800.0), 0.0);
+        assert_eq!(left.veil(), 0.0);
+    }
+}